@@ -11,16 +11,20 @@ use std::sync::{
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use chrono::{Datelike, Local, Utc};
+use chrono::{Datelike, Local, NaiveDate, Utc};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde_json::Value;
-use sysinfo::System;
+use sysinfo::{Pid, Signal, System};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::file_tail::read_tail_non_empty_lines_resilient;
 use crate::models::{
-    CodexAgentEvent, CodexAgentEventType, CodexMonitorSession, CodexMonitorSnapshot,
-    CodexMonitorState,
+    CodexAgentEvent, CodexAgentEventType, CodexModelUsage, CodexMonitorSession,
+    CodexMonitorSnapshot, CodexMonitorState, CodexSessionFilter, CodexSessionLine,
+    CodexSessionOpenResult, Project,
 };
+use crate::storage;
+use crate::system;
 
 const CODEX_SESSIONS_DIR: &str = ".codex/sessions";
 const MAX_TAIL_LINES: usize = 2000;
@@ -36,14 +40,19 @@ const RECENT_FILE_WINDOW_MS: i64 = 5 * 60_000;
 const WATCH_DEBOUNCE_MS: u64 = 350;
 const PROCESS_POLL_INTERVAL_MS: u64 = 3_000;
 const CANDIDATE_DAYS: usize = 2;
+const SESSION_LINE_PREVIEW_CHARS: usize = 200;
 
 pub const CODEX_MONITOR_SNAPSHOT_EVENT: &str = "codex-monitor-snapshot";
 pub const CODEX_MONITOR_AGENT_EVENT: &str = "codex-monitor-agent-event";
+pub const CODEX_SESSION_FOCUS_PROJECT_EVENT: &str = "codex-session-focus-project";
+pub const CODEX_SESSION_LINE_EVENT: &str = "codex-session-line";
 
 type SessionCache = HashMap<PathBuf, CachedSession>;
 
 static CODEX_MONITOR_RUNTIME: OnceLock<Mutex<MonitorRuntime>> = OnceLock::new();
 static CODEX_MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+static CODEX_SESSION_WATCHERS: OnceLock<Mutex<HashMap<String, RecommendedWatcher>>> =
+    OnceLock::new();
 
 #[derive(Clone)]
 struct CachedSession {
@@ -58,6 +67,7 @@ struct MonitorRuntime {
     previous_states: HashMap<String, CodexMonitorState>,
     previous_process_running: bool,
     has_bootstrapped: bool,
+    has_emitted_events: bool,
 }
 
 #[derive(Default)]
@@ -83,11 +93,7 @@ struct SessionMeta {
 }
 
 pub fn ensure_monitoring_started(app: &AppHandle) -> Result<(), String> {
-    let base_dir = app
-        .path()
-        .home_dir()
-        .map_err(|err| format!("无法获取用户目录: {err}"))?
-        .join(CODEX_SESSIONS_DIR);
+    let base_dir = resolve_sessions_dir(app)?;
 
     if !base_dir.exists() {
         return Ok(());
@@ -131,10 +137,397 @@ pub fn ensure_monitoring_started(app: &AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// 解析 Codex 会话目录：设置项 `codex_sessions_dir` 优先，其次 `$CODEX_HOME/sessions`，默认 `~/.codex/sessions`。
+///
+/// 文件监听在首次启动时绑定目录，修改设置后需重启应用才会切换监听；轮询刷新会立即使用新目录。
+pub fn resolve_sessions_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let home = app
+        .path()
+        .home_dir()
+        .map_err(|err| format!("无法获取用户目录: {err}"))?;
+    let configured = storage::load_app_state(app)
+        .ok()
+        .and_then(|state| state.settings.codex_sessions_dir);
+    Ok(sessions_dir_from(
+        configured.as_deref(),
+        std::env::var_os("CODEX_HOME"),
+        &home,
+    ))
+}
+
+fn sessions_dir_from(
+    configured: Option<&str>,
+    codex_home: Option<std::ffi::OsString>,
+    home: &Path,
+) -> PathBuf {
+    if let Some(configured) = configured.map(str::trim).filter(|value| !value.is_empty()) {
+        return match configured.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None if configured == "~" => home.to_path_buf(),
+            None => PathBuf::from(configured),
+        };
+    }
+    if let Some(codex_home) = codex_home.filter(|value| !value.is_empty()) {
+        return PathBuf::from(codex_home).join("sessions");
+    }
+    home.join(CODEX_SESSIONS_DIR)
+}
+
 pub fn get_snapshot(app: &AppHandle) -> Result<CodexMonitorSnapshot, String> {
     refresh_monitoring(app, false).map(|(snapshot, _)| snapshot)
 }
 
+/// 获取按模型/推理强度筛选后的快照；`model_usage` 仍统计筛选前的全部会话。
+pub fn get_filtered_snapshot(
+    app: &AppHandle,
+    filter: &CodexSessionFilter,
+) -> Result<CodexMonitorSnapshot, String> {
+    let mut snapshot = get_snapshot(app)?;
+    snapshot
+        .sessions
+        .retain(|session| session_matches_filter(session, filter));
+    Ok(snapshot)
+}
+
+fn session_matches_filter(session: &CodexMonitorSession, filter: &CodexSessionFilter) -> bool {
+    let matches = |expected: &Option<String>, actual: &Option<String>| match expected
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        Some(expected) => actual
+            .as_deref()
+            .is_some_and(|actual| actual.trim().eq_ignore_ascii_case(expected)),
+        None => true,
+    };
+    matches(&filter.model, &session.model) && matches(&filter.effort, &session.effort)
+}
+
+// 按 (model, effort) 统计会话数量，数量多的在前，相同数量按模型、推理强度排序。
+fn summarize_model_usage(sessions: &[CodexMonitorSession]) -> Vec<CodexModelUsage> {
+    let mut counts: HashMap<(Option<String>, Option<String>), usize> = HashMap::new();
+    for session in sessions {
+        *counts
+            .entry((session.model.clone(), session.effort.clone()))
+            .or_default() += 1;
+    }
+    let mut usage: Vec<CodexModelUsage> = counts
+        .into_iter()
+        .map(|((model, effort), count)| CodexModelUsage {
+            model,
+            effort,
+            count,
+        })
+        .collect();
+    usage.sort_by(|left, right| {
+        right
+            .count
+            .cmp(&left.count)
+            .then_with(|| left.model.cmp(&right.model))
+            .then_with(|| left.effort.cmp(&right.effort))
+    });
+    usage
+}
+
+/// 打开 Codex 会话的工作目录：匹配到已知项目（含 worktree）时广播 `codex-session-focus-project`
+/// 由前端聚焦该项目，否则在文件管理器中打开该目录。
+pub fn open_session_project(
+    app: &AppHandle,
+    session_id: &str,
+) -> Result<CodexSessionOpenResult, String> {
+    let snapshot = get_snapshot(app)?;
+    let session = snapshot
+        .sessions
+        .into_iter()
+        .find(|session| session.id == session_id)
+        .ok_or_else(|| "Codex 会话不存在".to_string())?;
+    let cwd = session.cwd.trim().to_string();
+    if cwd.is_empty() {
+        return Err("会话没有记录工作目录".to_string());
+    }
+
+    let projects = storage::load_projects(app)?;
+    let matched = match_project_for_cwd(&projects, &cwd);
+    let result = CodexSessionOpenResult {
+        session_id: session.id,
+        project_id: matched.map(|(project, _)| project.id.clone()),
+        project_path: matched.map(|(_, path)| path.to_string()),
+        cwd,
+    };
+
+    if result.project_id.is_some() {
+        if let Err(error) = app.emit(CODEX_SESSION_FOCUS_PROJECT_EVENT, result.clone()) {
+            log::warn!("发送 codex-session-focus-project 失败: {}", error);
+        }
+    } else {
+        system::open_in_finder(&result.cwd, false)?;
+    }
+    Ok(result)
+}
+
+/// 向打开该会话 rollout 文件的 codex 进程发送 SIGTERM；confirm 必须为 true，返回是否找到并通知了进程。
+pub fn kill_session(app: &AppHandle, session_id: &str, confirm: bool) -> Result<bool, String> {
+    if !confirm {
+        return Err("结束 Codex 进程需要确认".to_string());
+    }
+    if !(cfg!(target_os = "macos") || cfg!(target_os = "linux")) {
+        return Err("当前平台不支持结束 Codex 进程".to_string());
+    }
+
+    get_snapshot(app)?;
+    let rollout_path =
+        find_rollout_path(session_id)?.ok_or_else(|| "Codex 会话不存在".to_string())?;
+
+    let args = ["-n", "-P", "-F", "pc", "--"];
+    let output = run_lsof_output_with_fallback(&args, &rollout_path)
+        .ok_or_else(|| "无法查询 Codex 进程（lsof 不可用）".to_string())?;
+    let pids = lsof_stdout_codex_pids(&String::from_utf8_lossy(&output.stdout));
+    if pids.is_empty() {
+        return Ok(false);
+    }
+
+    let mut system = System::new();
+    system.refresh_processes();
+    let mut signaled = false;
+    for pid in pids {
+        let Some(process) = system.process(Pid::from_u32(pid)) else {
+            continue;
+        };
+        if process.kill_with(Signal::Term).unwrap_or(false) {
+            log::info!(
+                "已向 Codex 进程发送 SIGTERM pid={} session={}",
+                pid,
+                session_id
+            );
+            signaled = true;
+        }
+    }
+    Ok(signaled)
+}
+
+// 在监控缓存中查找会话对应的 rollout 文件路径。
+fn find_rollout_path(session_id: &str) -> Result<Option<PathBuf>, String> {
+    let runtime = CODEX_MONITOR_RUNTIME.get_or_init(|| Mutex::new(MonitorRuntime::default()));
+    let runtime = runtime
+        .lock()
+        .map_err(|_| "Codex 监控状态锁异常".to_string())?;
+    Ok(runtime
+        .cache
+        .iter()
+        .find(|(_, cached)| cached.session.id == session_id)
+        .map(|(path, _)| path.clone()))
+}
+
+/// 实时跟随单个 Codex 会话：监听其 rollout 文件，新追加的每一行解析后以 `codex-session-line` 事件推送。
+///
+/// 只推送开始监听之后追加的内容；重复调用同一会话不会重复监听。
+pub fn watch_session(app: &AppHandle, session_id: &str) -> Result<(), String> {
+    let watchers = CODEX_SESSION_WATCHERS.get_or_init(|| Mutex::new(HashMap::new()));
+    if watchers
+        .lock()
+        .map_err(|_| "Codex 会话监听状态锁异常".to_string())?
+        .contains_key(session_id)
+    {
+        return Ok(());
+    }
+
+    get_snapshot(app)?;
+    let rollout_path =
+        find_rollout_path(session_id)?.ok_or_else(|| "Codex 会话不存在".to_string())?;
+    let watch_dir = rollout_path
+        .parent()
+        .ok_or_else(|| "无法定位会话目录".to_string())?
+        .to_path_buf();
+    let offset = fs::metadata(&rollout_path)
+        .map_err(|err| format!("读取文件元信息失败: {err}"))?
+        .len();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = RecommendedWatcher::new(tx, notify::Config::default())
+        .map_err(|err| format!("创建会话监听失败: {err}"))?;
+    watcher
+        .watch(&watch_dir, RecursiveMode::NonRecursive)
+        .map_err(|err| format!("监听会话文件失败: {err}"))?;
+
+    let mut watchers = watchers
+        .lock()
+        .map_err(|_| "Codex 会话监听状态锁异常".to_string())?;
+    if watchers.contains_key(session_id) {
+        return Ok(());
+    }
+    watchers.insert(session_id.to_string(), watcher);
+    drop(watchers);
+
+    let app_handle = app.clone();
+    let session_id = session_id.to_string();
+    thread::spawn(move || follow_session_file(app_handle, session_id, rollout_path, offset, rx));
+    Ok(())
+}
+
+/// 停止跟随 Codex 会话，返回此前是否在监听。
+pub fn stop_watching_session(session_id: &str) -> Result<bool, String> {
+    let Some(watchers) = CODEX_SESSION_WATCHERS.get() else {
+        return Ok(false);
+    };
+    // 移除 watcher 会关闭事件通道，跟随线程随之退出。
+    let removed = watchers
+        .lock()
+        .map_err(|_| "Codex 会话监听状态锁异常".to_string())?
+        .remove(session_id);
+    Ok(removed.is_some())
+}
+
+// 从 offset 开始读取 rollout 文件的新增内容，按完整行解析并推送；不完整的尾行留到下次。
+fn follow_session_file(
+    app: AppHandle,
+    session_id: String,
+    path: PathBuf,
+    mut offset: u64,
+    rx: Receiver<Result<notify::Event, notify::Error>>,
+) {
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        match rx.recv() {
+            Ok(Ok(event)) => {
+                if !event.paths.iter().any(|changed| changed == &path) {
+                    continue;
+                }
+            }
+            Ok(Err(error)) => {
+                log::warn!("Codex 会话监听错误: {}", error);
+                continue;
+            }
+            Err(_) => break,
+        }
+
+        let lines = match read_appended_lines(&path, &mut offset, &mut pending) {
+            Ok(lines) => lines,
+            Err(error) => {
+                log::warn!("读取 Codex 会话新增内容失败: {}", error);
+                continue;
+            }
+        };
+        for line in lines {
+            let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            let Some(payload) = build_session_line(&session_id, &value) else {
+                continue;
+            };
+            if let Err(error) = app.emit(CODEX_SESSION_LINE_EVENT, payload) {
+                log::warn!("发送 codex-session-line 失败: {}", error);
+            }
+        }
+    }
+}
+
+// 读取 offset 之后追加的字节，返回其中完整的非空行；文件被截断时从头开始读取。
+fn read_appended_lines(
+    path: &Path,
+    offset: &mut u64,
+    pending: &mut Vec<u8>,
+) -> Result<Vec<String>, String> {
+    let mut file = File::open(path).map_err(|err| format!("读取会话文件失败: {err}"))?;
+    let size = file
+        .metadata()
+        .map_err(|err| format!("读取文件元信息失败: {err}"))?
+        .len();
+    if size < *offset {
+        *offset = 0;
+        pending.clear();
+    }
+    if size == *offset {
+        return Ok(Vec::new());
+    }
+
+    file.seek(SeekFrom::Start(*offset))
+        .map_err(|err| format!("定位会话文件失败: {err}"))?;
+    let read = file
+        .take(size - *offset)
+        .read_to_end(pending)
+        .map_err(|err| format!("读取会话文件失败: {err}"))?;
+    *offset += read as u64;
+
+    let Some(last_newline) = pending.iter().rposition(|byte| *byte == b'\n') else {
+        return Ok(Vec::new());
+    };
+    let complete: Vec<u8> = pending.drain(..=last_newline).collect();
+    Ok(String::from_utf8_lossy(&complete)
+        .split('\n')
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && line.len() <= MAX_JSON_LINE_BYTES)
+        .map(str::to_string)
+        .collect())
+}
+
+// 将一条 rollout 记录转为推送给前端的摘要，复用监控的消息预览提取逻辑。
+fn build_session_line(session_id: &str, value: &Value) -> Option<CodexSessionLine> {
+    let entry_type = value
+        .get("type")
+        .and_then(|item| item.as_str())?
+        .to_string();
+    let payload = value.get("payload");
+    let timestamp = value
+        .get("timestamp")
+        .and_then(parse_timestamp)
+        .or_else(|| {
+            payload
+                .and_then(|item| item.get("timestamp"))
+                .and_then(parse_timestamp)
+        })
+        .unwrap_or(0);
+    let item_type = payload
+        .and_then(|item| item.get("type"))
+        .and_then(|item| item.as_str())
+        .map(str::to_string);
+    let role = payload
+        .and_then(|item| item.get("role"))
+        .and_then(|item| item.as_str())
+        .map(str::to_string);
+    let preview = payload
+        .and_then(|item| match item_type.as_deref() {
+            Some("function_call") => build_function_call_details(item),
+            Some("user_message") => extract_user_message_text(item),
+            _ => extract_message_preview(item),
+        })
+        .map(|text| truncate_text(&text, SESSION_LINE_PREVIEW_CHARS));
+
+    Some(CodexSessionLine {
+        session_id: session_id.to_string(),
+        timestamp,
+        entry_type,
+        item_type,
+        role,
+        preview,
+    })
+}
+
+// 按最长路径前缀匹配 cwd 所属的项目或 worktree，返回项目及命中的路径。
+fn match_project_for_cwd<'a>(projects: &'a [Project], cwd: &str) -> Option<(&'a Project, &'a str)> {
+    let cwd = cwd.trim_end_matches('/');
+    let contains = |root: &str| {
+        let root = root.trim_end_matches('/');
+        !root.is_empty()
+            && (cwd == root
+                || cwd
+                    .strip_prefix(root)
+                    .is_some_and(|rest| rest.starts_with('/')))
+    };
+
+    projects
+        .iter()
+        .flat_map(|project| {
+            std::iter::once((project, project.path.as_str())).chain(
+                project
+                    .worktrees
+                    .iter()
+                    .map(move |worktree| (project, worktree.path.as_str())),
+            )
+        })
+        .filter(|(_, path)| contains(path))
+        .max_by_key(|(_, path)| path.trim_end_matches('/').len())
+}
+
 fn watch_loop(rx: Receiver<Result<notify::Event, notify::Error>>, app: AppHandle) {
     let mut pending = false;
     let mut last_emit = std::time::Instant::now()
@@ -201,11 +594,7 @@ fn refresh_monitoring(
     app: &AppHandle,
     emit_events: bool,
 ) -> Result<(CodexMonitorSnapshot, Vec<CodexAgentEvent>), String> {
-    let base_dir = app
-        .path()
-        .home_dir()
-        .map_err(|err| format!("无法获取用户目录: {err}"))?
-        .join(CODEX_SESSIONS_DIR);
+    let base_dir = resolve_sessions_dir(app)?;
 
     let now_ms = Utc::now().timestamp_millis();
     let mut seen = HashSet::new();
@@ -298,22 +687,35 @@ fn refresh_monitoring(
 
     sessions.sort_by(|left, right| right.last_activity_at.cmp(&left.last_activity_at));
 
+    let model_usage = summarize_model_usage(&sessions);
     let snapshot = CodexMonitorSnapshot {
         sessions,
         is_codex_running: process_running,
         updated_at: now_ms,
+        model_usage,
     };
 
-    let events = build_monitor_events(&mut runtime, &snapshot, now_ms, emit_events);
+    let synthesize_current =
+        emit_events && !runtime.has_emitted_events && bootstrap_events_enabled(app);
+    let events = build_monitor_events(
+        &mut runtime,
+        &snapshot,
+        now_ms,
+        emit_events,
+        synthesize_current,
+    );
 
     Ok((snapshot, events))
 }
 
+// synthesize_current=true 时（仅首次推送事件且开启设置），为仍处于活跃/待处理/错误状态的会话
+// 补发一条当前状态事件，避免中途打开监控时事件日志为空；其余情况只在状态变化时产生事件。
 fn build_monitor_events(
     runtime: &mut MonitorRuntime,
     snapshot: &CodexMonitorSnapshot,
     timestamp: i64,
     emit_events: bool,
+    synthesize_current: bool,
 ) -> Vec<CodexAgentEvent> {
     let mut events = Vec::new();
 
@@ -339,12 +741,19 @@ fn build_monitor_events(
     for session in &snapshot.sessions {
         next_states.insert(session.id.clone(), session.state.clone());
 
-        if !runtime.has_bootstrapped || !emit_events {
-            continue;
-        }
-
-        let previous = runtime.previous_states.get(&session.id);
-        if let Some(event_type) = transition_to_event_type(previous, &session.state) {
+        let transition = if runtime.has_bootstrapped && emit_events {
+            transition_to_event_type(runtime.previous_states.get(&session.id), &session.state)
+        } else {
+            None
+        };
+        let event_type = transition.or_else(|| {
+            if synthesize_current {
+                current_state_event_type(&session.state)
+            } else {
+                None
+            }
+        });
+        if let Some(event_type) = event_type {
             events.push(build_session_event(event_type, session, timestamp));
         }
     }
@@ -376,10 +785,28 @@ fn build_monitor_events(
     runtime.previous_states = next_states;
     runtime.previous_process_running = snapshot.is_codex_running;
     runtime.has_bootstrapped = true;
+    if emit_events {
+        runtime.has_emitted_events = true;
+    }
 
     events
 }
 
+fn current_state_event_type(state: &CodexMonitorState) -> Option<CodexAgentEventType> {
+    match state {
+        CodexMonitorState::Working
+        | CodexMonitorState::NeedsAttention
+        | CodexMonitorState::Error => transition_to_event_type(None, state),
+        _ => None,
+    }
+}
+
+fn bootstrap_events_enabled(app: &AppHandle) -> bool {
+    storage::load_app_state(app)
+        .map(|state| state.settings.codex_monitor_bootstrap_events)
+        .unwrap_or(false)
+}
+
 fn transition_to_event_type(
     previous: Option<&CodexMonitorState>,
     current: &CodexMonitorState,
@@ -472,20 +899,36 @@ fn collect_rollout_files(base_dir: &Path) -> Result<Vec<PathBuf>, String> {
     Ok(files)
 }
 
+// Codex 按写入时刻的日期划分 `YYYY/MM/DD` 目录，但无法确定使用本地时区还是 UTC，
+// 因此同时覆盖本地与 UTC 的最近几天，避免跨零点时当天会话落在"昨天"目录而被漏掉。
 fn collect_candidate_dirs(base_dir: &Path) -> Vec<PathBuf> {
-    let mut dirs = Vec::new();
-    let mut date = Local::now().date_naive();
-    for _ in 0..CANDIDATE_DAYS {
-        let dir = build_date_dir(base_dir, date);
-        if dir.exists() && dir.is_dir() {
-            dirs.push(dir);
+    candidate_dates(Local::now().date_naive(), Utc::now().date_naive())
+        .into_iter()
+        .map(|date| build_date_dir(base_dir, date))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+// 以本地与 UTC 的"今天"为起点各向前取 CANDIDATE_DAYS 天，去重后按日期倒序返回。
+fn candidate_dates(local_today: NaiveDate, utc_today: NaiveDate) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    for today in [local_today, utc_today] {
+        let mut date = today;
+        for _ in 0..CANDIDATE_DAYS {
+            if !dates.contains(&date) {
+                dates.push(date);
+            }
+            match date.pred_opt() {
+                Some(prev) => date = prev,
+                None => break,
+            }
         }
-        date = date.pred_opt().unwrap_or(date);
     }
-    dirs
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    dates
 }
 
-fn build_date_dir(base_dir: &Path, date: chrono::NaiveDate) -> PathBuf {
+fn build_date_dir(base_dir: &Path, date: NaiveDate) -> PathBuf {
     base_dir
         .join(format!("{:04}", date.year()))
         .join(format!("{:02}", date.month()))
@@ -539,8 +982,12 @@ fn parse_session_file(
     is_codex_running: bool,
 ) -> Result<CodexMonitorSession, String> {
     let meta = read_session_meta(path)?;
-    let tail_lines =
-        read_tail_lines_resilient(path, MAX_TAIL_LINES, MAX_TAIL_BYTES, MAX_TAIL_BYTES_CAP)?;
+    let tail_lines = read_tail_non_empty_lines_resilient(
+        path,
+        MAX_TAIL_LINES,
+        MAX_TAIL_BYTES,
+        MAX_TAIL_BYTES_CAP,
+    )?;
 
     let mut tracker = SessionTracker {
         last_activity_at: meta.started_at,
@@ -1022,71 +1469,6 @@ fn read_session_meta(path: &Path) -> Result<SessionMeta, String> {
     })
 }
 
-fn read_tail_lines(path: &Path, max_lines: usize, max_bytes: u64) -> Result<Vec<String>, String> {
-    let mut file = File::open(path).map_err(|err| format!("读取会话文件失败: {err}"))?;
-    let size = file
-        .metadata()
-        .map_err(|err| format!("读取文件元信息失败: {err}"))?
-        .len();
-    let start = if size > max_bytes {
-        size - max_bytes
-    } else {
-        0
-    };
-    let read_start = if start > 0 {
-        start.saturating_sub(1)
-    } else {
-        0
-    };
-
-    file.seek(SeekFrom::Start(read_start))
-        .map_err(|err| format!("定位会话文件失败: {err}"))?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
-        .map_err(|err| format!("读取会话文件失败: {err}"))?;
-
-    let text = String::from_utf8_lossy(&buffer);
-    let mut lines: Vec<&str> = text.split("\n").collect();
-    if read_start > 0 && !lines.is_empty() {
-        lines.remove(0);
-    }
-
-    let mut trimmed: Vec<String> = lines
-        .into_iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| line.to_string())
-        .collect();
-
-    if trimmed.len() > max_lines {
-        trimmed = trimmed.split_off(trimmed.len() - max_lines);
-    }
-
-    Ok(trimmed)
-}
-
-fn read_tail_lines_resilient(
-    path: &Path,
-    max_lines: usize,
-    initial_bytes: u64,
-    max_bytes_cap: u64,
-) -> Result<Vec<String>, String> {
-    let size = fs::metadata(path)
-        .map_err(|err| format!("读取文件元信息失败: {err}"))?
-        .len();
-    if size == 0 {
-        return Ok(Vec::new());
-    }
-
-    let mut bytes = initial_bytes.min(size).max(1024);
-    loop {
-        let lines = read_tail_lines(path, max_lines, bytes)?;
-        if !lines.is_empty() || bytes >= size || bytes >= max_bytes_cap {
-            return Ok(lines);
-        }
-        bytes = bytes.saturating_mul(2).min(size).min(max_bytes_cap);
-    }
-}
-
 fn parse_timestamp(value: &Value) -> Option<i64> {
     value
         .as_str()
@@ -1147,6 +1529,26 @@ fn lsof_stdout_indicates_codex_open(stdout: &str) -> bool {
     })
 }
 
+// 解析 `lsof -F pc` 输出中命令名为 codex 的进程 ID（`p<pid>` 行后跟 `c<command>` 行）。
+fn lsof_stdout_codex_pids(stdout: &str) -> Vec<u32> {
+    let mut pids = Vec::new();
+    let mut current_pid = None;
+    for line in stdout.lines() {
+        if let Some(pid) = line.strip_prefix('p') {
+            current_pid = pid.trim().parse::<u32>().ok();
+        } else if let Some(command) = line.strip_prefix('c') {
+            let name = command.trim().to_ascii_lowercase();
+            if (name == "codex" || name == "codex.exe")
+                && let Some(pid) = current_pid
+                && !pids.contains(&pid)
+            {
+                pids.push(pid);
+            }
+        }
+    }
+    pids
+}
+
 fn run_lsof_output_with_fallback(args: &[&str], path: &Path) -> Option<std::process::Output> {
     match Command::new("lsof").args(args).arg(path).output() {
         Ok(output) => Some(output),
@@ -1189,6 +1591,51 @@ mod tests {
         path
     }
 
+    #[test]
+    fn candidate_dates_should_cover_local_and_utc_days() {
+        let local = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+        let utc = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(
+            candidate_dates(local, utc),
+            vec![local, utc, NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()]
+        );
+        assert_eq!(candidate_dates(local, local), vec![local, utc]);
+    }
+
+    #[test]
+    fn read_appended_lines_should_keep_partial_line_pending() {
+        let path = write_session(&["{\"type\":\"a\"}"]);
+        let mut offset = fs::metadata(&path).unwrap().len();
+        let mut pending = Vec::new();
+
+        fs::write(&path, "{\"type\":\"a\"}\n{\"type\":\"b\"}\n{\"ty").unwrap();
+        let lines = read_appended_lines(&path, &mut offset, &mut pending).unwrap();
+        assert_eq!(lines, vec!["{\"type\":\"b\"}".to_string()]);
+
+        fs::write(
+            &path,
+            "{\"type\":\"a\"}\n{\"type\":\"b\"}\n{\"type\":\"c\"}\n",
+        )
+        .unwrap();
+        let lines = read_appended_lines(&path, &mut offset, &mut pending).unwrap();
+        assert_eq!(lines, vec!["{\"type\":\"c\"}".to_string()]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn build_session_line_should_extract_message_preview() {
+        let value: Value = serde_json::from_str(
+            r#"{"timestamp":"2025-01-01T00:00:00Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"完成了"}]}}"#,
+        )
+        .unwrap();
+        let line = build_session_line("s1", &value).expect("line");
+        assert_eq!(line.entry_type, "response_item");
+        assert_eq!(line.item_type.as_deref(), Some("message"));
+        assert_eq!(line.role.as_deref(), Some("assistant"));
+        assert_eq!(line.preview.as_deref(), Some("完成了"));
+        assert!(line.timestamp > 0);
+    }
+
     #[test]
     fn parse_session_file_marks_working_when_pending_turn_is_streaming() {
         let path = write_session(&[
@@ -1285,4 +1732,180 @@ mod tests {
             Some(CodexAgentEventType::AgentIdle)
         );
     }
+
+    #[test]
+    fn match_project_for_cwd_prefers_longest_project_or_worktree_path() {
+        let project = |id: &str, path: &str, worktrees: &[&str]| Project {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string(),
+            tags: Vec::new(),
+            scripts: Vec::new(),
+            worktrees: worktrees
+                .iter()
+                .map(|path| crate::models::ProjectWorktree {
+                    id: format!("worktree:{path}"),
+                    name: "wt".to_string(),
+                    path: path.to_string(),
+                    branch: "feature".to_string(),
+                    base_branch: None,
+                    inherit_config: true,
+                    created: 0.0,
+                    status: None,
+                    init_step: None,
+                    init_message: None,
+                    init_error: None,
+                    init_job_id: None,
+                    updated_at: None,
+                })
+                .collect(),
+            mtime: 0.0,
+            size: 0,
+            checksum: String::new(),
+            git_commits: 0,
+            git_last_commit: 0.0,
+            git_daily: None,
+            created: 0.0,
+            checked: 0.0,
+            terminal_shell: None,
+            terminal_env: HashMap::new(),
+        };
+        let projects = vec![
+            project("outer", "/work", &[]),
+            project("repo", "/work/repo", &["/wt/repo-feature"]),
+        ];
+
+        let (matched, path) =
+            match_project_for_cwd(&projects, "/work/repo/src").expect("nested project");
+        assert_eq!(matched.id, "repo");
+        assert_eq!(path, "/work/repo");
+
+        let (matched, path) =
+            match_project_for_cwd(&projects, "/wt/repo-feature").expect("worktree");
+        assert_eq!(matched.id, "repo");
+        assert_eq!(path, "/wt/repo-feature");
+
+        assert!(match_project_for_cwd(&projects, "/work-other").is_none());
+    }
+
+    #[test]
+    fn lsof_stdout_codex_pids_only_returns_codex_processes() {
+        let stdout = "p101\ncnode\np202\nccodex\np303\nccodex\np202\nccodex\n";
+        assert_eq!(lsof_stdout_codex_pids(stdout), vec![202, 303]);
+        assert!(lsof_stdout_codex_pids("p1\ncvim\n").is_empty());
+    }
+
+    #[test]
+    fn sessions_dir_from_prefers_setting_then_codex_home() {
+        let home = Path::new("/home/dev");
+        assert_eq!(
+            sessions_dir_from(None, None, home),
+            PathBuf::from("/home/dev/.codex/sessions")
+        );
+        assert_eq!(
+            sessions_dir_from(None, Some("/data/codex".into()), home),
+            PathBuf::from("/data/codex/sessions")
+        );
+        assert_eq!(
+            sessions_dir_from(Some("~/codex-sessions"), Some("/data/codex".into()), home),
+            PathBuf::from("/home/dev/codex-sessions")
+        );
+        assert_eq!(
+            sessions_dir_from(Some("  "), None, home),
+            PathBuf::from("/home/dev/.codex/sessions")
+        );
+    }
+
+    #[test]
+    fn build_monitor_events_synthesizes_current_state_only_once() {
+        let session = |id: &str, state: CodexMonitorState| CodexMonitorSession {
+            id: id.to_string(),
+            cwd: "/repo".to_string(),
+            cli_version: None,
+            model: None,
+            effort: None,
+            started_at: 0,
+            last_activity_at: 0,
+            state,
+            is_running: true,
+            session_title: None,
+            details: None,
+        };
+        let snapshot = CodexMonitorSnapshot {
+            sessions: vec![
+                session("working", CodexMonitorState::Working),
+                session("idle", CodexMonitorState::Idle),
+                session("attention", CodexMonitorState::NeedsAttention),
+            ],
+            is_codex_running: true,
+            updated_at: 0,
+            model_usage: Vec::new(),
+        };
+
+        let mut runtime = MonitorRuntime::default();
+        let first = build_monitor_events(&mut runtime, &snapshot, 1, true, true);
+        let types: Vec<_> = first.iter().map(|event| event.event_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                CodexAgentEventType::AgentActive,
+                CodexAgentEventType::NeedsAttention
+            ]
+        );
+
+        let second = build_monitor_events(&mut runtime, &snapshot, 2, true, false);
+        assert!(second.is_empty());
+
+        let mut quiet = MonitorRuntime::default();
+        assert!(build_monitor_events(&mut quiet, &snapshot, 1, true, false).is_empty());
+    }
+
+    #[test]
+    fn model_usage_should_count_and_filter_by_model_and_effort() {
+        let session = |id: &str, model: Option<&str>, effort: Option<&str>| CodexMonitorSession {
+            id: id.to_string(),
+            cwd: "/repo".to_string(),
+            cli_version: None,
+            model: model.map(str::to_string),
+            effort: effort.map(str::to_string),
+            started_at: 0,
+            last_activity_at: 0,
+            state: CodexMonitorState::Idle,
+            is_running: true,
+            session_title: None,
+            details: None,
+        };
+        let sessions = vec![
+            session("a", Some("gpt-5-codex"), Some("high")),
+            session("b", Some("gpt-5-codex"), Some("high")),
+            session("c", Some("gpt-5-codex"), Some("xhigh")),
+            session("d", None, None),
+        ];
+
+        let usage = summarize_model_usage(&sessions);
+        assert_eq!(
+            usage[0],
+            CodexModelUsage {
+                model: Some("gpt-5-codex".to_string()),
+                effort: Some("high".to_string()),
+                count: 2,
+            }
+        );
+        assert_eq!(usage.len(), 3);
+
+        let filter = CodexSessionFilter {
+            model: Some("GPT-5-Codex".to_string()),
+            effort: Some("xhigh".to_string()),
+        };
+        let matched: Vec<_> = sessions
+            .iter()
+            .filter(|session| session_matches_filter(session, &filter))
+            .map(|session| session.id.as_str())
+            .collect();
+        assert_eq!(matched, vec!["c"]);
+        assert!(session_matches_filter(
+            &sessions[3],
+            &CodexSessionFilter::default()
+        ));
+    }
 }
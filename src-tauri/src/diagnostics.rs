@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use tauri::{AppHandle, Manager};
+
+use crate::file_tail;
+use crate::models::LogFileInfo;
+
+const DEFAULT_LOG_TAIL_LINES: usize = 200;
+const MAX_LOG_TAIL_LINES: usize = 2000;
+// 只读取日志末尾的有限字节，避免日志文件过大时占用过多内存。
+const MAX_LOG_TAIL_BYTES: u64 = 512 * 1024;
+
+/// 解析日志目录与当前日志文件（log 插件默认以应用名命名日志文件）。
+pub fn resolve_log_file(app: &AppHandle) -> Result<LogFileInfo, String> {
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|err| format!("无法获取日志目录: {err}"))?;
+    let log_file = log_dir.join(format!("{}.log", app.package_info().name));
+    Ok(LogFileInfo {
+        exists: log_file.is_file(),
+        log_dir: log_dir.to_string_lossy().to_string(),
+        log_file: log_file.to_string_lossy().to_string(),
+    })
+}
+
+/// 读取当前日志文件的最后若干行（默认 200 行，最多 2000 行）。
+pub fn read_recent_logs(app: &AppHandle, lines: Option<usize>) -> Result<Vec<String>, String> {
+    let info = resolve_log_file(app)?;
+    if !info.exists {
+        return Ok(Vec::new());
+    }
+    read_log_tail(Path::new(&info.log_file), lines)
+}
+
+fn read_log_tail(path: &Path, lines: Option<usize>) -> Result<Vec<String>, String> {
+    let lines = lines
+        .unwrap_or(DEFAULT_LOG_TAIL_LINES)
+        .clamp(1, MAX_LOG_TAIL_LINES);
+    file_tail::read_tail_lines(path, lines, MAX_LOG_TAIL_BYTES)
+        .map_err(|err| format!("读取日志文件失败: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_LOG_TAIL_LINES, read_log_tail};
+    use std::fs;
+
+    #[test]
+    fn read_log_tail_returns_last_lines_with_default_and_clamped_limits() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp dir");
+        let path = root.join("DevHaven.log");
+        let content: String = (1..=250).map(|index| format!("line {index}\n\n")).collect();
+        fs::write(&path, content).expect("write log");
+
+        let tail = read_log_tail(&path, None).unwrap();
+        assert_eq!(tail.len(), DEFAULT_LOG_TAIL_LINES);
+        assert_eq!(tail.last().map(String::as_str), Some(""));
+        assert_eq!(tail[tail.len() - 2], "line 250");
+
+        let tail = read_log_tail(&path, Some(3)).unwrap();
+        assert_eq!(tail, vec!["", "line 250", ""]);
+
+        // 0 行按最少 1 行处理。
+        assert_eq!(read_log_tail(&path, Some(0)).unwrap(), vec![""]);
+
+        assert!(read_log_tail(&root.join("missing.log"), None).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
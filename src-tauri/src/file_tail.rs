@@ -0,0 +1,115 @@
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 从文件末尾读取最多 max_bytes 字节，返回其中最后 max_lines 行（保留空行）。
+///
+/// 窗口起点落在行中间时丢弃该残缺行；文件末尾的换行不会产生额外空行。
+pub fn read_tail_lines(
+    path: &Path,
+    max_lines: usize,
+    max_bytes: u64,
+) -> Result<Vec<String>, String> {
+    let lines = read_tail_window(path, max_bytes)?;
+    Ok(keep_last(lines, max_lines))
+}
+
+/// 同 [`read_tail_lines`]，但跳过空白行，max_lines 按非空行计数。
+pub fn read_tail_non_empty_lines(
+    path: &Path,
+    max_lines: usize,
+    max_bytes: u64,
+) -> Result<Vec<String>, String> {
+    let lines = read_tail_window(path, max_bytes)?
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    Ok(keep_last(lines, max_lines))
+}
+
+/// 读取非空尾行；窗口内没有完整非空行时逐步翻倍窗口，直到覆盖整个文件或达到 max_bytes_cap。
+pub fn read_tail_non_empty_lines_resilient(
+    path: &Path,
+    max_lines: usize,
+    initial_bytes: u64,
+    max_bytes_cap: u64,
+) -> Result<Vec<String>, String> {
+    let size = fs::metadata(path)
+        .map_err(|err| format!("读取文件元信息失败: {err}"))?
+        .len();
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut bytes = initial_bytes.min(size).max(1024);
+    loop {
+        let lines = read_tail_non_empty_lines(path, max_lines, bytes)?;
+        if !lines.is_empty() || bytes >= size || bytes >= max_bytes_cap {
+            return Ok(lines);
+        }
+        bytes = bytes.saturating_mul(2).min(size).min(max_bytes_cap);
+    }
+}
+
+fn read_tail_window(path: &Path, max_bytes: u64) -> Result<Vec<String>, String> {
+    let mut file = File::open(path).map_err(|err| format!("打开文件失败: {err}"))?;
+    let size = file
+        .metadata()
+        .map_err(|err| format!("读取文件元信息失败: {err}"))?
+        .len();
+    // 多读一个字节用于判断窗口起点是否恰好位于行首。
+    let read_start = size.saturating_sub(max_bytes).saturating_sub(1);
+
+    file.seek(SeekFrom::Start(read_start))
+        .map_err(|err| format!("定位文件读取位置失败: {err}"))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .map_err(|err| format!("读取文件失败: {err}"))?;
+
+    let text = String::from_utf8_lossy(&buffer);
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    if read_start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    if text.ends_with('\n') {
+        lines.pop();
+    }
+    Ok(lines)
+}
+
+fn keep_last(mut lines: Vec<String>, max_lines: usize) -> Vec<String> {
+    if lines.len() > max_lines {
+        lines = lines.split_off(lines.len() - max_lines);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_tail_lines, read_tail_non_empty_lines};
+    use std::fs;
+
+    #[test]
+    fn read_tail_lines_keeps_blank_lines_and_drops_partial_first_line() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create temp dir");
+        let path = root.join("sample.log");
+        fs::write(&path, "first\nsecond\n\nthird\n").expect("write sample");
+
+        assert_eq!(
+            read_tail_lines(&path, 10, 1024).unwrap(),
+            vec!["first", "second", "", "third"]
+        );
+        assert_eq!(read_tail_lines(&path, 2, 1024).unwrap(), vec!["", "third"]);
+        // 8 字节窗口起于 "cond" 中间，残缺行应被丢弃。
+        assert_eq!(read_tail_lines(&path, 10, 8).unwrap(), vec!["", "third"]);
+        // 窗口起点恰好在行首时保留整行。
+        assert_eq!(read_tail_lines(&path, 10, 7).unwrap(), vec!["", "third"]);
+        assert_eq!(
+            read_tail_non_empty_lines(&path, 2, 1024).unwrap(),
+            vec!["second", "third"]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,602 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::git_ops;
+use crate::models::{
+    GitCloneJobStatus, GitCloneProgressPayload, GitCloneStartRequest, GitCloneStep, Project,
+};
+use crate::project_loader;
+use crate::storage;
+
+pub const GIT_CLONE_PROGRESS_EVENT: &str = "git-clone-progress";
+
+const CLONE_ERROR_TAIL_LINES: usize = 20;
+// 未被查询过的已结束任务最多保留的数量，超出时丢弃最旧的记录。
+const MAX_FINISHED_CLONE_JOBS: usize = 20;
+
+/// Git 克隆任务状态：克隆在后台线程执行，进度通过 `git-clone-progress` 事件推送。
+#[derive(Clone, Default)]
+pub struct GitCloneState {
+    jobs: Arc<Mutex<HashMap<String, GitCloneJob>>>,
+}
+
+#[derive(Clone)]
+struct GitCloneJob {
+    job_id: String,
+    url: String,
+    dest: String,
+    branch: Option<String>,
+    register_as_project: bool,
+    /// 启动前目标目录是否不存在；取消或失败时仅清理由本次克隆创建的目录。
+    dest_created: bool,
+    step: GitCloneStep,
+    message: String,
+    phase: Option<String>,
+    percent: Option<u8>,
+    error: Option<String>,
+    updated_at: i64,
+    is_running: bool,
+    cancel_requested: bool,
+}
+
+enum CloneRunOutcome {
+    Ready(Option<Box<Project>>),
+    Failed(String),
+    Cancelled,
+}
+
+impl GitCloneState {
+    pub fn start(
+        &self,
+        app: &AppHandle,
+        request: GitCloneStartRequest,
+    ) -> Result<GitCloneJobStatus, String> {
+        let url = request.url.trim().to_string();
+        if url.is_empty() {
+            return Err("仓库地址不能为空".to_string());
+        }
+        let dest = request.dest.trim().to_string();
+        if dest.is_empty() {
+            return Err("目标目录不能为空".to_string());
+        }
+        let branch = request
+            .branch
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let dest_path = Path::new(&dest);
+        let dest_created = !dest_path.exists();
+        if !dest_created && !is_empty_dir(dest_path) {
+            return Err(format!("目标目录已存在且不为空：{dest}"));
+        }
+
+        let job_id = Uuid::new_v4().to_string();
+        let job = GitCloneJob {
+            job_id: job_id.clone(),
+            url,
+            dest,
+            branch,
+            register_as_project: request.register_as_project,
+            dest_created,
+            step: GitCloneStep::Pending,
+            message: "等待开始克隆".to_string(),
+            phase: None,
+            percent: None,
+            error: None,
+            updated_at: now_millis(),
+            is_running: true,
+            cancel_requested: false,
+        };
+        let status = build_job_status(&job);
+        self.jobs
+            .lock()
+            .map_err(|_| "克隆任务状态锁定失败".to_string())?
+            .insert(job_id.clone(), job);
+
+        let state = self.clone();
+        let app_handle = app.clone();
+        thread::spawn(move || state.run_job(app_handle, job_id));
+
+        Ok(status)
+    }
+
+    /// 请求取消克隆任务；正在运行的 git 进程会在下一次轮询时被结束。
+    pub fn cancel(&self, job_id: &str) -> Result<(), String> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|_| "克隆任务状态锁定失败".to_string())?;
+        let Some(job) = jobs.get_mut(job_id) else {
+            return Err("克隆任务不存在".to_string());
+        };
+        if job.is_running {
+            job.cancel_requested = true;
+            job.updated_at = now_millis();
+        }
+        Ok(())
+    }
+
+    /// 列出所有克隆任务，按更新时间倒序；已结束的任务在本次查询中返回后即被移除。
+    pub fn query_status(&self) -> Result<Vec<GitCloneJobStatus>, String> {
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|_| "克隆任务状态锁定失败".to_string())?;
+        Ok(drain_job_statuses(&mut jobs))
+    }
+
+    fn run_job(&self, app: AppHandle, job_id: String) {
+        let Some(job) = self.snapshot_job(&job_id) else {
+            return;
+        };
+
+        let outcome = self.run_job_flow(&app, &job);
+        if !matches!(outcome, CloneRunOutcome::Ready(_)) && job.dest_created {
+            let _ = fs::remove_dir_all(&job.dest);
+        }
+        match outcome {
+            CloneRunOutcome::Ready(project) => self.emit_progress(
+                &app,
+                &job_id,
+                GitCloneStep::Ready,
+                "克隆完成",
+                None,
+                project.map(|project| *project),
+            ),
+            CloneRunOutcome::Failed(error) => self.emit_progress(
+                &app,
+                &job_id,
+                GitCloneStep::Failed,
+                "克隆失败",
+                Some(error),
+                None,
+            ),
+            CloneRunOutcome::Cancelled => self.emit_progress(
+                &app,
+                &job_id,
+                GitCloneStep::Cancelled,
+                "克隆已取消",
+                None,
+                None,
+            ),
+        }
+        if let Ok(mut jobs) = self.jobs.lock() {
+            if let Some(job) = jobs.get_mut(&job_id) {
+                job.is_running = false;
+            }
+            prune_finished_jobs(&mut jobs, MAX_FINISHED_CLONE_JOBS);
+        }
+    }
+
+    fn run_job_flow(&self, app: &AppHandle, job: &GitCloneJob) -> CloneRunOutcome {
+        self.emit_progress(
+            app,
+            &job.job_id,
+            GitCloneStep::Cloning,
+            "执行中：正在克隆仓库...",
+            None,
+            None,
+        );
+
+        let mut command = git_ops::git_command();
+        command.args(["clone", "--progress"]);
+        if let Some(branch) = job.branch.as_deref() {
+            command.args(["--branch", branch]);
+        }
+        command
+            .arg("--")
+            .arg(&job.url)
+            .arg(&job.dest)
+            // 禁止交互式凭据提示，认证失败时直接报错而不是挂起。
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(error) => return CloneRunOutcome::Failed(format!("启动 git clone 失败: {error}")),
+        };
+
+        let (sender, receiver) = mpsc::channel::<String>();
+        let reader = child
+            .stderr
+            .take()
+            .map(|stderr| thread::spawn(move || read_progress_lines(stderr, sender)));
+
+        let mut tail: VecDeque<String> = VecDeque::new();
+        let mut last_progress: Option<(String, u8)> = None;
+        loop {
+            if self.is_cancel_requested(&job.job_id) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return CloneRunOutcome::Cancelled;
+            }
+            match receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(line) => {
+                    if let Some(progress) = parse_clone_progress(&line) {
+                        if last_progress.as_ref() != Some(&progress) {
+                            self.emit_clone_progress(app, &job.job_id, &progress.0, progress.1);
+                            last_progress = Some(progress);
+                        }
+                        continue;
+                    }
+                    if tail.len() == CLONE_ERROR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        if let Some(reader) = reader {
+            let _ = reader.join();
+        }
+
+        let status = match child.wait() {
+            Ok(status) => status,
+            Err(error) => {
+                return CloneRunOutcome::Failed(format!("等待 git clone 结束失败: {error}"));
+            }
+        };
+        if !status.success() {
+            let raw = tail.into_iter().collect::<Vec<_>>().join("\n");
+            return CloneRunOutcome::Failed(normalize_clone_error(&raw));
+        }
+
+        if !job.register_as_project {
+            return CloneRunOutcome::Ready(None);
+        }
+        self.emit_progress(
+            app,
+            &job.job_id,
+            GitCloneStep::Registering,
+            "执行中：登记到项目列表...",
+            None,
+            None,
+        );
+        match register_cloned_project(app, &job.dest) {
+            Ok(project) => CloneRunOutcome::Ready(project.map(Box::new)),
+            Err(error) => {
+                log::warn!("登记克隆项目失败 dest={}: {}", job.dest, error);
+                CloneRunOutcome::Ready(None)
+            }
+        }
+    }
+
+    fn snapshot_job(&self, job_id: &str) -> Option<GitCloneJob> {
+        self.jobs.lock().ok()?.get(job_id).cloned()
+    }
+
+    fn is_cancel_requested(&self, job_id: &str) -> bool {
+        self.jobs
+            .lock()
+            .ok()
+            .and_then(|jobs| jobs.get(job_id).map(|job| job.cancel_requested))
+            .unwrap_or(false)
+    }
+
+    fn emit_clone_progress(&self, app: &AppHandle, job_id: &str, phase: &str, percent: u8) {
+        let payload = self.update_job(job_id, |job| {
+            job.phase = Some(phase.to_string());
+            job.percent = Some(percent);
+            job.message = format!("执行中：{phase} {percent}%");
+        });
+        emit_payload(app, payload);
+    }
+
+    fn emit_progress(
+        &self,
+        app: &AppHandle,
+        job_id: &str,
+        step: GitCloneStep,
+        message: &str,
+        error: Option<String>,
+        project: Option<Project>,
+    ) {
+        let payload = self
+            .update_job(job_id, |job| {
+                job.step = step;
+                job.message = message.to_string();
+                job.error = error;
+            })
+            .map(|payload| GitCloneProgressPayload { project, ..payload });
+        emit_payload(app, payload);
+    }
+
+    fn update_job(
+        &self,
+        job_id: &str,
+        update: impl FnOnce(&mut GitCloneJob),
+    ) -> Option<GitCloneProgressPayload> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.get_mut(job_id)?;
+        update(job);
+        job.updated_at = now_millis();
+        Some(GitCloneProgressPayload {
+            job_id: job.job_id.clone(),
+            url: job.url.clone(),
+            dest: job.dest.clone(),
+            branch: job.branch.clone(),
+            step: job.step.clone(),
+            message: job.message.clone(),
+            phase: job.phase.clone(),
+            percent: job.percent,
+            error: job.error.clone(),
+            project: None,
+        })
+    }
+}
+
+fn emit_payload(app: &AppHandle, payload: Option<GitCloneProgressPayload>) {
+    let Some(payload) = payload else {
+        return;
+    };
+    if let Err(error) = app.emit(GIT_CLONE_PROGRESS_EVENT, payload) {
+        log::warn!("发送 git-clone-progress 失败: {}", error);
+    }
+}
+
+fn build_job_status(job: &GitCloneJob) -> GitCloneJobStatus {
+    GitCloneJobStatus {
+        job_id: job.job_id.clone(),
+        url: job.url.clone(),
+        dest: job.dest.clone(),
+        branch: job.branch.clone(),
+        step: job.step.clone(),
+        message: job.message.clone(),
+        phase: job.phase.clone(),
+        percent: job.percent,
+        error: job.error.clone(),
+        updated_at: job.updated_at,
+        is_running: job.is_running,
+        cancel_requested: job.cancel_requested,
+    }
+}
+
+// 汇总任务状态（按更新时间倒序），并移除已结束的任务。
+fn drain_job_statuses(jobs: &mut HashMap<String, GitCloneJob>) -> Vec<GitCloneJobStatus> {
+    let mut statuses: Vec<GitCloneJobStatus> = jobs.values().map(build_job_status).collect();
+    jobs.retain(|_, job| job.is_running);
+    statuses.sort_by_key(|status| std::cmp::Reverse(status.updated_at));
+    statuses
+}
+
+// 已结束任务超过 keep 个时，按更新时间丢弃最旧的记录；运行中的任务不受影响。
+fn prune_finished_jobs(jobs: &mut HashMap<String, GitCloneJob>, keep: usize) {
+    let mut finished: Vec<(i64, String)> = jobs
+        .values()
+        .filter(|job| !job.is_running)
+        .map(|job| (job.updated_at, job.job_id.clone()))
+        .collect();
+    if finished.len() <= keep {
+        return;
+    }
+    finished.sort();
+    let excess = finished.len() - keep;
+    for (_, job_id) in finished.into_iter().take(excess) {
+        jobs.remove(&job_id);
+    }
+}
+
+// 将克隆结果合并进项目缓存（按路径去重），返回登记的项目。
+fn register_cloned_project(app: &AppHandle, dest: &str) -> Result<Option<Project>, String> {
+    let mut projects = storage::load_projects(app)?;
+    let Some(project) = project_loader::build_projects(&[dest.to_string()], &projects)
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    match projects.iter_mut().find(|item| item.path == project.path) {
+        Some(existing) => *existing = project.clone(),
+        None => projects.push(project.clone()),
+    }
+    storage::save_projects(app, &projects)?;
+    Ok(Some(project))
+}
+
+// git 以 `\r` 刷新同一行进度，按 `\r`/`\n` 切分 stderr 并逐行发送。
+fn read_progress_lines(mut stderr: impl Read, sender: mpsc::Sender<String>) {
+    let mut buffer = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let size = match stderr.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(size) => size,
+        };
+        for byte in &buffer[..size] {
+            if *byte == b'\r' || *byte == b'\n' {
+                if !pending.is_empty() {
+                    let line = String::from_utf8_lossy(&pending).trim().to_string();
+                    pending.clear();
+                    if !line.is_empty() && sender.send(line).is_err() {
+                        return;
+                    }
+                }
+            } else {
+                pending.push(*byte);
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let _ = sender.send(String::from_utf8_lossy(&pending).trim().to_string());
+    }
+}
+
+// 解析进度行，如 `Receiving objects:  45% (450/1000)` 或 `remote: Counting objects: 10% (1/10)`。
+fn parse_clone_progress(line: &str) -> Option<(String, u8)> {
+    let line = line.strip_prefix("remote:").unwrap_or(line).trim();
+    let (phase, rest) = line.split_once(':')?;
+    let (percent, _) = rest.trim_start().split_once('%')?;
+    let percent = percent.trim().parse::<u8>().ok()?;
+    Some((phase.trim().to_string(), percent.min(100)))
+}
+
+// 将常见的 git clone 错误转换为可读提示。
+fn normalize_clone_error(raw: &str) -> String {
+    let raw = raw.trim();
+    let lower = raw.to_lowercase();
+    if lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("terminal prompts disabled")
+        || lower.contains("permission denied (publickey")
+    {
+        return "认证失败：请检查仓库访问权限，或先在终端中配置好凭据/SSH Key".to_string();
+    }
+    if lower.contains("already exists and is not an empty directory") {
+        return "目标目录已存在且不为空".to_string();
+    }
+    if lower.contains("remote branch") && lower.contains("not found") {
+        return "远程分支不存在，请检查分支名".to_string();
+    }
+    if lower.contains("repository not found")
+        || lower.contains("does not appear to be a git repository")
+    {
+        return "仓库不存在或无访问权限，请检查仓库地址".to_string();
+    }
+    if lower.contains("could not resolve host") {
+        return "无法连接远程主机，请检查网络或仓库地址".to_string();
+    }
+    if raw.is_empty() {
+        return "克隆失败".to_string();
+    }
+    format!("克隆失败: {raw}")
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    fs::read_dir(path)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false)
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GitCloneJob, drain_job_statuses, normalize_clone_error, parse_clone_progress,
+        prune_finished_jobs,
+    };
+    use crate::models::GitCloneStep;
+    use std::collections::HashMap;
+
+    fn job(job_id: &str, updated_at: i64, is_running: bool) -> GitCloneJob {
+        GitCloneJob {
+            job_id: job_id.to_string(),
+            url: "https://example.com/demo.git".to_string(),
+            dest: format!("/tmp/{job_id}"),
+            branch: None,
+            register_as_project: false,
+            dest_created: true,
+            step: if is_running {
+                GitCloneStep::Cloning
+            } else {
+                GitCloneStep::Ready
+            },
+            message: String::new(),
+            phase: None,
+            percent: None,
+            error: None,
+            updated_at,
+            is_running,
+            cancel_requested: false,
+        }
+    }
+
+    fn jobs_of(items: Vec<GitCloneJob>) -> HashMap<String, GitCloneJob> {
+        items
+            .into_iter()
+            .map(|job| (job.job_id.clone(), job))
+            .collect()
+    }
+
+    #[test]
+    fn drain_job_statuses_returns_finished_jobs_once() {
+        let mut jobs = jobs_of(vec![
+            job("running", 30, true),
+            job("done", 20, false),
+            job("failed", 10, false),
+        ]);
+
+        let ids: Vec<String> = drain_job_statuses(&mut jobs)
+            .into_iter()
+            .map(|status| status.job_id)
+            .collect();
+        assert_eq!(ids, vec!["running", "done", "failed"]);
+
+        let ids: Vec<String> = drain_job_statuses(&mut jobs)
+            .into_iter()
+            .map(|status| status.job_id)
+            .collect();
+        assert_eq!(ids, vec!["running"]);
+    }
+
+    #[test]
+    fn prune_finished_jobs_keeps_newest_finished_and_all_running() {
+        let mut jobs = jobs_of(vec![
+            job("old", 1, false),
+            job("middle", 2, false),
+            job("new", 3, false),
+            job("running", 0, true),
+        ]);
+
+        prune_finished_jobs(&mut jobs, 2);
+
+        let mut ids: Vec<&str> = jobs.keys().map(String::as_str).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["middle", "new", "running"]);
+
+        prune_finished_jobs(&mut jobs, 2);
+        assert_eq!(jobs.len(), 3);
+    }
+
+    #[test]
+    fn parse_clone_progress_should_read_phase_and_percent() {
+        assert_eq!(
+            parse_clone_progress("Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s"),
+            Some(("Receiving objects".to_string(), 45))
+        );
+        assert_eq!(
+            parse_clone_progress("remote: Counting objects: 100% (10/10), done."),
+            Some(("Counting objects".to_string(), 100))
+        );
+        assert_eq!(parse_clone_progress("Cloning into 'demo'..."), None);
+        assert_eq!(
+            parse_clone_progress("remote: Enumerating objects: 10, done."),
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_clone_error_should_map_common_failures() {
+        assert!(
+            normalize_clone_error(
+                "fatal: could not read Username for 'https://github.com': terminal prompts disabled"
+            )
+            .starts_with("认证失败")
+        );
+        assert_eq!(
+            normalize_clone_error(
+                "fatal: destination path 'demo' already exists and is not an empty directory."
+            ),
+            "目标目录已存在且不为空"
+        );
+        assert!(normalize_clone_error("fatal: unexpected").starts_with("克隆失败: "));
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
-use std::process::Command;
 
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike};
+
+use crate::git_ops;
 use crate::models::{GitDailyResult, GitIdentity};
 
 pub fn collect_git_daily(paths: &[String], identities: &[GitIdentity]) -> Vec<GitDailyResult> {
@@ -12,6 +14,105 @@ pub fn collect_git_daily(paths: &[String], identities: &[GitIdentity]) -> Vec<Gi
         .collect()
 }
 
+/// 统计指定日期（本地时区，`YYYY-MM-DD`）各小时的提交数，返回 24 个槽位，按作者时间归入小时。
+pub fn collect_hourly_activity(
+    paths: &[String],
+    date: &str,
+    identities: &[GitIdentity],
+) -> Result<Vec<i64>, String> {
+    let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("日期格式无效: {date}"))?;
+    let matcher = IdentityMatcher::new(identities);
+    let since = format!("{day} 00:00:00");
+    let until = format!("{day} 23:59:59");
+
+    let mut hours = vec![0_i64; 24];
+    for path in paths {
+        let repo_root = Path::new(path);
+        if !repo_root.join(".git").exists() {
+            continue;
+        }
+        let output = git_ops::git_command()
+            .args([
+                "log",
+                "--pretty=format:%an%x1f%ae%x1f%at",
+                &format!("--since={since}"),
+                &format!("--until={until}"),
+            ])
+            .current_dir(repo_root)
+            .output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                log::warn!("git log 返回失败: path={} err={}", path, stderr.trim());
+                continue;
+            }
+            Err(err) => {
+                log::warn!("执行 git log 失败: path={} err={}", path, err);
+                continue;
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let mut parts = line.split('\u{1f}');
+            let name = parts.next().unwrap_or("");
+            let email = parts.next().unwrap_or("");
+            let Some(author_time) = parts
+                .next()
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                .map(|time| Local.from_utc_datetime(&time.naive_utc()))
+            else {
+                continue;
+            };
+            // --since/--until 按提交时间过滤，作者时间可能落在其他日期（如 rebase 后），此处剔除。
+            if author_time.date_naive() != day || !matcher.matches(name, email) {
+                continue;
+            }
+            hours[author_time.hour() as usize] += 1;
+        }
+    }
+    Ok(hours)
+}
+
+/// 计算每日提交统计的签名（路径 + 各仓库 HEAD），签名未变时可跳过重新统计。
+pub fn compute_git_daily_signature(paths: &[String]) -> String {
+    let mut sorted: Vec<&String> = paths.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+
+    // 使用 FNV-1a 而非 DefaultHasher，保证签名跨版本稳定，可持久化到缓存文件。
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for path in sorted {
+        let head = resolve_head(path).unwrap_or_default();
+        for byte in path.bytes().chain([0]).chain(head.bytes()).chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+// 读取仓库 HEAD 指向的提交；非 Git 目录或空仓库返回 None。
+fn resolve_head(path: &str) -> Option<String> {
+    let repo_root = Path::new(path);
+    if !repo_root.join(".git").exists() {
+        return None;
+    }
+    let output = git_ops::git_command()
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(repo_root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if head.is_empty() { None } else { Some(head) }
+}
+
 fn collect_single(path: &str, matcher: &IdentityMatcher) -> GitDailyResult {
     let repo_root = Path::new(path);
     if !repo_root.join(".git").exists() {
@@ -22,7 +123,7 @@ fn collect_single(path: &str, matcher: &IdentityMatcher) -> GitDailyResult {
         };
     }
 
-    let output = Command::new("git")
+    let output = git_ops::git_command()
         .args(["log", "--pretty=format:%an%x1f%ae%x1f%cd", "--date=short"])
         .current_dir(repo_root)
         .output();
@@ -131,3 +232,164 @@ fn normalize_identity_value(value: &str) -> Option<String> {
         Some(trimmed.to_lowercase())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_hourly_activity, compute_git_daily_signature};
+    use crate::git_ops;
+    use crate::models::GitIdentity;
+    use chrono::{Local, TimeZone};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn git(repo: &Path, args: &[&str], envs: &[(&str, &str)]) {
+        let output = git_ops::git_command()
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .envs(envs.iter().copied())
+            .current_dir(repo)
+            .output()
+            .expect("run git");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn init_repo(root: &Path, name: &str) -> PathBuf {
+        let repo = root.join(name);
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init", "-q"], &[]);
+        repo
+    }
+
+    fn commit(repo: &Path, message: &str, envs: &[(&str, &str)]) {
+        git(
+            repo,
+            &["commit", "-q", "--allow-empty", "-m", message],
+            envs,
+        );
+    }
+
+    // 以本地时区构造带偏移的 ISO 8601 时间，供 GIT_AUTHOR_DATE/GIT_COMMITTER_DATE 使用。
+    fn local_time(day: u32, hour: u32, minute: u32, second: u32) -> String {
+        Local
+            .with_ymd_and_hms(2024, 3, day, hour, minute, second)
+            .earliest()
+            .expect("valid local time")
+            .to_rfc3339()
+    }
+
+    fn commit_at(repo: &Path, message: &str, author: &str, committer: &str, email: &str) {
+        commit(
+            repo,
+            message,
+            &[
+                ("GIT_AUTHOR_DATE", author),
+                ("GIT_COMMITTER_DATE", committer),
+                ("GIT_AUTHOR_EMAIL", email),
+            ],
+        );
+    }
+
+    #[test]
+    fn collect_hourly_activity_buckets_by_local_author_hour() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let repo = init_repo(&root, "repo");
+        let me = "me@example.com";
+
+        let before = local_time(9, 23, 59, 59);
+        let day_start = local_time(10, 0, 0, 0);
+        let day_end = local_time(10, 23, 59, 59);
+        let after = local_time(11, 0, 0, 0);
+        // 按提交时间先后创建，前一天与后一天的边界提交不计入。
+        commit_at(&repo, "previous day", &before, &before, me);
+        commit_at(&repo, "start of day", &day_start, &day_start, me);
+        commit_at(
+            &repo,
+            "end of hour 0",
+            &local_time(10, 0, 59, 59),
+            &day_start,
+            me,
+        );
+        commit_at(
+            &repo,
+            "start of hour 1",
+            &local_time(10, 1, 0, 0),
+            &day_start,
+            me,
+        );
+        // 提交时间在当天但作者时间在前一天（如 rebase 后）不计入。
+        commit_at(&repo, "rebased", &before, &day_end, me);
+        // 其他作者的提交在指定身份时不计入。
+        commit_at(
+            &repo,
+            "someone else",
+            &day_end,
+            &day_end,
+            "other@example.com",
+        );
+        commit_at(&repo, "end of day", &day_end, &day_end, me);
+        commit_at(&repo, "next day", &after, &after, me);
+
+        let paths = vec![repo.to_string_lossy().to_string()];
+        let identities = vec![GitIdentity {
+            name: String::new(),
+            email: me.to_string(),
+        }];
+        let hours = collect_hourly_activity(&paths, "2024-03-10", &identities).unwrap();
+        assert_eq!(hours.len(), 24);
+        assert_eq!(hours[0], 2, "hours={hours:?}");
+        assert_eq!(hours[1], 1);
+        assert_eq!(hours[23], 1);
+        assert_eq!(hours.iter().sum::<i64>(), 4);
+
+        // 不指定身份时统计所有作者。
+        let hours = collect_hourly_activity(&paths, " 2024-03-10 ", &[]).unwrap();
+        assert_eq!(hours[23], 2);
+
+        assert!(collect_hourly_activity(&paths, "2024/03/10", &[]).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn compute_git_daily_signature_changes_only_when_head_moves() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let repo = init_repo(&root, "repo");
+        let plain = root.join("plain");
+        fs::create_dir_all(&plain).expect("create plain dir");
+        let repo_path = repo.to_string_lossy().to_string();
+        let plain_path = plain.to_string_lossy().to_string();
+        let paths = vec![repo_path.clone(), plain_path.clone()];
+
+        // 空仓库没有 HEAD，签名仍然稳定。
+        let empty = compute_git_daily_signature(&paths);
+        assert_eq!(empty, compute_git_daily_signature(&paths));
+
+        commit(&repo, "first", &[]);
+        let first = compute_git_daily_signature(&paths);
+        assert_ne!(first, empty);
+        assert_eq!(first, compute_git_daily_signature(&paths));
+        // 路径顺序与重复不影响签名。
+        assert_eq!(
+            first,
+            compute_git_daily_signature(&[plain_path, repo_path.clone(), repo_path.clone()])
+        );
+
+        // 工作区改动不移动 HEAD，签名不变。
+        fs::write(repo.join("README.md"), "draft").expect("write file");
+        assert_eq!(first, compute_git_daily_signature(&paths));
+
+        commit(&repo, "second", &[]);
+        let second = compute_git_daily_signature(&paths);
+        assert_ne!(second, first);
+
+        git(&repo, &["reset", "-q", "--hard", "HEAD~1"], &[]);
+        assert_eq!(compute_git_daily_signature(&paths), first);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
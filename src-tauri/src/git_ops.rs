@@ -1,13 +1,116 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 
 use crate::models::{
-    BranchListItem, GitChangedFile, GitDiffContents, GitFileStatus, GitRepoStatus,
-    GitWorktreeAddResult, GitWorktreeListItem,
+    BranchListItem, CommitValidationCode, CommitValidationError, GitAheadBehind, GitBaseDiff,
+    GitChangedFile, GitCleanResult, GitConfigScope, GitConflictContents, GitConflictRegion,
+    GitContributor, GitDiffContents, GitDiffOptions, GitDiffStatEntry, GitError, GitErrorKind,
+    GitFileStatus, GitHeadInfo, GitIdentity, GitOperation, GitOperationState, GitRemote,
+    GitRenameDetection, GitRepoHealth, GitRepoIdentity, GitRepoStats, GitRepoStatus, GitResetMode,
+    GitResetResult, GitTagItem, GitUntrackedMode, GitWorktreeAddResult, GitWorktreeDivergence,
+    GitWorktreeListItem, GitWorktreePruneEntry, GitWorktreePruneResult,
 };
 
+pub const BRANCH_CHECKED_OUT_EVENT: &str = "branch-checked-out";
+
+const MAX_DIFF_FILE_BYTES: usize = 1_200_000;
+const STATUS_CACHE_TTL: Duration = Duration::from_secs(1);
+const INDEX_LOCK_STALE_AFTER: Duration = Duration::from_secs(5);
+// 优先使用 Homebrew 安装的较新 Git，最后回退系统 Git 与 PATH 查找。
+const GIT_BIN_CANDIDATES: &[&str] = &[
+    "/opt/homebrew/bin/git",
+    "/usr/local/bin/git",
+    "/usr/bin/git",
+];
+#[cfg(not(windows))]
+const GIT_PATH_EXTRA_DIRS: &[&str] = &[
+    "/opt/homebrew/bin",
+    "/opt/homebrew/sbin",
+    "/usr/local/bin",
+    "/usr/local/sbin",
+];
+
+impl GitError {
+    pub fn new(kind: GitErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// 非 Git 仓库。
+    pub fn not_a_repo() -> Self {
+        Self::new(GitErrorKind::NotARepo, "不是 Git 仓库")
+    }
+
+    /// 未归类的错误（参数校验失败等）。
+    pub fn raw(message: impl Into<String>) -> Self {
+        Self::new(GitErrorKind::Raw, message)
+    }
+
+    /// 按 git 输出内容归类错误，message 保留原始输出。
+    pub fn from_output(output: impl Into<String>) -> Self {
+        let output = output.into();
+        Self::new(classify_git_error(&output), output)
+    }
+}
+
+impl From<String> for GitError {
+    fn from(message: String) -> Self {
+        Self::from_output(message)
+    }
+}
+
+// 根据 git 的原始输出判断错误类别，无法识别时归为 Raw。
+fn classify_git_error(output: &str) -> GitErrorKind {
+    let lower = output.to_ascii_lowercase();
+    let contains_any = |patterns: &[&str]| patterns.iter().any(|pattern| lower.contains(pattern));
+
+    if contains_any(&["not a git repository"]) {
+        GitErrorKind::NotARepo
+    } else if contains_any(&[
+        "would be overwritten",
+        "please commit your changes or stash them",
+        "contains modified or untracked files",
+        "your local changes",
+    ]) {
+        GitErrorKind::DirtyWorktree
+    } else if contains_any(&["conflict", "unmerged", "needs merge"]) {
+        GitErrorKind::Conflict
+    } else if contains_any(&[
+        "could not read from remote repository",
+        "unable to access",
+        "authentication failed",
+        "could not resolve host",
+        "repository not found",
+        "failed to push",
+        "[rejected]",
+        "[remote rejected]",
+        "connection timed out",
+        "connection refused",
+    ]) {
+        GitErrorKind::RemoteError
+    } else if contains_any(&[
+        "invalid reference",
+        "not a valid object name",
+        "unknown revision",
+        "did not match any file(s) known to git",
+        "couldn't find remote ref",
+        "not a valid branch name",
+    ]) || (lower.contains("branch '") && lower.contains("not found"))
+    {
+        GitErrorKind::BranchNotFound
+    } else if output.starts_with("执行命令失败") {
+        GitErrorKind::Io
+    } else {
+        GitErrorKind::Raw
+    }
+}
+
 /// 列出仓库下所有分支名称。
 pub fn list_branches(base_path: &str) -> Vec<BranchListItem> {
     if !is_git_repo(base_path) {
@@ -44,1082 +147,3516 @@ pub fn is_git_repo(path: &str) -> bool {
     Path::new(path).join(".git").exists()
 }
 
+/// 在普通目录中初始化 Git 仓库（`git init -b <branch>`），可选写入 README 并完成首次提交。
+///
+/// identity 为空时使用 git 配置中的 user.name/user.email；返回初始化后的仓库状态。
+pub fn init_repo(
+    base_path: &str,
+    initial_branch: &str,
+    create_readme: bool,
+    identity: Option<&GitIdentity>,
+) -> Result<GitRepoStatus, GitError> {
+    let root = Path::new(base_path);
+    if !root.is_dir() {
+        return Err(GitError::raw("目录不存在"));
+    }
+    if is_git_repo(base_path) {
+        return Err(GitError::raw("目录已是 Git 仓库"));
+    }
+    let branch = initial_branch.trim();
+    let branch = if branch.is_empty() { "main" } else { branch };
+    let check = execute_git_command(base_path, &["check-ref-format", "--branch", branch]);
+    if !check.success {
+        return Err(GitError::raw(format!("分支名不合法: {branch}")));
+    }
+
+    let init = execute_git_command(base_path, &["init", "-b", branch]);
+    if !init.success {
+        return Err(GitError::from_output(format!(
+            "初始化仓库失败: {}",
+            init.output
+        )));
+    }
+
+    if create_readme {
+        let readme = root.join("README.md");
+        if !readme.exists() {
+            let name = root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Project".to_string());
+            fs::write(&readme, format!("# {name}\n"))
+                .map_err(|error| format!("写入 README 失败: {error}"))?;
+        }
+        let add = execute_git_command(base_path, &["add", "--", "README.md"]);
+        if !add.success {
+            return Err(GitError::from_output(format!(
+                "暂存 README 失败: {}",
+                add.output
+            )));
+        }
+
+        let name_arg = identity.map(|identity| format!("user.name={}", identity.name.trim()));
+        let email_arg = identity.map(|identity| format!("user.email={}", identity.email.trim()));
+        let mut args: Vec<&str> = Vec::new();
+        if let (Some(name_arg), Some(email_arg)) = (name_arg.as_deref(), email_arg.as_deref()) {
+            args.extend(["-c", name_arg, "-c", email_arg]);
+        }
+        args.extend(["commit", "-m", "Initial commit"]);
+        let commit = execute_git_command(base_path, &args);
+        if !commit.success {
+            let lower = commit.output.to_lowercase();
+            if lower.contains("please tell me who you are") || lower.contains("empty ident") {
+                return Err(GitError::raw(
+                    "首次提交失败：未配置提交身份（user.name/user.email）",
+                ));
+            }
+            return Err(GitError::from_output(format!(
+                "首次提交失败: {}",
+                commit.output
+            )));
+        }
+    }
+
+    invalidate_status_cache(base_path);
+    default_repo_status(base_path)
+}
+
 /// 获取仓库状态（staged/unstaged/untracked + 分支信息）。
-pub fn get_repo_status(base_path: &str) -> Result<GitRepoStatus, String> {
+///
+/// `untracked_mode` 控制未跟踪文件粒度；Normal 模式下未跟踪目录以 `dir/` 单条返回。
+pub fn get_repo_status(
+    base_path: &str,
+    untracked_mode: GitUntrackedMode,
+    renames: GitRenameDetection,
+) -> Result<GitRepoStatus, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
 
-    let result = execute_git_command(base_path, &["status", "--porcelain=v2", "-z", "-b"]);
+    let untracked_arg = match untracked_mode {
+        GitUntrackedMode::No => "-uno",
+        GitUntrackedMode::Normal => "-unormal",
+        GitUntrackedMode::All => "-uall",
+    };
+    let rename_arg = match (renames.detect_renames, renames.rename_threshold) {
+        (false, _) => "--no-renames".to_string(),
+        (true, Some(threshold)) => format!("--find-renames={}%", threshold.clamp(1, 100)),
+        (true, None) => "--find-renames".to_string(),
+    };
+    let result = execute_git_command(
+        base_path,
+        &[
+            "status",
+            "--porcelain=v2",
+            "-z",
+            "-b",
+            untracked_arg,
+            &rename_arg,
+        ],
+    );
     if !result.success {
-        return Err(result.output);
+        return Err(GitError::from_output(result.output));
     }
 
-    parse_porcelain_v2_status(&result.output)
+    parse_porcelain_v2_status(&result.output).map_err(GitError::raw)
 }
 
-/// 获取单文件对比用的原始/修改内容（用于 Monaco DiffEditor）。
-///
-/// - staged=true: original=HEAD:<old_or_current_path> modified=:<current_path>
-/// - staged=false: original=:<old_or_current_path> modified=工作区文件内容
-pub fn get_diff_contents(
-    base_path: &str,
-    relative_path: &str,
-    staged: bool,
-    old_relative_path: Option<&str>,
-) -> Result<GitDiffContents, String> {
+/// 仅计算当前分支相对上游的领先/落后数，比完整的 `get_repo_status` 轻量，适合频繁轮询。
+pub fn ahead_behind(base_path: &str) -> Result<GitAheadBehind, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
-    let relative_path = relative_path.trim();
-    if relative_path.is_empty() {
-        return Err("路径为空".to_string());
+    let upstream = execute_git_command(
+        base_path,
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+    );
+    if !upstream.success || upstream.output.is_empty() {
+        return Ok(GitAheadBehind::default());
     }
 
-    let old_path = old_relative_path
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .unwrap_or(relative_path);
+    let counts = execute_git_command(
+        base_path,
+        &["rev-list", "--left-right", "--count", "@{upstream}...HEAD"],
+    );
+    if !counts.success {
+        return Err(GitError::from_output(counts.output));
+    }
+    let (behind, ahead) = parse_left_right_counts(&counts.output);
+    Ok(GitAheadBehind {
+        ahead,
+        behind,
+        has_upstream: true,
+        upstream: Some(upstream.output),
+    })
+}
 
-    const MAX_FILE_BYTES: usize = 1_200_000;
+// 解析 `rev-list --left-right --count A...B` 的输出：左侧为 A 独有的提交（落后），右侧为 B 独有的提交（领先）。
+fn parse_left_right_counts(output: &str) -> (i32, i32) {
+    let mut parts = output.split_whitespace();
+    let left = parts
+        .next()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    let right = parts
+        .next()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    (left, right)
+}
 
-    let (original_bytes, original_truncated) = if staged {
-        let spec = format!("HEAD:{old_path}");
-        read_git_object_optional(base_path, &spec, MAX_FILE_BYTES)?
-    } else {
-        let spec = format!(":{old_path}");
-        read_git_object_optional(base_path, &spec, MAX_FILE_BYTES)?
-    };
+// 使用默认的未跟踪粒度与重命名检测读取状态，供写操作完成后返回最新状态。
+fn default_repo_status(base_path: &str) -> Result<GitRepoStatus, GitError> {
+    get_repo_status(
+        base_path,
+        GitUntrackedMode::default(),
+        GitRenameDetection::default(),
+    )
+}
 
-    let (modified_bytes, modified_truncated) = if staged {
-        let spec = format!(":{relative_path}");
-        read_git_object_optional(base_path, &spec, MAX_FILE_BYTES)?
-    } else {
-        read_worktree_file_optional(base_path, relative_path, MAX_FILE_BYTES)?
-    };
+/// 带短期缓存的仓库状态：同一路径与选项在 STATUS_CACHE_TTL 内复用上次结果，force=true 时强制重新读取。
+pub fn get_repo_status_cached(
+    base_path: &str,
+    untracked_mode: GitUntrackedMode,
+    renames: GitRenameDetection,
+    force: bool,
+) -> Result<GitRepoStatus, GitError> {
+    let key = normalize_status_cache_key(base_path);
+    if !force
+        && let Ok(cache) = status_cache().lock()
+        && let Some(cached) = cache.get(&key)
+        && cached.untracked_mode == untracked_mode
+        && cached.renames == renames
+        && cached.cached_at.elapsed() < STATUS_CACHE_TTL
+    {
+        return Ok(cached.status.clone());
+    }
 
-    let original = bytes_to_text(original_bytes)?;
-    let modified = bytes_to_text(modified_bytes)?;
+    let status = get_repo_status(base_path, untracked_mode, renames)?;
+    if let Ok(mut cache) = status_cache().lock() {
+        cache.insert(
+            key,
+            CachedRepoStatus {
+                untracked_mode,
+                renames,
+                cached_at: Instant::now(),
+                status: status.clone(),
+            },
+        );
+    }
+    Ok(status)
+}
 
-    Ok(GitDiffContents {
-        original,
-        modified,
-        original_truncated,
-        modified_truncated,
-    })
+/// 使指定路径的状态缓存失效（项目目录变更时调用）。
+pub fn invalidate_status_cache(base_path: &str) {
+    if let Ok(mut cache) = status_cache().lock() {
+        cache.remove(&normalize_status_cache_key(base_path));
+    }
 }
 
-/// 暂存文件（git add）。
-pub fn stage_files(base_path: &str, relative_paths: &[String]) -> Result<(), String> {
-    run_git_with_paths(base_path, ["add", "--"], relative_paths)
+struct CachedRepoStatus {
+    untracked_mode: GitUntrackedMode,
+    renames: GitRenameDetection,
+    cached_at: Instant,
+    status: GitRepoStatus,
 }
 
-/// 取消暂存（git reset HEAD -- <paths>）。
-pub fn unstage_files(base_path: &str, relative_paths: &[String]) -> Result<(), String> {
-    run_git_with_paths(base_path, ["reset", "HEAD", "--"], relative_paths)
+fn status_cache() -> &'static Mutex<HashMap<String, CachedRepoStatus>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CachedRepoStatus>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-/// 丢弃未暂存修改（git checkout -- <paths>）。
-pub fn discard_files(base_path: &str, relative_paths: &[String]) -> Result<(), String> {
-    run_git_with_paths(base_path, ["checkout", "--"], relative_paths)
+fn normalize_status_cache_key(base_path: &str) -> String {
+    let normalized = base_path.trim().replace('\\', "/");
+    normalized.trim_end_matches('/').to_string()
 }
 
-/// 提交已暂存改动（git commit -m）。
-pub fn commit(base_path: &str, message: &str) -> Result<(), String> {
+/// 获取 HEAD 信息（提交、分支、是否游离、指向的 tag）。
+pub fn head_info(base_path: &str) -> Result<GitHeadInfo, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
-    let message = message.trim();
-    if message.is_empty() {
-        return Err("提交信息不能为空".to_string());
+
+    let commit = execute_git_command(base_path, &["rev-parse", "HEAD"]);
+    if !commit.success {
+        return Err(GitError::raw("仓库尚无提交"));
     }
-    let result = execute_git_command(base_path, &["commit", "-m", message]);
-    if result.success {
-        Ok(())
+    let short_commit = execute_git_command(base_path, &["rev-parse", "--short", "HEAD"]);
+    let symbolic = execute_git_command(base_path, &["symbolic-ref", "-q", "--short", "HEAD"]);
+    let branch = if symbolic.success && !symbolic.output.is_empty() {
+        Some(symbolic.output)
     } else {
-        Err(result.output)
+        None
+    };
+    let tag = execute_git_command(base_path, &["describe", "--tags", "--exact-match", "HEAD"]);
+
+    Ok(GitHeadInfo {
+        short_commit: if short_commit.success {
+            short_commit.output
+        } else {
+            commit.output.chars().take(7).collect()
+        },
+        commit: commit.output,
+        detached: branch.is_none(),
+        branch,
+        tag: if tag.success && !tag.output.is_empty() {
+            Some(tag.output)
+        } else {
+            None
+        },
+    })
+}
+
+/// 检查仓库健康状况：是否残留 index.lock，以及 `git fsck --connectivity-only` 是否报告损坏。
+pub fn repo_health(base_path: &str) -> Result<GitRepoHealth, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
+
+    let lock_path = resolve_index_lock_path(base_path)?;
+    let has_index_lock = lock_path.exists();
+
+    let fsck = execute_git_command(
+        base_path,
+        &[
+            "fsck",
+            "--connectivity-only",
+            "--no-progress",
+            "--no-dangling",
+        ],
+    );
+    let fsck_errors = fsck
+        .output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter(|line| {
+            !fsck.success
+                || line.starts_with("error")
+                || line.starts_with("missing")
+                || line.starts_with("broken")
+        })
+        .map(str::to_string)
+        .collect();
+
+    Ok(GitRepoHealth {
+        has_index_lock,
+        index_lock_path: has_index_lock.then(|| lock_path.to_string_lossy().to_string()),
+        fsck_errors,
+    })
 }
 
-/// 切换分支（git checkout <branch>）。
-pub fn checkout_branch(base_path: &str, branch: &str) -> Result<(), String> {
+/// 统计仓库提交数、最后提交时间与贡献者数量；尚无提交的仓库返回全 0。
+pub fn repo_stats(base_path: &str) -> Result<GitRepoStats, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
-    let branch = branch.trim();
-    if branch.is_empty() {
-        return Err("分支名不能为空".to_string());
+    if !execute_git_command(base_path, &["rev-parse", "--verify", "--quiet", "HEAD"]).success {
+        return Ok(GitRepoStats::default());
     }
-    let result = execute_git_command(base_path, &["checkout", branch]);
-    if result.success {
-        Ok(())
-    } else {
-        Err(result.output)
+
+    let count = execute_git_command(base_path, &["rev-list", "--count", "HEAD"]);
+    if !count.success {
+        return Err(GitError::from_output(format!(
+            "统计提交数失败: {}",
+            count.output
+        )));
     }
+    let last = execute_git_command(base_path, &["log", "-1", "--format=%at", "HEAD"]);
+    if !last.success {
+        return Err(GitError::from_output(format!(
+            "读取最后提交时间失败: {}",
+            last.output
+        )));
+    }
+    // shortlog 未指定修订时会从 stdin 读取，必须显式传入 HEAD。
+    let shortlog = execute_git_command(base_path, &["shortlog", "-sne", "HEAD"]);
+    if !shortlog.success {
+        return Err(GitError::from_output(format!(
+            "统计贡献者失败: {}",
+            shortlog.output
+        )));
+    }
+
+    Ok(GitRepoStats {
+        commit_count: count.output.trim().parse().unwrap_or(0),
+        last_commit_ts: last.output.trim().parse::<i64>().unwrap_or(0) * 1000,
+        contributor_count: shortlog
+            .output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    })
 }
 
-/// 删除本地分支（git branch -d/-D）。
-pub fn delete_branch(base_path: &str, branch: &str, force: bool) -> Result<(), String> {
+/// 按提交数倒序列出贡献者，since 为 git 可识别的日期（如 `2024-01-01`、`3.months`）；尚无提交时返回空列表。
+pub fn contributors(base_path: &str, since: Option<&str>) -> Result<Vec<GitContributor>, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
-
-    let branch = branch.trim();
-    if branch.is_empty() {
-        return Err("分支名不能为空".to_string());
+    if !execute_git_command(base_path, &["rev-parse", "--verify", "--quiet", "HEAD"]).success {
+        return Ok(Vec::new());
     }
 
-    let args = if force {
-        ["branch", "-D", branch]
-    } else {
-        ["branch", "-d", branch]
-    };
+    let since_arg = since
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| format!("--since={value}"));
+    let mut args = vec!["shortlog", "-sne"];
+    if let Some(since_arg) = since_arg.as_deref() {
+        args.push(since_arg);
+    }
+    args.push("HEAD");
     let result = execute_git_command(base_path, &args);
-    if result.success {
-        Ok(())
-    } else {
-        Err(normalize_delete_branch_error(&result.output, force))
+    if !result.success {
+        return Err(GitError::from_output(format!(
+            "统计贡献者失败: {}",
+            result.output
+        )));
     }
+    Ok(parse_shortlog_output(&result.output))
 }
 
-/// 创建 Git worktree。
-///
-/// - create_branch=true: `git worktree add -b <branch> <target_path> [<start_point>]`
-/// - create_branch=false: `git worktree add <target_path> <branch>`
-pub fn add_worktree(
-    base_path: &str,
-    target_path: Option<&str>,
-    branch: &str,
-    create_branch: bool,
-    start_point: Option<&str>,
-) -> Result<GitWorktreeAddResult, String> {
+/// 删除残留的 index.lock；锁文件在 INDEX_LOCK_STALE_AFTER 内有更新时视为仍在使用，拒绝删除。
+pub fn remove_index_lock(base_path: &str) -> Result<bool, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
 
-    let branch = branch.trim();
-    if branch.is_empty() {
-        return Err("分支名不能为空".to_string());
+    let lock_path = resolve_index_lock_path(base_path)?;
+    let metadata = match fs::metadata(&lock_path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => {
+            return Err(GitError::new(
+                GitErrorKind::Io,
+                format!("读取 index.lock 失败: {error}"),
+            ));
+        }
+    };
+    let recently_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < INDEX_LOCK_STALE_AFTER);
+    if recently_modified {
+        return Err(GitError::raw(
+            "index.lock 刚被更新，可能有 Git 操作正在进行，请稍后重试",
+        ));
     }
 
-    let target_path = resolve_worktree_target_path(base_path, branch, target_path)?;
+    fs::remove_file(&lock_path).map_err(|error| format!("删除 index.lock 失败: {error}"))?;
+    invalidate_status_cache(base_path);
+    Ok(true)
+}
 
-    let target = Path::new(&target_path);
-    if target.exists() {
-        return Err("目标目录已存在，无法创建 worktree".to_string());
+// 通过 `git rev-parse --git-path` 定位 index.lock，兼容 worktree（.git 为文件）的情况。
+fn resolve_index_lock_path(base_path: &str) -> Result<PathBuf, String> {
+    let result = execute_git_command(base_path, &["rev-parse", "--git-path", "index.lock"]);
+    if !result.success {
+        return Err(result.output);
     }
+    let path = PathBuf::from(&result.output);
+    Ok(if path.is_absolute() {
+        path
+    } else {
+        Path::new(base_path).join(path)
+    })
+}
 
-    let parent = match target.parent() {
-        Some(value) => value,
-        None => return Err("目标路径非法".to_string()),
-    };
-    if !parent.exists() {
-        fs::create_dir_all(parent).map_err(|err| format!("创建目标目录失败: {err}"))?;
+/// 检测仓库是否处于 rebase/merge/cherry-pick/revert/bisect 进行中，以及 HEAD 是否游离。
+pub fn operation_state(base_path: &str) -> Result<GitOperationState, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-    if !parent.is_dir() {
-        return Err("目标目录的父路径不是文件夹".to_string());
+    // 使用 worktree 自身的 git 目录，进行中的操作标记文件按 worktree 独立存放。
+    let git_dir = execute_git_command(base_path, &["rev-parse", "--absolute-git-dir"]);
+    if !git_dir.success {
+        return Err(GitError::from_output(git_dir.output));
     }
+    let git_dir = PathBuf::from(git_dir.output);
+    let operation =
+        if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+            GitOperation::Rebase
+        } else if git_dir.join("MERGE_HEAD").exists() {
+            GitOperation::Merge
+        } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+            GitOperation::CherryPick
+        } else if git_dir.join("REVERT_HEAD").exists() {
+            GitOperation::Revert
+        } else if git_dir.join("BISECT_LOG").exists() {
+            GitOperation::Bisect
+        } else {
+            GitOperation::None
+        };
+    let detached = !execute_git_command(base_path, &["symbolic-ref", "-q", "HEAD"]).success;
+    Ok(GitOperationState {
+        operation,
+        detached,
+    })
+}
 
-    let mut args: Vec<&str> = vec!["worktree", "add"];
-    if create_branch {
-        let start_point = start_point.map(str::trim).filter(|value| !value.is_empty());
-        args.push("-b");
-        args.push(branch);
-        args.push(target_path.as_str());
-        if let Some(start_point) = start_point {
-            args.push(start_point);
+/// 继续进行中的 rebase/merge/cherry-pick/revert（冲突已解决后），返回最新仓库状态。
+pub fn continue_operation(base_path: &str) -> Result<GitRepoStatus, GitError> {
+    let args: &[&str] = match operation_state(base_path)?.operation {
+        GitOperation::None => return Err(GitError::raw("当前没有进行中的 Git 操作")),
+        GitOperation::Rebase => &["rebase", "--continue"],
+        GitOperation::Merge => &["merge", "--continue"],
+        GitOperation::CherryPick => &["cherry-pick", "--continue"],
+        GitOperation::Revert => &["revert", "--continue"],
+        GitOperation::Bisect => return Err(GitError::raw("bisect 无法继续，只能结束（abort）")),
+    };
+    // 沿用默认提交信息，避免 git 等待编辑器。
+    let mut command_args = vec!["-c", "core.editor=true"];
+    command_args.extend_from_slice(args);
+    run_operation_command(base_path, &command_args)
+}
+
+/// 中止进行中的 rebase/merge/cherry-pick/revert，或结束 bisect，返回最新仓库状态。
+pub fn abort_operation(base_path: &str) -> Result<GitRepoStatus, GitError> {
+    let args: &[&str] = match operation_state(base_path)?.operation {
+        GitOperation::None => return Err(GitError::raw("当前没有进行中的 Git 操作")),
+        GitOperation::Rebase => &["rebase", "--abort"],
+        GitOperation::Merge => &["merge", "--abort"],
+        GitOperation::CherryPick => &["cherry-pick", "--abort"],
+        GitOperation::Revert => &["revert", "--abort"],
+        GitOperation::Bisect => &["bisect", "reset"],
+    };
+    run_operation_command(base_path, args)
+}
+
+fn run_operation_command(base_path: &str, args: &[&str]) -> Result<GitRepoStatus, GitError> {
+    let result = execute_git_command(base_path, args);
+    invalidate_status_cache(base_path);
+    if !result.success {
+        let lower = result.output.to_ascii_lowercase();
+        if lower.contains("unmerged") || lower.contains("conflict") {
+            return Err(GitError::new(
+                GitErrorKind::Conflict,
+                "仍有未解决的冲突，请先解决并标记为已解决",
+            ));
         }
-    } else {
-        args.push(target_path.as_str());
-        args.push(branch);
+        return Err(GitError::from_output(result.output));
     }
+    default_repo_status(base_path)
+}
 
-    let result = execute_git_command(base_path, &args);
-    if result.success {
-        return Ok(GitWorktreeAddResult {
-            path: target_path.to_string(),
-            branch: branch.to_string(),
-        });
+/// 获取仓库提交时生效的 user.name/user.email 及其配置层级（仓库/全局/系统）。
+pub fn get_identity(base_path: &str) -> Result<GitRepoIdentity, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
 
-    Err(normalize_worktree_add_error(&result.output, create_branch))
+    let (name_scope, name) = read_config_with_scope(base_path, "user.name");
+    let (email_scope, email) = read_config_with_scope(base_path, "user.email");
+    Ok(GitRepoIdentity {
+        identity: GitIdentity { name, email },
+        name_scope,
+        email_scope,
+    })
 }
 
-/// 解析“新建分支”模式下的创建起点：远端优先，本地回退。
-///
-/// 返回值是可直接用于 `git worktree add -b ... <start_point>` 的引用。
-/// 优先返回 `origin/<base_branch>`，若不可用则回退 `<base_branch>`。
-pub fn resolve_create_branch_start_point(
+/// 设置仓库（local）或全局（global）的 user.name/user.email。
+pub fn set_identity(
     base_path: &str,
-    base_branch: &str,
-) -> Result<String, String> {
+    identity: &GitIdentity,
+    scope: GitConfigScope,
+) -> Result<(), GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
+    }
+    let scope_arg = match scope {
+        GitConfigScope::Local => "--local",
+        GitConfigScope::Global => "--global",
+        GitConfigScope::System | GitConfigScope::Worktree => {
+            return Err(GitError::raw("仅支持设置仓库或全局身份"));
+        }
+    };
+    let name = identity.name.trim();
+    if name.is_empty() {
+        return Err(GitError::raw("用户名不能为空"));
+    }
+    let email = identity.email.trim();
+    if !is_valid_email(email) {
+        return Err(GitError::raw(format!("邮箱格式不正确: {email}")));
+    }
+
+    for (key, value) in [("user.name", name), ("user.email", email)] {
+        let result = execute_git_command(base_path, &["config", scope_arg, key, value]);
+        if !result.success {
+            return Err(GitError::from_output(result.output));
+        }
+    }
+    Ok(())
+}
+
+// 最小化的邮箱校验：单个 `@`、两侧非空、域名含 `.`，且不含空白。
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.chars().any(char::is_whitespace)
+}
+
+// 读取配置项及其来源层级；未设置时返回空值。
+fn read_config_with_scope(base_path: &str, key: &str) -> (Option<GitConfigScope>, String) {
+    let result = execute_git_command(base_path, &["config", "--show-scope", "--get", key]);
+    if !result.success {
+        return (None, String::new());
     }
+    parse_config_scope_output(&result.output)
+}
+
+// 解析 `git config --show-scope --get` 的输出：`<scope>\t<value>`。
+fn parse_config_scope_output(output: &str) -> (Option<GitConfigScope>, String) {
+    let Some((scope, value)) = output.split_once('\t') else {
+        return (None, output.trim().to_string());
+    };
+    let scope = match scope.trim() {
+        "system" => Some(GitConfigScope::System),
+        "global" => Some(GitConfigScope::Global),
+        "local" => Some(GitConfigScope::Local),
+        "worktree" => Some(GitConfigScope::Worktree),
+        _ => None,
+    };
+    (scope, value.trim().to_string())
+}
 
+/// 获取当前 HEAD 相对于与 base_branch 分叉点的变更文件（`git diff --name-status <merge-base> HEAD`）。
+pub fn diff_against_base(base_path: &str, base_branch: &str) -> Result<GitBaseDiff, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
     let base_branch = base_branch.trim();
     if base_branch.is_empty() {
-        return Err("基线分支不可用：分支名不能为空".to_string());
+        return Err(GitError::raw("基准分支不能为空"));
     }
 
-    let remote_ref = format!("origin/{base_branch}");
+    let merge_base = execute_git_command(base_path, &["merge-base", "HEAD", base_branch]);
+    if !merge_base.success {
+        // 无共同祖先时 git merge-base 以非零退出且没有输出。
+        if merge_base.output.is_empty() {
+            return Err(GitError::raw(format!(
+                "当前分支与 {base_branch} 没有共同祖先"
+            )));
+        }
+        return Err(GitError::from_output(merge_base.output));
+    }
+    let merge_base = merge_base.output;
+
+    let result = execute_git_command(
+        base_path,
+        &[
+            "diff",
+            "--name-status",
+            "-z",
+            "--find-renames",
+            &merge_base,
+            "HEAD",
+        ],
+    );
+    if !result.success {
+        return Err(GitError::from_output(result.output));
+    }
 
-    if has_origin_remote(base_path) {
-        match branch_exists_on_remote(base_path, base_branch) {
-            RemoteBranchCheck::Exists => {
-                let fetch_error = fetch_origin_branch(base_path, base_branch).err();
-                if ref_exists_locally(base_path, &remote_ref) {
-                    if let Some(err) = fetch_error {
-                        log::warn!(
-                            "刷新远端基线分支失败，改用本地缓存引用 {}: {}",
-                            remote_ref,
-                            err
-                        );
-                    }
-                    return Ok(remote_ref);
-                }
+    Ok(GitBaseDiff {
+        files: parse_name_status_output(&result.output),
+        merge_base,
+    })
+}
 
-                if ref_exists_locally(base_path, base_branch) {
-                    return Ok(base_branch.to_string());
-                }
+/// 获取单文件对比用的原始/修改内容（用于 Monaco DiffEditor）。
+///
+/// - staged=true: original=HEAD:<old_or_current_path> modified=:<current_path>
+/// - staged=false: original=:<old_or_current_path> modified=工作区文件内容
+pub fn get_diff_contents(
+    base_path: &str,
+    relative_path: &str,
+    staged: bool,
+    old_relative_path: Option<&str>,
+    options: &GitDiffOptions,
+) -> Result<GitDiffContents, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let relative_path = relative_path.trim();
+    if relative_path.is_empty() {
+        return Err(GitError::raw("路径为空"));
+    }
 
-                if let Some(err) = fetch_error {
-                    return Err(format!(
-                        "基线分支不可用：远端分支 {} 刷新失败，且本地不存在同名分支（{}）",
-                        remote_ref, err
-                    ));
-                }
+    let old_path = old_relative_path
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .unwrap_or(relative_path);
 
-                return Err(format!(
-                    "基线分支不可用：远端分支 {} 无法在本地解析",
-                    remote_ref
-                ));
-            }
-            RemoteBranchCheck::NotFound => {
-                if ref_exists_locally(base_path, base_branch) {
-                    return Ok(base_branch.to_string());
-                }
-                return Err(format!(
-                    "基线分支不可用：远端与本地均不存在分支 {}",
-                    base_branch
-                ));
-            }
-            RemoteBranchCheck::Error(error) => {
-                if ref_exists_locally(base_path, base_branch) {
-                    log::warn!(
-                        "远端基线分支校验失败，回退本地分支 {}: {}",
-                        base_branch,
-                        error
-                    );
-                    return Ok(base_branch.to_string());
-                }
-                return Err(format!(
-                    "基线分支不可用：无法校验远端分支 {}，且本地不存在同名分支（{}）",
-                    base_branch, error
-                ));
-            }
-        }
-    }
+    let (original_bytes, original_truncated) = if staged {
+        let spec = format!("HEAD:{old_path}");
+        read_git_object_optional(base_path, &spec, MAX_DIFF_FILE_BYTES)?
+    } else {
+        let spec = format!(":{old_path}");
+        read_git_object_optional(base_path, &spec, MAX_DIFF_FILE_BYTES)?
+    };
 
-    if ref_exists_locally(base_path, base_branch) {
-        return Ok(base_branch.to_string());
+    let (modified_bytes, modified_truncated) = if staged {
+        let spec = format!(":{relative_path}");
+        read_git_object_optional(base_path, &spec, MAX_DIFF_FILE_BYTES)?
+    } else {
+        read_worktree_file_optional(base_path, relative_path, MAX_DIFF_FILE_BYTES)?
+    };
+
+    let original = bytes_to_text(original_bytes)?;
+    let modified = bytes_to_text(modified_bytes)?;
+    let eol_only = original != modified
+        && normalize_line_endings(&original) == normalize_line_endings(&modified);
+    let original = normalize_diff_text(original, options);
+    let modified = normalize_diff_text(modified, options);
+    let conflict_regions = if staged {
+        Vec::new()
+    } else {
+        parse_conflict_regions(&modified)
+    };
+
+    Ok(GitDiffContents {
+        original,
+        modified,
+        original_truncated,
+        modified_truncated,
+        conflict: !conflict_regions.is_empty(),
+        conflict_regions,
+        eol_only,
+    })
+}
+
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+// 按选项归一化对比文本；仅移除行尾的空格/制表符，不改变行数，便于行号与原文件对应。
+fn normalize_diff_text(text: String, options: &GitDiffOptions) -> String {
+    let text = if options.ignore_eol {
+        normalize_line_endings(&text)
+    } else {
+        text
+    };
+    if !options.ignore_trailing_whitespace {
+        return text;
     }
 
-    Err(format!("基线分支不可用：未找到本地分支 {}", base_branch))
+    let mut normalized = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let (body, ending) = if let Some(body) = line.strip_suffix("\r\n") {
+            (body, "\r\n")
+        } else if let Some(body) = line.strip_suffix('\n') {
+            (body, "\n")
+        } else {
+            (line, "")
+        };
+        normalized.push_str(body.trim_end_matches([' ', '\t']));
+        normalized.push_str(ending);
+    }
+    normalized
 }
 
-pub fn resolve_worktree_target_path(
+/// 获取冲突文件在索引中的三方内容（:1: base / :2: ours / :3: theirs），缺失的一方返回 None。
+pub fn get_conflict_contents(
     base_path: &str,
-    branch: &str,
-    target_path: Option<&str>,
-) -> Result<String, String> {
-    let branch = branch.trim();
-    if branch.is_empty() {
-        return Err("分支名不能为空".to_string());
+    relative_path: &str,
+) -> Result<GitConflictContents, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let relative_path = relative_path.trim();
+    if relative_path.is_empty() {
+        return Err(GitError::raw("路径为空"));
     }
 
-    Ok(target_path
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(ToString::to_string)
-        .unwrap_or(resolve_default_worktree_path(base_path, branch)?))
+    let read_stage = |stage: u8| -> Result<(Option<String>, bool), String> {
+        let spec = format!(":{stage}:{relative_path}");
+        let (bytes, truncated) = read_git_object_optional(base_path, &spec, MAX_DIFF_FILE_BYTES)?;
+        match bytes {
+            Some(bytes) => Ok((Some(bytes_to_text(Some(bytes))?), truncated)),
+            None => Ok((None, false)),
+        }
+    };
+
+    let (base, base_truncated) = read_stage(1)?;
+    let (ours, ours_truncated) = read_stage(2)?;
+    let (theirs, theirs_truncated) = read_stage(3)?;
+    if base.is_none() && ours.is_none() && theirs.is_none() {
+        return Err(GitError::raw("文件不处于冲突状态"));
+    }
+
+    Ok(GitConflictContents {
+        base,
+        ours,
+        theirs,
+        base_truncated,
+        ours_truncated,
+        theirs_truncated,
+    })
 }
 
-/// 列出仓库下已有 worktree（不包含主仓库目录）。
-pub fn list_worktrees(base_path: &str) -> Result<Vec<GitWorktreeListItem>, String> {
+/// 获取改动统计（git diff --numstat），staged=true 时统计暂存区。
+pub fn diff_stat(base_path: &str, staged: bool) -> Result<Vec<GitDiffStatEntry>, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
 
-    let result = execute_git_command(base_path, &["worktree", "list", "--porcelain"]);
+    let mut args = vec!["diff", "--numstat", "-z"];
+    if staged {
+        args.push("--cached");
+    }
+    let result = execute_git_command(base_path, &args);
     if !result.success {
-        return Err(result.output);
+        return Err(GitError::from_output(result.output));
     }
 
-    Ok(parse_worktree_list_output(base_path, &result.output))
+    Ok(parse_numstat_output(&result.output))
 }
 
-/// 删除 Git worktree（git worktree remove）。
-pub fn remove_worktree(base_path: &str, worktree_path: &str, force: bool) -> Result<(), String> {
+/// 暂存文件（git add）。
+pub fn stage_files(base_path: &str, relative_paths: &[String]) -> Result<(), GitError> {
+    run_git_with_paths(base_path, ["add", "--"], relative_paths)
+}
+
+/// 标记冲突已解决：先检查文件中不再包含冲突标记，再执行 git add。
+pub fn mark_resolved(base_path: &str, relative_paths: &[String]) -> Result<(), GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
 
-    let worktree_path = worktree_path.trim();
-    if worktree_path.is_empty() {
-        return Err("worktree 路径不能为空".to_string());
+    let mut unresolved = Vec::new();
+    for relative_path in relative_paths {
+        let (bytes, _) =
+            read_worktree_file_optional(base_path, relative_path, MAX_DIFF_FILE_BYTES)?;
+        let Some(bytes) = bytes else {
+            continue;
+        };
+        if looks_binary(&bytes) {
+            continue;
+        }
+        if !parse_conflict_regions(&String::from_utf8_lossy(&bytes)).is_empty() {
+            unresolved.push(relative_path.as_str());
+        }
     }
-
-    let base_normalized = normalize_path_for_compare(base_path);
-    let worktree_normalized = normalize_path_for_compare(worktree_path);
-    if base_normalized == worktree_normalized {
-        return Err("不能删除主仓库目录".to_string());
+    if !unresolved.is_empty() {
+        return Err(GitError::new(
+            GitErrorKind::Conflict,
+            format!("以下文件仍包含冲突标记: {}", unresolved.join(", ")),
+        ));
     }
 
-    // 先校验 worktree 是否存在于该仓库，避免误删任意目录。
-    let listed = list_worktrees(base_path)?
-        .into_iter()
-        .any(|item| normalize_path_for_compare(&item.path) == worktree_normalized);
-    if !listed {
-        return Err("worktree 不存在或已移除".to_string());
-    }
+    stage_files(base_path, relative_paths)
+}
 
-    let mut args: Vec<&str> = vec!["worktree", "remove"];
-    if force {
-        args.push("--force");
+/// 暂存全部改动（git add -A），返回最新仓库状态。
+pub fn stage_all(base_path: &str) -> Result<GitRepoStatus, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-    args.push(worktree_path);
+    let result = execute_git_command(base_path, &["add", "-A"]);
+    invalidate_status_cache(base_path);
+    if !result.success {
+        return Err(GitError::from_output(result.output));
+    }
+    default_repo_status(base_path)
+}
 
-    let result = execute_git_command(base_path, &args);
-    if result.success {
-        return Ok(());
+/// 取消暂存全部改动（git reset），尚无提交时改用 `git rm --cached`，返回最新仓库状态。
+pub fn unstage_all(base_path: &str) -> Result<GitRepoStatus, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let has_head =
+        execute_git_command(base_path, &["rev-parse", "--verify", "--quiet", "HEAD"]).success;
+    let args: &[&str] = if has_head {
+        &["reset", "-q"]
+    } else {
+        &["rm", "-r", "-q", "--cached", "--ignore-unmatch", "--", "."]
+    };
+    let result = execute_git_command(base_path, args);
+    invalidate_status_cache(base_path);
+    if !result.success {
+        return Err(GitError::from_output(result.output));
     }
+    default_repo_status(base_path)
+}
 
-    // 若用户手动删除了目录，git 可能会提示 not a working tree；这时尝试 prune 清理残留记录。
-    let lower = result.output.to_ascii_lowercase();
-    if lower.contains("not a working tree")
-        || lower.contains("is missing")
-        || lower.contains("no such file or directory")
-    {
-        let prune = execute_git_command(base_path, &["worktree", "prune"]);
-        if prune.success {
-            let remaining = list_worktrees(base_path)?
-                .into_iter()
-                .any(|item| normalize_path_for_compare(&item.path) == worktree_normalized);
-            if !remaining {
-                return Ok(());
-            }
+/// 丢弃全部未暂存修改（git checkout -- .），include_untracked 时再执行 `git clean -fd`。
+///
+/// 操作不可恢复，confirm 必须为 true；返回最新仓库状态。
+pub fn discard_all(
+    base_path: &str,
+    confirm: bool,
+    include_untracked: bool,
+) -> Result<GitRepoStatus, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    if !confirm {
+        return Err(GitError::raw("丢弃全部修改不可恢复，需要确认后执行"));
+    }
+    let has_tracked = !execute_git_command(base_path, &["ls-files", "-z"])
+        .output
+        .is_empty();
+    if has_tracked {
+        let result = execute_git_command(base_path, &["checkout", "--", "."]);
+        if !result.success {
+            invalidate_status_cache(base_path);
+            return Err(GitError::from_output(result.output));
         }
     }
-
-    Err(normalize_worktree_remove_error(&result.output, force))
+    if include_untracked {
+        let result = execute_git_command(base_path, &["clean", "-fd"]);
+        if !result.success {
+            invalidate_status_cache(base_path);
+            return Err(GitError::from_output(result.output));
+        }
+    }
+    invalidate_status_cache(base_path);
+    default_repo_status(base_path)
 }
 
-fn resolve_default_worktree_path(base_path: &str, branch: &str) -> Result<String, String> {
-    let home = resolve_home_dir().ok_or_else(|| "无法解析用户主目录".to_string())?;
-    let repo_name = resolve_repo_name(base_path);
-    let normalized_branch = branch
-        .replace('\\', "/")
-        .split('/')
-        .filter(|segment| !segment.trim().is_empty())
-        .collect::<Vec<_>>()
-        .join("/");
+/// 删除未跟踪文件与目录（git clean -fd），include_ignored 时追加 `-x` 一并删除被忽略的文件。
+///
+/// dry_run=true 时只返回将被删除的列表（`git clean -n`）；实际删除必须显式 confirm。
+/// paths 为空时作用于整个仓库。
+pub fn clean(
+    base_path: &str,
+    include_ignored: bool,
+    dry_run: bool,
+    paths: &[String],
+    confirm: bool,
+) -> Result<GitCleanResult, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    if !dry_run && !confirm {
+        return Err(GitError::raw("删除未跟踪文件不可恢复，需要确认后执行"));
+    }
 
-    if normalized_branch.is_empty() {
-        return Err("分支名不能为空".to_string());
+    let result = execute_git_command(base_path, &clean_args(include_ignored, dry_run, paths));
+    if !dry_run {
+        invalidate_status_cache(base_path);
+    }
+    if !result.success {
+        return Err(GitError::from_output(result.output));
     }
 
-    Ok(home
-        .join(".devhaven")
-        .join("worktrees")
-        .join(repo_name)
-        .join(normalized_branch)
-        .to_string_lossy()
-        .to_string())
+    Ok(GitCleanResult {
+        performed: !dry_run,
+        files: parse_clean_output(&result.output),
+    })
 }
 
-fn has_origin_remote(base_path: &str) -> bool {
-    let result = execute_git_command(base_path, &["remote", "get-url", "origin"]);
-    result.success
+/// 暂存单个 hunk：将统一 diff 补丁应用到暂存区（git apply --cached）。
+pub fn stage_hunk(base_path: &str, relative_path: &str, patch: &str) -> Result<(), GitError> {
+    apply_patch_to_index(base_path, relative_path, patch, false)
 }
 
-enum RemoteBranchCheck {
-    Exists,
-    NotFound,
-    Error(String),
+/// 取消暂存单个 hunk（git apply --cached --reverse）。
+pub fn unstage_hunk(base_path: &str, relative_path: &str, patch: &str) -> Result<(), GitError> {
+    apply_patch_to_index(base_path, relative_path, patch, true)
 }
 
-fn branch_exists_on_remote(base_path: &str, branch: &str) -> RemoteBranchCheck {
-    let output = Command::new(resolve_git_executable())
-        .args(["ls-remote", "--exit-code", "--heads", "origin", branch])
-        .current_dir(base_path)
-        .output();
+/// 取消暂存（git reset HEAD -- <paths>）。
+pub fn unstage_files(base_path: &str, relative_paths: &[String]) -> Result<(), GitError> {
+    run_git_with_paths(base_path, ["reset", "HEAD", "--"], relative_paths)
+}
 
-    match output {
-        Ok(output) => {
-            if output.status.success() {
-                return RemoteBranchCheck::Exists;
-            }
+/// 丢弃未暂存修改（git checkout -- <paths>）。
+///
+/// 仅用暂存区内容覆盖工作区，已暂存的改动会保留；需要回到 HEAD 状态请使用 `restore_file`。
+pub fn discard_files(base_path: &str, relative_paths: &[String]) -> Result<(), GitError> {
+    run_git_with_paths(base_path, ["checkout", "--"], relative_paths)
+}
 
-            if output.status.code() == Some(2) {
-                return RemoteBranchCheck::NotFound;
-            }
+/// 将文件恢复到 HEAD 状态（git restore --source=HEAD）。
+///
+/// - staged=true: 恢复暂存区（等同取消暂存该文件的改动）
+/// - worktree=true: 恢复工作区
+/// - 两者都为 true 时彻底回到 HEAD，与只处理未暂存改动的 `discard_files` 不同
+pub fn restore_file(
+    base_path: &str,
+    relative_path: &str,
+    staged: bool,
+    worktree: bool,
+) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let relative_path = relative_path.trim();
+    if relative_path.is_empty() {
+        return Err(GitError::raw("路径为空"));
+    }
+    if !staged && !worktree {
+        return Err(GitError::raw("请至少选择恢复暂存区或工作区"));
+    }
 
-            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            let message = if stderr.is_empty() {
-                stdout
-            } else if stdout.is_empty() {
-                stderr
-            } else {
-                format!("{stdout}\n{stderr}").trim().to_string()
-            };
-            RemoteBranchCheck::Error(message)
-        }
-        Err(err) => RemoteBranchCheck::Error(format!("执行命令失败: {err}")),
+    let mut args = vec!["restore", "--source=HEAD"];
+    if staged {
+        args.push("--staged");
     }
-}
+    if worktree {
+        args.push("--worktree");
+    }
+    args.push("--");
+    args.push(relative_path);
 
-fn fetch_origin_branch(base_path: &str, branch: &str) -> Result<(), String> {
-    let result = execute_git_command(base_path, &["fetch", "origin", branch]);
+    let result = execute_git_command(base_path, &args);
     if result.success {
-        return Ok(());
+        Ok(())
+    } else {
+        Err(GitError::from_output(result.output))
     }
-    Err(result.output)
 }
 
-fn ref_exists_locally(base_path: &str, reference: &str) -> bool {
+/// 从指定引用检出单个文件（git checkout <ref> -- <path>），同时更新暂存区与工作区。
+/// 文件存在未提交改动时需 force=true 才会覆盖。
+pub fn checkout_file(
+    base_path: &str,
+    reference: &str,
+    relative_path: &str,
+    force: bool,
+) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
     let reference = reference.trim();
     if reference.is_empty() {
-        return false;
+        return Err(GitError::raw("引用不能为空"));
     }
-    let commit_ref = format!("{reference}^{{commit}}");
-    let output = Command::new(resolve_git_executable())
-        .args(["rev-parse", "--verify", "--quiet", commit_ref.as_str()])
-        .current_dir(base_path)
-        .output();
-    match output {
-        Ok(output) => output.status.success(),
-        Err(_) => false,
+    let relative_path = relative_path.trim();
+    if relative_path.is_empty() {
+        return Err(GitError::raw("路径为空"));
     }
-}
-
-fn resolve_repo_name(base_path: &str) -> String {
-    let fallback = "repository".to_string();
-    let raw = Path::new(base_path)
-        .file_name()
-        .and_then(|value| value.to_str())
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .map(ToString::to_string)
-        .unwrap_or_else(|| fallback.clone());
 
-    let sanitized: String = raw
-        .chars()
-        .map(|ch| {
-            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
-                ch
-            } else {
-                '-'
-            }
-        })
-        .collect();
-
-    let result = sanitized.trim_matches(['-', '.', '_']);
-    if result.is_empty() {
-        fallback
-    } else {
-        result.to_string()
+    let spec = format!("{reference}^{{commit}}");
+    let resolved = execute_git_command(base_path, &["rev-parse", "--verify", "--quiet", &spec]);
+    if !resolved.success || resolved.output.is_empty() {
+        return Err(GitError::new(
+            GitErrorKind::BranchNotFound,
+            format!("无法解析引用: {reference}"),
+        ));
     }
-}
+    let commit = resolved.output;
 
-fn resolve_home_dir() -> Option<PathBuf> {
-    if let Some(home) = std::env::var_os("HOME") {
-        if !home.is_empty() {
-            return Some(PathBuf::from(home));
-        }
+    let (object, _) = read_git_object_optional(base_path, &format!("{commit}:{relative_path}"), 1)?;
+    if object.is_none() {
+        return Err(GitError::raw(format!(
+            "{reference} 中不存在文件: {relative_path}"
+        )));
     }
 
-    if cfg!(windows) {
-        if let Some(profile) = std::env::var_os("USERPROFILE") {
-            if !profile.is_empty() {
-                return Some(PathBuf::from(profile));
-            }
+    if !force {
+        let status =
+            execute_git_command(base_path, &["status", "--porcelain", "--", relative_path]);
+        if !status.success {
+            return Err(GitError::from_output(status.output));
         }
-        match (std::env::var_os("HOMEDRIVE"), std::env::var_os("HOMEPATH")) {
-            (Some(drive), Some(path)) if !drive.is_empty() && !path.is_empty() => {
-                let mut result = PathBuf::from(drive);
-                result.push(path);
-                return Some(result);
-            }
-            _ => {}
+        if !status.output.is_empty() {
+            return Err(GitError::new(
+                GitErrorKind::DirtyWorktree,
+                format!("文件存在未提交改动，将被覆盖: {relative_path}"),
+            ));
         }
     }
 
-    None
+    let result = execute_git_command(base_path, &["checkout", &commit, "--", relative_path]);
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::from_output(result.output))
+    }
 }
 
-fn parse_worktree_list_output(base_path: &str, output: &str) -> Vec<GitWorktreeListItem> {
-    let base_normalized = normalize_path_for_compare(base_path);
-    let mut items: Vec<GitWorktreeListItem> = Vec::new();
-
-    let mut current_path: Option<String> = None;
-    let mut current_branch: Option<String> = None;
-    let mut current_detached = false;
-
-    let mut flush_current =
-        |path: &mut Option<String>, branch: &mut Option<String>, detached: &mut bool| {
-            let Some(path_value) = path.take() else {
-                *branch = None;
-                *detached = false;
-                return;
-            };
-
-            let branch_value = branch.take().unwrap_or_default();
-            let normalized_path = normalize_path_for_compare(&path_value);
-            let is_base = normalized_path == base_normalized;
-
-            if !is_base && !*detached && !branch_value.is_empty() {
-                items.push(GitWorktreeListItem {
-                    path: path_value,
-                    branch: branch_value,
-                });
-            }
-            *detached = false;
-        };
-
-    for raw_line in output.lines() {
-        let line = raw_line.trim();
-
-        if line.is_empty() {
-            flush_current(
-                &mut current_path,
-                &mut current_branch,
-                &mut current_detached,
-            );
-            continue;
-        }
-
-        if let Some(path_value) = line.strip_prefix("worktree ") {
-            flush_current(
-                &mut current_path,
-                &mut current_branch,
-                &mut current_detached,
-            );
-            current_path = Some(path_value.trim().to_string());
-            continue;
-        }
-
-        if let Some(branch_ref) = line.strip_prefix("branch ") {
-            let branch_name = branch_ref
-                .trim()
-                .strip_prefix("refs/heads/")
-                .unwrap_or(branch_ref.trim());
-            current_branch = Some(branch_name.to_string());
-            continue;
-        }
+/// 提交信息校验规则（来自设置，默认不做额外限制）。
+#[derive(Debug, Clone, Default)]
+pub struct CommitMessageRules {
+    /// 标题最大长度，0 表示不限制。
+    pub subject_max_len: u32,
+    /// 是否要求填写正文。
+    pub require_body: bool,
+}
 
-        if line == "detached" {
-            current_detached = true;
+impl CommitMessageRules {
+    /// 根据设置生成规则：模板在标题之后还有非注释内容时视为要求正文。
+    pub fn from_settings(template: Option<&str>, subject_max_len: u32) -> Self {
+        let require_body = template
+            .map(|value| {
+                value
+                    .lines()
+                    .skip(1)
+                    .any(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            })
+            .unwrap_or(false);
+        Self {
+            subject_max_len,
+            require_body,
         }
     }
-
-    flush_current(
-        &mut current_path,
-        &mut current_branch,
-        &mut current_detached,
-    );
-
-    items.sort_by(|left, right| left.path.cmp(&right.path));
-    items
 }
 
-fn normalize_path_for_compare(path: &str) -> String {
-    let trimmed = path.trim();
-    if trimmed.is_empty() {
-        return String::new();
+/// 提交已暂存改动（git commit -m）；skip_hooks=true 时追加 `--no-verify` 跳过 hook。
+pub fn commit(
+    base_path: &str,
+    message: &str,
+    rules: &CommitMessageRules,
+    skip_hooks: bool,
+) -> Result<(), CommitValidationError> {
+    if !is_git_repo(base_path) {
+        return Err(commit_error(
+            CommitValidationCode::CommitFailed,
+            "不是 Git 仓库",
+        ));
     }
-
-    // 优先用 canonicalize 消除软链接/大小写差异（例如 macOS 的 /var -> /private/var），避免比较失败。
-    let mut normalized = trimmed.replace('\\', "/").trim_end_matches('/').to_string();
-    if let Ok(canonical) = std::fs::canonicalize(&normalized) {
-        normalized = canonical
-            .to_string_lossy()
-            .replace('\\', "/")
-            .trim_end_matches('/')
-            .to_string();
+    let message = message.trim();
+    validate_commit_message(message, rules)?;
+    let mut args = vec!["commit", "-m", message];
+    if skip_hooks {
+        log::warn!("git commit 跳过 hooks path={}", base_path);
+        args.push("--no-verify");
     }
-    if cfg!(windows) {
-        normalized.to_ascii_lowercase()
+    let result = execute_git_command(base_path, &args);
+    if result.success {
+        Ok(())
     } else {
-        normalized
+        Err(commit_error(
+            CommitValidationCode::CommitFailed,
+            &result.output,
+        ))
     }
 }
 
-fn normalize_worktree_add_error(raw: &str, create_branch: bool) -> String {
-    let lower = raw.to_ascii_lowercase();
+/// 手动执行仓库中的 Git hook（例如 pre-commit/pre-push），返回 hook 输出。
+pub fn run_hook(base_path: &str, hook_name: &str) -> Result<String, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let hook_name = hook_name.trim();
+    if hook_name.is_empty()
+        || !hook_name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch == '-')
+    {
+        return Err(GitError::raw(format!("hook 名称不合法: {hook_name}")));
+    }
 
-    if lower.contains("already checked out") || lower.contains("already used by worktree") {
-        return "该分支已在其他 worktree 检出，请切换分支或先移除旧 worktree".to_string();
+    // --git-path 会考虑 core.hooksPath 与 worktree 场景。
+    let hook_spec = format!("hooks/{hook_name}");
+    let resolved = execute_git_command(base_path, &["rev-parse", "--git-path", &hook_spec]);
+    if !resolved.success {
+        return Err(GitError::from_output(resolved.output));
+    }
+    let hook_path = Path::new(base_path).join(resolved.output.trim());
+    if !hook_path.is_file() {
+        return Err(GitError::raw(format!("未配置 {hook_name} hook")));
     }
 
-    if create_branch && lower.contains("already exists") && lower.contains("branch") {
-        return "分支已存在，请改用“已有分支”模式或更换分支名".to_string();
+    // GUI 启动时 PATH 往往缺少 Homebrew 等目录，与 git 调用保持一致，保证 hook 能找到 node/npx 等命令。
+    let mut command = Command::new(&hook_path);
+    apply_normalized_path(&mut command);
+    let output = command
+        .current_dir(base_path)
+        .output()
+        .map_err(|err| format!("执行 hook 失败: {err}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let combined = format!("{stdout}\n{stderr}").trim().to_string();
+    if output.status.success() {
+        Ok(combined)
+    } else {
+        Err(GitError::from_output(combined))
     }
+}
 
-    if lower.contains("already exists") {
-        return "目标目录已存在，无法创建 worktree".to_string();
+/// 按规则校验提交信息（标题长度、正文是否必填）。
+pub fn validate_commit_message(
+    message: &str,
+    rules: &CommitMessageRules,
+) -> Result<(), CommitValidationError> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(commit_error(
+            CommitValidationCode::EmptyMessage,
+            "提交信息不能为空",
+        ));
     }
 
-    if lower.contains("not a git repository") {
-        return "不是 Git 仓库".to_string();
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("").trim();
+    let subject_len = subject.chars().count();
+    if rules.subject_max_len > 0 && subject_len > rules.subject_max_len as usize {
+        return Err(commit_error(
+            CommitValidationCode::SubjectTooLong,
+            &format!(
+                "提交标题过长（{subject_len}/{}），请精简后重试",
+                rules.subject_max_len
+            ),
+        ));
     }
 
-    if lower.contains("invalid reference")
-        || lower.contains("unknown revision")
-        || lower.contains("not a valid object name")
-        || lower.contains("pathspec")
-    {
-        return "分支不存在或不可用，请检查分支名称".to_string();
+    if rules.require_body && lines.all(|line| line.trim().is_empty()) {
+        return Err(commit_error(
+            CommitValidationCode::MissingBody,
+            "提交模板要求填写正文（标题后空一行再填写）",
+        ));
     }
 
-    raw.to_string()
+    Ok(())
 }
 
-fn normalize_worktree_remove_error(raw: &str, force: bool) -> String {
-    let lower = raw.to_ascii_lowercase();
+fn commit_error(code: CommitValidationCode, message: &str) -> CommitValidationError {
+    CommitValidationError {
+        code,
+        message: message.to_string(),
+    }
+}
 
-    if lower.contains("not a git repository") {
-        return "不是 Git 仓库".to_string();
+/// 重置当前分支到指定引用（git reset --soft/--mixed/--hard）。
+///
+/// hard 模式需显式确认；未确认时只返回将被丢弃改动的文件列表，不执行重置。
+pub fn reset(
+    base_path: &str,
+    reference: &str,
+    mode: GitResetMode,
+    confirm_hard: bool,
+) -> Result<GitResetResult, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let reference = reference.trim();
+    if reference.is_empty() {
+        return Err(GitError::raw("引用不能为空"));
     }
 
-    if lower.contains("not a working tree") {
-        return "worktree 不存在或已移除".to_string();
+    let spec = format!("{reference}^{{commit}}");
+    let resolved = execute_git_command(base_path, &["rev-parse", "--verify", "--quiet", &spec]);
+    if !resolved.success || resolved.output.is_empty() {
+        return Err(GitError::new(
+            GitErrorKind::BranchNotFound,
+            format!("无法解析引用: {reference}"),
+        ));
     }
+    let commit = resolved.output;
 
-    if lower.contains("contains modified or untracked files")
-        || lower.contains("cannot remove a dirty worktree")
-        || (lower.contains("dirty") && lower.contains("worktree"))
-    {
-        if force {
-            return "强制删除失败：worktree 可能被进程占用或被锁定，请先关闭相关终端/编辑器后重试"
-                .to_string();
+    let mut discarded_files = Vec::new();
+    if mode == GitResetMode::Hard {
+        let diff = execute_git_command(base_path, &["diff", "--name-only", "-z", &commit]);
+        if !diff.success {
+            return Err(GitError::from_output(diff.output));
+        }
+        discarded_files = diff
+            .output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| path.to_string())
+            .collect();
+        if !confirm_hard {
+            return Ok(GitResetResult {
+                performed: false,
+                commit,
+                discarded_files,
+            });
         }
-        return "该 worktree 存在未提交修改，无法删除。请先提交/清理，或使用“强制删除”".to_string();
     }
 
-    if lower.contains("locked") && lower.contains("worktree") {
-        return "worktree 已锁定，无法删除。可先执行 git worktree unlock 后重试".to_string();
+    let mode_arg = match mode {
+        GitResetMode::Soft => "--soft",
+        GitResetMode::Mixed => "--mixed",
+        GitResetMode::Hard => "--hard",
+    };
+    let result = execute_git_command(base_path, &["reset", mode_arg, &commit]);
+    if !result.success {
+        return Err(GitError::from_output(result.output));
     }
 
-    raw.to_string()
+    Ok(GitResetResult {
+        performed: true,
+        commit,
+        discarded_files,
+    })
 }
 
-fn normalize_delete_branch_error(raw: &str, force: bool) -> String {
-    let lower = raw.to_ascii_lowercase();
-
-    if lower.contains("not a git repository") {
-        return "不是 Git 仓库".to_string();
+/// 切换分支（git checkout <branch>）。
+pub fn checkout_branch(base_path: &str, branch: &str) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-
-    if lower.contains("not found") && lower.contains("branch") {
-        return "分支不存在或已删除".to_string();
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err(GitError::raw("分支名不能为空"));
     }
-
-    if lower.contains("checked out") {
-        return "分支正在当前仓库或其他 worktree 中使用，无法删除".to_string();
+    let result = execute_git_command(base_path, &["checkout", branch]);
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::from_output(result.output))
     }
+}
 
-    if !force && lower.contains("not fully merged") {
-        return "分支包含未合并提交，无法删除。请先合并后重试".to_string();
+/// 删除本地分支（git branch -d/-D）。
+pub fn delete_branch(base_path: &str, branch: &str, force: bool) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
 
-    raw.to_string()
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err(GitError::raw("分支名不能为空"));
+    }
+
+    let args = if force {
+        ["branch", "-D", branch]
+    } else {
+        ["branch", "-d", branch]
+    };
+    let result = execute_git_command(base_path, &args);
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::new(
+            classify_git_error(&result.output),
+            normalize_delete_branch_error(&result.output, force),
+        ))
+    }
 }
 
-// 执行 Git 命令并统一输出格式。
-fn execute_git_command(path: &str, args: &[&str]) -> GitCommandResult {
-    let output = Command::new(resolve_git_executable())
-        .args(args)
-        .current_dir(path)
-        .output();
+/// 列出仓库所有 tag（按创建时间倒序）。
+pub fn list_tags(base_path: &str) -> Result<Vec<GitTagItem>, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
 
-    match output {
-        Ok(output) => {
-            let success = output.status.success();
-            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-            let combined = if success {
-                stdout
-            } else if stderr.is_empty() {
-                stdout
-            } else {
-                format!("{stdout}\n{stderr}").trim().to_string()
-            };
-            GitCommandResult {
-                success,
-                output: combined,
-            }
-        }
-        Err(err) => GitCommandResult {
-            success: false,
-            output: format!("执行命令失败: {err}"),
-        },
+    let result = execute_git_command(
+        base_path,
+        &[
+            "tag",
+            "-l",
+            "--sort=-creatordate",
+            "--format=%(refname:short)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(contents:subject)",
+        ],
+    );
+    if !result.success {
+        return Err(GitError::from_output(result.output));
     }
-}
 
-fn is_git_show_not_found(stderr: &str) -> bool {
-    let msg = stderr.to_ascii_lowercase();
-    msg.contains("does not exist")
-        || msg.contains("not in the index")
-        || msg.contains("exists on disk, but not in the index")
-        || msg.contains("invalid object name")
-        || msg.contains("ambiguous argument")
+    Ok(parse_tag_list_output(&result.output))
 }
 
-fn looks_binary(bytes: &[u8]) -> bool {
-    bytes.iter().take(8000).any(|b| *b == 0)
-}
+/// 创建 tag；annotated=true 时创建附注 tag（git tag -a -m）。
+pub fn create_tag(
+    base_path: &str,
+    name: &str,
+    message: Option<&str>,
+    annotated: bool,
+) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(GitError::raw("Tag 名不能为空"));
+    }
 
-fn bytes_to_text(bytes: Option<Vec<u8>>) -> Result<String, String> {
-    match bytes {
-        None => Ok(String::new()),
-        Some(bytes) => {
-            if looks_binary(&bytes) {
-                return Err("检测到二进制文件，无法以文本对比展示。".to_string());
-            }
-            Ok(String::from_utf8_lossy(&bytes).to_string())
+    let message = message.map(str::trim).unwrap_or("");
+    let result = if annotated {
+        if message.is_empty() {
+            return Err(GitError::raw("附注 tag 需要填写说明"));
         }
+        execute_git_command(base_path, &["tag", "-a", name, "-m", message])
+    } else {
+        execute_git_command(base_path, &["tag", name])
+    };
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::new(
+            classify_git_error(&result.output),
+            normalize_tag_error(&result.output, name),
+        ))
     }
 }
 
-fn read_git_object_optional(
-    base_path: &str,
-    spec: &str,
-    max_bytes: usize,
-) -> Result<(Option<Vec<u8>>, bool), String> {
-    let output = Command::new(resolve_git_executable())
-        .args(["show", spec])
-        .current_dir(base_path)
-        .output()
-        .map_err(|err| format!("执行命令失败: {err}"))?;
-
-    if output.status.success() {
-        let (bytes, truncated) = truncate_bytes(output.stdout, max_bytes);
-        return Ok((Some(bytes), truncated));
+/// 删除本地 tag（git tag -d）。
+pub fn delete_tag(base_path: &str, name: &str) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    if is_git_show_not_found(&stderr) {
-        return Ok((None, false));
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(GitError::raw("Tag 名不能为空"));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if stderr.is_empty() {
-        Err(stdout)
-    } else if stdout.is_empty() {
-        Err(stderr)
+    let result = execute_git_command(base_path, &["tag", "-d", name]);
+    if result.success {
+        Ok(())
     } else {
-        Err(format!("{stdout}\n{stderr}").trim().to_string())
+        Err(GitError::new(
+            classify_git_error(&result.output),
+            normalize_tag_error(&result.output, name),
+        ))
     }
 }
 
-fn read_worktree_file_optional(
-    base_path: &str,
-    relative_path: &str,
-    max_bytes: usize,
-) -> Result<(Option<Vec<u8>>, bool), String> {
-    use std::io::Read;
-
-    let path = Path::new(base_path).join(relative_path);
-    let file = match fs::File::open(&path) {
-        Ok(file) => file,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok((None, false)),
-        Err(err) => return Err(format!("读取文件失败: {err}")),
-    };
-
-    let mut buf: Vec<u8> = Vec::new();
-    let mut handle = file.take((max_bytes as u64) + 1);
-    handle
-        .read_to_end(&mut buf)
-        .map_err(|err| format!("读取文件失败: {err}"))?;
+/// 列出远程仓库及其 fetch/push 地址。
+pub fn list_remotes(base_path: &str) -> Result<Vec<GitRemote>, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let result = execute_git_command(base_path, &["remote", "-v"]);
+    if !result.success {
+        return Err(GitError::from_output(result.output));
+    }
+    Ok(parse_remote_list_output(&result.output))
+}
 
-    let truncated = buf.len() > max_bytes;
-    if truncated {
-        buf.truncate(max_bytes);
+/// 添加远程仓库（git remote add）。
+pub fn add_remote(base_path: &str, name: &str, url: &str) -> Result<(), GitError> {
+    let (name, url) = validate_remote_args(base_path, name, url)?;
+    let result = execute_git_command(base_path, &["remote", "add", name, url]);
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::new(
+            classify_git_error(&result.output),
+            normalize_remote_error(&result.output, name),
+        ))
     }
-    Ok((Some(buf), truncated))
 }
 
-fn truncate_bytes(mut bytes: Vec<u8>, max_bytes: usize) -> (Vec<u8>, bool) {
-    if bytes.len() <= max_bytes {
-        return (bytes, false);
+/// 修改远程仓库地址（git remote set-url），同时作用于 fetch 与 push。
+pub fn set_remote_url(base_path: &str, name: &str, url: &str) -> Result<(), GitError> {
+    let (name, url) = validate_remote_args(base_path, name, url)?;
+    let result = execute_git_command(base_path, &["remote", "set-url", name, url]);
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::new(
+            classify_git_error(&result.output),
+            normalize_remote_error(&result.output, name),
+        ))
     }
-    bytes.truncate(max_bytes);
-    (bytes, true)
 }
 
-fn resolve_git_executable() -> &'static str {
-    static BIN: OnceLock<String> = OnceLock::new();
-    BIN.get_or_init(|| {
-        if Path::new("/usr/bin/git").exists() {
-            "/usr/bin/git".to_string()
-        } else {
-            "git".to_string()
-        }
-    })
-    .as_str()
+fn validate_remote_args<'a>(
+    base_path: &str,
+    name: &'a str,
+    url: &'a str,
+) -> Result<(&'a str, &'a str), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(GitError::raw("远程名称不能为空"));
+    }
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(GitError::raw("远程地址不能为空"));
+    }
+    Ok((name, url))
 }
 
-fn run_git_with_paths<const N: usize>(
+/// 创建 Git worktree。
+///
+/// - create_branch=true: `git worktree add -b <branch> <target_path> [<start_point>]`
+/// - create_branch=false: `git worktree add <target_path> <branch>`
+pub fn add_worktree(
     base_path: &str,
-    prefix_args: [&str; N],
-    relative_paths: &[String],
-) -> Result<(), String> {
+    target_path: Option<&str>,
+    branch: &str,
+    create_branch: bool,
+    start_point: Option<&str>,
+) -> Result<GitWorktreeAddResult, GitError> {
     if !is_git_repo(base_path) {
-        return Err("不是 Git 仓库".to_string());
+        return Err(GitError::not_a_repo());
     }
-    if relative_paths.is_empty() {
-        return Ok(());
+
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err(GitError::raw("分支名不能为空"));
     }
 
-    let output = Command::new(resolve_git_executable())
-        .args(prefix_args)
-        .args(relative_paths)
-        .current_dir(base_path)
-        .output()
-        .map_err(|err| format!("执行命令失败: {err}"))?;
+    let target_path = resolve_worktree_target_path(base_path, branch, target_path)?;
 
-    if output.status.success() {
-        return Ok(());
+    let target = Path::new(&target_path);
+    if target.exists() {
+        return Err(GitError::raw("目标目录已存在，无法创建 worktree"));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    if stderr.is_empty() {
-        Err(stdout)
-    } else if stdout.is_empty() {
-        Err(stderr)
+    let parent = match target.parent() {
+        Some(value) => value,
+        None => return Err(GitError::raw("目标路径非法")),
+    };
+    if !parent.exists() {
+        fs::create_dir_all(parent).map_err(|err| format!("创建目标目录失败: {err}"))?;
+    }
+    if !parent.is_dir() {
+        return Err(GitError::raw("目标目录的父路径不是文件夹"));
+    }
+
+    let mut args: Vec<&str> = vec!["worktree", "add"];
+    if create_branch {
+        let start_point = start_point.map(str::trim).filter(|value| !value.is_empty());
+        args.push("-b");
+        args.push(branch);
+        args.push(target_path.as_str());
+        if let Some(start_point) = start_point {
+            args.push(start_point);
+        }
     } else {
-        Err(format!("{stdout}\n{stderr}").trim().to_string())
+        args.push(target_path.as_str());
+        args.push(branch);
+    }
+
+    let result = execute_git_command(base_path, &args);
+    if result.success {
+        return Ok(GitWorktreeAddResult {
+            path: target_path.to_string(),
+            branch: branch.to_string(),
+        });
     }
+
+    Err(GitError::new(
+        classify_git_error(&result.output),
+        normalize_worktree_add_error(&result.output, create_branch),
+    ))
 }
 
-fn parse_porcelain_v2_status(output: &str) -> Result<GitRepoStatus, String> {
-    let mut branch = String::new();
-    let mut upstream: Option<String> = None;
-    let mut ahead: i32 = 0;
-    let mut behind: i32 = 0;
-    let mut staged: Vec<GitChangedFile> = Vec::new();
-    let mut unstaged: Vec<GitChangedFile> = Vec::new();
-    let mut untracked: Vec<GitChangedFile> = Vec::new();
+/// 解析“新建分支”模式下的创建起点：远端优先，本地回退。
+///
+/// 返回值是可直接用于 `git worktree add -b ... <start_point>` 的引用。
+/// 优先返回 `origin/<base_branch>`，若不可用则回退 `<base_branch>`。
+pub fn resolve_create_branch_start_point(
+    base_path: &str,
+    base_branch: &str,
+) -> Result<String, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
 
-    let parts: Vec<&str> = output.split('\0').collect();
-    let mut index = 0usize;
-    while index < parts.len() {
-        let record = parts[index];
-        if record.is_empty() {
-            index += 1;
-            continue;
-        }
+    let base_branch = base_branch.trim();
+    if base_branch.is_empty() {
+        return Err(GitError::raw("基线分支不可用：分支名不能为空"));
+    }
 
-        if let Some(rest) = record.strip_prefix("# ") {
-            if let Some(value) = rest.strip_prefix("branch.head ") {
-                branch = value.trim().to_string();
-            } else if let Some(value) = rest.strip_prefix("branch.upstream ") {
-                let value = value.trim();
-                if !value.is_empty() {
-                    upstream = Some(value.to_string());
-                }
-            } else if let Some(value) = rest.strip_prefix("branch.ab ") {
-                // Format: +A -B
-                let mut iter = value.split_whitespace();
-                let ahead_token = iter.next().unwrap_or("");
-                let behind_token = iter.next().unwrap_or("");
-                ahead = ahead_token
-                    .trim_start_matches('+')
-                    .parse::<i32>()
-                    .unwrap_or(0);
-                behind = behind_token
-                    .trim_start_matches('-')
-                    .parse::<i32>()
-                    .unwrap_or(0);
-            }
-            index += 1;
-            continue;
-        }
+    let remote_ref = format!("origin/{base_branch}");
 
-        let record_type = record.chars().next().unwrap_or(' ');
-        match record_type {
-            '1' => {
-                parse_change_record(record, 8, None, &mut staged, &mut unstaged);
-            }
-            '2' => {
-                let old_path = parts.get(index + 1).map(|value| (*value).to_string());
-                parse_change_record(record, 9, old_path, &mut staged, &mut unstaged);
-                // Record type 2 uses an extra NUL-delimited pathname
-                index += 1;
-            }
-            '?' => {
-                // Format: "? <path>"
-                if let Some(path) = record.strip_prefix("? ") {
-                    let path = path.trim();
-                    if !path.is_empty() {
-                        untracked.push(GitChangedFile {
-                            path: path.to_string(),
-                            old_path: None,
-                            status: GitFileStatus::Untracked,
-                        });
+    if has_origin_remote(base_path) {
+        match branch_exists_on_remote(base_path, base_branch) {
+            RemoteBranchCheck::Exists => {
+                let fetch_error = fetch_origin_branch(base_path, base_branch).err();
+                if ref_exists_locally(base_path, &remote_ref) {
+                    if let Some(err) = fetch_error {
+                        log::warn!(
+                            "刷新远端基线分支失败，改用本地缓存引用 {}: {}",
+                            remote_ref,
+                            err
+                        );
                     }
+                    return Ok(remote_ref);
+                }
+
+                if ref_exists_locally(base_path, base_branch) {
+                    return Ok(base_branch.to_string());
+                }
+
+                if let Some(err) = fetch_error {
+                    return Err(GitError::new(
+                        GitErrorKind::RemoteError,
+                        format!(
+                            "基线分支不可用：远端分支 {} 刷新失败，且本地不存在同名分支（{}）",
+                            remote_ref, err
+                        ),
+                    ));
+                }
+
+                return Err(GitError::new(
+                    GitErrorKind::BranchNotFound,
+                    format!("基线分支不可用：远端分支 {} 无法在本地解析", remote_ref),
+                ));
+            }
+            RemoteBranchCheck::NotFound => {
+                if ref_exists_locally(base_path, base_branch) {
+                    return Ok(base_branch.to_string());
                 }
+                return Err(GitError::new(
+                    GitErrorKind::BranchNotFound,
+                    format!("基线分支不可用：远端与本地均不存在分支 {}", base_branch),
+                ));
             }
-            // ignore ignored files: "! <path>"
-            '!' => {}
-            _ => {
-                // Unrecognized record type; ignore to avoid breaking the UI.
+            RemoteBranchCheck::Error(error) => {
+                if ref_exists_locally(base_path, base_branch) {
+                    log::warn!(
+                        "远端基线分支校验失败，回退本地分支 {}: {}",
+                        base_branch,
+                        error
+                    );
+                    return Ok(base_branch.to_string());
+                }
+                return Err(GitError::new(
+                    GitErrorKind::RemoteError,
+                    format!(
+                        "基线分支不可用：无法校验远端分支 {}，且本地不存在同名分支（{}）",
+                        base_branch, error
+                    ),
+                ));
             }
         }
+    }
 
-        index += 1;
+    if ref_exists_locally(base_path, base_branch) {
+        return Ok(base_branch.to_string());
     }
 
+    Err(GitError::new(
+        GitErrorKind::BranchNotFound,
+        format!("基线分支不可用：未找到本地分支 {}", base_branch),
+    ))
+}
+
+pub fn resolve_worktree_target_path(
+    base_path: &str,
+    branch: &str,
+    target_path: Option<&str>,
+) -> Result<String, GitError> {
+    let branch = branch.trim();
     if branch.is_empty() {
-        branch = "HEAD".to_string();
+        return Err(GitError::raw("分支名不能为空"));
     }
 
-    Ok(GitRepoStatus {
-        branch,
-        upstream,
+    Ok(target_path
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .unwrap_or(resolve_default_worktree_path(base_path, branch)?))
+}
+
+/// 列出仓库下已有 worktree（不包含主仓库目录）。
+pub fn list_worktrees(base_path: &str) -> Result<Vec<GitWorktreeListItem>, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+
+    let result = execute_git_command(base_path, &["worktree", "list", "--porcelain"]);
+    if !result.success {
+        return Err(GitError::from_output(result.output));
+    }
+
+    Ok(parse_worktree_list_output(base_path, &result.output))
+}
+
+/// 在仓库的 worktree 列表中查找指定路径（不包含主仓库目录）。
+pub fn find_worktree(
+    base_path: &str,
+    worktree_path: &str,
+) -> Result<GitWorktreeListItem, GitError> {
+    let worktree_normalized = normalize_path_for_compare(worktree_path);
+    list_worktrees(base_path)?
+        .into_iter()
+        .find(|item| normalize_path_for_compare(&item.path) == worktree_normalized)
+        .ok_or_else(|| GitError::raw("worktree 不存在或已移除"))
+}
+
+/// 统计 worktree 当前分支相对基线分支的领先/落后提交数。
+pub fn worktree_divergence(
+    base_path: &str,
+    worktree_path: &str,
+    base_branch: &str,
+) -> Result<GitWorktreeDivergence, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+
+    let worktree_path = worktree_path.trim();
+    if worktree_path.is_empty() {
+        return Err(GitError::raw("worktree 路径不能为空"));
+    }
+    let base_branch = base_branch.trim();
+    if base_branch.is_empty() {
+        return Err(GitError::raw("基准分支不能为空"));
+    }
+
+    let worktree = find_worktree(base_path, worktree_path)?;
+
+    let base_ref = format!("{base_branch}^{{commit}}");
+    let resolved = execute_git_command(
+        worktree_path,
+        &["rev-parse", "--verify", "--quiet", &base_ref],
+    );
+    if !resolved.success {
+        return Err(GitError::new(
+            GitErrorKind::BranchNotFound,
+            format!("无法解析引用: {base_branch}"),
+        ));
+    }
+
+    let range = format!("{}...HEAD", resolved.output);
+    let counts = execute_git_command(
+        worktree_path,
+        &["rev-list", "--left-right", "--count", &range],
+    );
+    if !counts.success {
+        return Err(GitError::from_output(counts.output));
+    }
+    let (behind, ahead) = parse_left_right_counts(&counts.output);
+
+    Ok(GitWorktreeDivergence {
+        worktree_path: worktree.path,
+        branch: worktree.branch,
+        base_branch: base_branch.to_string(),
         ahead,
         behind,
-        staged,
-        unstaged,
-        untracked,
     })
 }
 
-fn parse_change_record(
-    record: &str,
-    path_token_start_index: usize,
-    old_path: Option<String>,
-    staged: &mut Vec<GitChangedFile>,
-    unstaged: &mut Vec<GitChangedFile>,
-) {
-    let tokens: Vec<&str> = record.split_whitespace().collect();
-    if tokens.len() < 2 {
-        return;
+/// 删除 Git worktree（git worktree remove）。
+pub fn remove_worktree(base_path: &str, worktree_path: &str, force: bool) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-    let xy = tokens[1];
-    let x = xy.chars().next().unwrap_or('.');
-    let y = xy.chars().nth(1).unwrap_or('.');
 
-    if tokens.len() <= path_token_start_index {
-        return;
+    let worktree_path = worktree_path.trim();
+    if worktree_path.is_empty() {
+        return Err(GitError::raw("worktree 路径不能为空"));
     }
-    let path_tokens = &tokens[path_token_start_index..];
-    let path = path_tokens.join(" ").trim().to_string();
-    if path.is_empty() {
-        return;
+
+    let base_normalized = normalize_path_for_compare(base_path);
+    let worktree_normalized = normalize_path_for_compare(worktree_path);
+    if base_normalized == worktree_normalized {
+        return Err(GitError::raw("不能删除主仓库目录"));
     }
 
-    if x != '.' && x != ' ' {
-        staged.push(GitChangedFile {
-            path: path.clone(),
-            old_path: old_path.clone(),
-            status: map_git_status_char(x),
-        });
+    // 先校验 worktree 是否存在于该仓库，避免误删任意目录。
+    let listed = list_worktrees(base_path)?
+        .into_iter()
+        .any(|item| normalize_path_for_compare(&item.path) == worktree_normalized);
+    if !listed {
+        return Err(GitError::raw("worktree 不存在或已移除"));
     }
 
-    if y != '.' && y != ' ' {
-        unstaged.push(GitChangedFile {
-            path,
-            old_path,
-            status: map_git_status_char(y),
-        });
+    let mut args: Vec<&str> = vec!["worktree", "remove"];
+    if force {
+        args.push("--force");
     }
-}
+    args.push(worktree_path);
 
-fn map_git_status_char(value: char) -> GitFileStatus {
-    match value {
-        'A' => GitFileStatus::Added,
-        'M' => GitFileStatus::Modified,
-        'D' => GitFileStatus::Deleted,
-        'R' => GitFileStatus::Renamed,
-        'C' => GitFileStatus::Copied,
-        '?' => GitFileStatus::Untracked,
-        _ => GitFileStatus::Modified,
+    let result = execute_git_command(base_path, &args);
+    if result.success {
+        return Ok(());
     }
-}
 
-fn resolve_default_branch(branches: &[String], base_path: &str) -> Option<String> {
-    let symbolic = execute_git_command(base_path, &["symbolic-ref", "refs/remotes/origin/HEAD"]);
-    if symbolic.success {
-        if let Some(name) = symbolic.output.split('/').last() {
-            let name = name.trim();
-            if branches.iter().any(|branch| branch == name) {
-                return Some(name.to_string());
+    // 若用户手动删除了目录，git 可能会提示 not a working tree；这时尝试 prune 清理残留记录。
+    let lower = result.output.to_ascii_lowercase();
+    if lower.contains("not a working tree")
+        || lower.contains("is missing")
+        || lower.contains("no such file or directory")
+    {
+        let prune = execute_git_command(base_path, &["worktree", "prune"]);
+        if prune.success {
+            let remaining = list_worktrees(base_path)?
+                .into_iter()
+                .any(|item| normalize_path_for_compare(&item.path) == worktree_normalized);
+            if !remaining {
+                return Ok(());
             }
         }
     }
 
-    if branches.iter().any(|branch| branch == "main") {
-        return Some("main".to_string());
+    Err(GitError::new(
+        classify_git_error(&result.output),
+        normalize_worktree_remove_error(&result.output, force),
+    ))
+}
+
+/// 清理目录已被手动删除的 worktree 残留记录（git worktree prune）；dry_run 时仅返回将被清理的记录。
+pub fn prune_worktrees(base_path: &str, dry_run: bool) -> Result<GitWorktreePruneResult, GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
     }
-    if branches.iter().any(|branch| branch == "master") {
-        return Some("master".to_string());
+
+    // prune -v 的明细输出在 stderr，execute_git_command 成功时只保留 stdout，这里单独读取。
+    let output = git_command()
+        .args(worktree_prune_args(dry_run))
+        .current_dir(base_path)
+        .output()
+        .map_err(|err| GitError::new(GitErrorKind::Io, format!("执行命令失败: {err}")))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !output.status.success() {
+        return Err(GitError::from_output(
+            format!("{stdout}\n{stderr}").trim().to_string(),
+        ));
     }
 
-    None
+    Ok(GitWorktreePruneResult {
+        performed: !dry_run,
+        entries: parse_worktree_prune_output(&stderr),
+    })
 }
 
-struct GitCommandResult {
-    success: bool,
-    output: String,
+// `Removing ...` 明细只有英文原文可解析，依赖 git_command() 固定的提示语言。
+fn worktree_prune_args(dry_run: bool) -> Vec<&'static str> {
+    let mut args = vec!["worktree", "prune", "-v"];
+    if dry_run {
+        args.push("-n");
+    }
+    args
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        add_worktree, delete_branch, is_git_repo, list_worktrees, parse_worktree_list_output,
-        remove_worktree, resolve_create_branch_start_point, resolve_git_executable,
-    };
-    use std::fs;
-    use std::path::Path;
-    use std::process::Command;
+fn resolve_default_worktree_path(base_path: &str, branch: &str) -> Result<String, String> {
+    let home = resolve_home_dir().ok_or_else(|| "无法解析用户主目录".to_string())?;
+    let repo_name = resolve_repo_name(base_path);
+    let normalized_branch = branch
+        .replace('\\', "/")
+        .split('/')
+        .filter(|segment| !segment.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
 
-    fn git(path: &Path, args: &[&str]) -> Result<String, String> {
-        let output = Command::new(resolve_git_executable())
-            .args(args)
-            .current_dir(path)
-            .output()
-            .map_err(|err| format!("执行 git 失败: {err}"))?;
+    if normalized_branch.is_empty() {
+        return Err("分支名不能为空".to_string());
+    }
+
+    Ok(home
+        .join(".devhaven")
+        .join("worktrees")
+        .join(repo_name)
+        .join(normalized_branch)
+        .to_string_lossy()
+        .to_string())
+}
+
+fn has_origin_remote(base_path: &str) -> bool {
+    let result = execute_git_command(base_path, &["remote", "get-url", "origin"]);
+    result.success
+}
+
+enum RemoteBranchCheck {
+    Exists,
+    NotFound,
+    Error(String),
+}
+
+fn branch_exists_on_remote(base_path: &str, branch: &str) -> RemoteBranchCheck {
+    let output = git_command()
+        .args(["ls-remote", "--exit-code", "--heads", "origin", branch])
+        .current_dir(base_path)
+        .output();
+
+    match output {
+        Ok(output) => {
+            if output.status.success() {
+                return RemoteBranchCheck::Exists;
+            }
+
+            if output.status.code() == Some(2) {
+                return RemoteBranchCheck::NotFound;
+            }
+
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let message = if stderr.is_empty() {
+                stdout
+            } else if stdout.is_empty() {
+                stderr
+            } else {
+                format!("{stdout}\n{stderr}").trim().to_string()
+            };
+            RemoteBranchCheck::Error(message)
+        }
+        Err(err) => RemoteBranchCheck::Error(format!("执行命令失败: {err}")),
+    }
+}
+
+fn fetch_origin_branch(base_path: &str, branch: &str) -> Result<(), String> {
+    let result = execute_git_command(base_path, &["fetch", "origin", branch]);
+    if result.success {
+        return Ok(());
+    }
+    Err(result.output)
+}
+
+fn ref_exists_locally(base_path: &str, reference: &str) -> bool {
+    let reference = reference.trim();
+    if reference.is_empty() {
+        return false;
+    }
+    let commit_ref = format!("{reference}^{{commit}}");
+    let output = git_command()
+        .args(["rev-parse", "--verify", "--quiet", commit_ref.as_str()])
+        .current_dir(base_path)
+        .output();
+    match output {
+        Ok(output) => output.status.success(),
+        Err(_) => false,
+    }
+}
+
+fn resolve_repo_name(base_path: &str) -> String {
+    let fallback = "repository".to_string();
+    let raw = Path::new(base_path)
+        .file_name()
+        .and_then(|value| value.to_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| fallback.clone());
+
+    let sanitized: String = raw
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    let result = sanitized.trim_matches(['-', '.', '_']);
+    if result.is_empty() {
+        fallback
+    } else {
+        result.to_string()
+    }
+}
+
+fn resolve_home_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("HOME") {
+        if !home.is_empty() {
+            return Some(PathBuf::from(home));
+        }
+    }
+
+    if cfg!(windows) {
+        if let Some(profile) = std::env::var_os("USERPROFILE") {
+            if !profile.is_empty() {
+                return Some(PathBuf::from(profile));
+            }
+        }
+        match (std::env::var_os("HOMEDRIVE"), std::env::var_os("HOMEPATH")) {
+            (Some(drive), Some(path)) if !drive.is_empty() && !path.is_empty() => {
+                let mut result = PathBuf::from(drive);
+                result.push(path);
+                return Some(result);
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+// 解析 `git worktree prune -v` 输出中的 `Removing worktrees/<name>: <reason>` 行。
+fn parse_worktree_prune_output(output: &str) -> Vec<GitWorktreePruneEntry> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Removing "))
+        .filter_map(|entry| {
+            let (name, reason) = entry.split_once(": ").unwrap_or((entry, ""));
+            let name = name.trim();
+            (!name.is_empty()).then(|| GitWorktreePruneEntry {
+                name: name.to_string(),
+                reason: reason.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_worktree_list_output(base_path: &str, output: &str) -> Vec<GitWorktreeListItem> {
+    let base_normalized = normalize_path_for_compare(base_path);
+    let mut items: Vec<GitWorktreeListItem> = Vec::new();
+
+    let mut current_path: Option<String> = None;
+    let mut current_branch: Option<String> = None;
+    let mut current_detached = false;
+
+    let mut flush_current =
+        |path: &mut Option<String>, branch: &mut Option<String>, detached: &mut bool| {
+            let Some(path_value) = path.take() else {
+                *branch = None;
+                *detached = false;
+                return;
+            };
+
+            let branch_value = branch.take().unwrap_or_default();
+            let normalized_path = normalize_path_for_compare(&path_value);
+            let is_base = normalized_path == base_normalized;
+
+            if !is_base && !*detached && !branch_value.is_empty() {
+                items.push(GitWorktreeListItem {
+                    path: path_value,
+                    branch: branch_value,
+                });
+            }
+            *detached = false;
+        };
+
+    for raw_line in output.lines() {
+        let line = raw_line.trim();
+
+        if line.is_empty() {
+            flush_current(
+                &mut current_path,
+                &mut current_branch,
+                &mut current_detached,
+            );
+            continue;
+        }
+
+        if let Some(path_value) = line.strip_prefix("worktree ") {
+            flush_current(
+                &mut current_path,
+                &mut current_branch,
+                &mut current_detached,
+            );
+            current_path = Some(path_value.trim().to_string());
+            continue;
+        }
+
+        if let Some(branch_ref) = line.strip_prefix("branch ") {
+            let branch_name = branch_ref
+                .trim()
+                .strip_prefix("refs/heads/")
+                .unwrap_or(branch_ref.trim());
+            current_branch = Some(branch_name.to_string());
+            continue;
+        }
+
+        if line == "detached" {
+            current_detached = true;
+        }
+    }
+
+    flush_current(
+        &mut current_path,
+        &mut current_branch,
+        &mut current_detached,
+    );
+
+    items.sort_by(|left, right| left.path.cmp(&right.path));
+    items
+}
+
+fn normalize_path_for_compare(path: &str) -> String {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return String::new();
+    }
+
+    // 优先用 canonicalize 消除软链接/大小写差异（例如 macOS 的 /var -> /private/var），避免比较失败。
+    let mut normalized = trimmed.replace('\\', "/").trim_end_matches('/').to_string();
+    if let Ok(canonical) = std::fs::canonicalize(&normalized) {
+        normalized = canonical
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_end_matches('/')
+            .to_string();
+    }
+    if cfg!(windows) {
+        normalized.to_ascii_lowercase()
+    } else {
+        normalized
+    }
+}
+
+fn normalize_worktree_add_error(raw: &str, create_branch: bool) -> String {
+    let lower = raw.to_ascii_lowercase();
+
+    if lower.contains("already checked out") || lower.contains("already used by worktree") {
+        return "该分支已在其他 worktree 检出，请切换分支或先移除旧 worktree".to_string();
+    }
+
+    if create_branch && lower.contains("already exists") && lower.contains("branch") {
+        return "分支已存在，请改用“已有分支”模式或更换分支名".to_string();
+    }
+
+    if lower.contains("already exists") {
+        return "目标目录已存在，无法创建 worktree".to_string();
+    }
+
+    if lower.contains("not a git repository") {
+        return "不是 Git 仓库".to_string();
+    }
+
+    if lower.contains("invalid reference")
+        || lower.contains("unknown revision")
+        || lower.contains("not a valid object name")
+        || lower.contains("pathspec")
+    {
+        return "分支不存在或不可用，请检查分支名称".to_string();
+    }
+
+    raw.to_string()
+}
+
+fn normalize_worktree_remove_error(raw: &str, force: bool) -> String {
+    let lower = raw.to_ascii_lowercase();
+
+    if lower.contains("not a git repository") {
+        return "不是 Git 仓库".to_string();
+    }
+
+    if lower.contains("not a working tree") {
+        return "worktree 不存在或已移除".to_string();
+    }
+
+    if lower.contains("contains modified or untracked files")
+        || lower.contains("cannot remove a dirty worktree")
+        || (lower.contains("dirty") && lower.contains("worktree"))
+    {
+        if force {
+            return "强制删除失败：worktree 可能被进程占用或被锁定，请先关闭相关终端/编辑器后重试"
+                .to_string();
+        }
+        return "该 worktree 存在未提交修改，无法删除。请先提交/清理，或使用“强制删除”".to_string();
+    }
+
+    if lower.contains("locked") && lower.contains("worktree") {
+        return "worktree 已锁定，无法删除。可先执行 git worktree unlock 后重试".to_string();
+    }
+
+    raw.to_string()
+}
+
+fn normalize_delete_branch_error(raw: &str, force: bool) -> String {
+    let lower = raw.to_ascii_lowercase();
+
+    if lower.contains("not a git repository") {
+        return "不是 Git 仓库".to_string();
+    }
+
+    if lower.contains("not found") && lower.contains("branch") {
+        return "分支不存在或已删除".to_string();
+    }
+
+    if lower.contains("checked out") {
+        return "分支正在当前仓库或其他 worktree 中使用，无法删除".to_string();
+    }
+
+    if !force && lower.contains("not fully merged") {
+        return "分支包含未合并提交，无法删除。请先合并后重试".to_string();
+    }
+
+    raw.to_string()
+}
+
+fn normalize_tag_error(raw: &str, name: &str) -> String {
+    let lower = raw.to_ascii_lowercase();
+
+    if lower.contains("already exists") {
+        return format!("Tag 已存在: {name}");
+    }
+
+    if lower.contains("not found") {
+        return format!("Tag 不存在或已删除: {name}");
+    }
+
+    if lower.contains("is not a valid tag name") {
+        return format!("Tag 名不合法: {name}");
+    }
+
+    raw.to_string()
+}
+
+fn normalize_remote_error(raw: &str, name: &str) -> String {
+    let lower = raw.to_ascii_lowercase();
+
+    if lower.contains("already exists") {
+        return format!("远程已存在: {name}");
+    }
+
+    if lower.contains("no such remote") {
+        return format!("远程不存在: {name}");
+    }
+
+    if lower.contains("not a valid remote name") {
+        return format!("远程名称不合法: {name}");
+    }
+
+    raw.to_string()
+}
+
+// 解析 `git remote -v` 输出（`name\turl (fetch|push)`），按首次出现顺序合并同名远程。
+fn parse_remote_list_output(output: &str) -> Vec<GitRemote> {
+    let mut remotes: Vec<GitRemote> = Vec::new();
+    for line in output.lines() {
+        let Some((name, rest)) = line.trim().split_once('\t') else {
+            continue;
+        };
+        let (url, kind) = match rest.rsplit_once(' ') {
+            Some((url, kind)) => (url.trim(), kind.trim()),
+            None => (rest.trim(), "(fetch)"),
+        };
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(GitRemote {
+                    name: name.to_string(),
+                    fetch_url: String::new(),
+                    push_url: String::new(),
+                });
+                remotes.len() - 1
+            }
+        };
+        let remote = &mut remotes[index];
+        if kind == "(push)" {
+            remote.push_url = url.to_string();
+        } else {
+            remote.fetch_url = url.to_string();
+        }
+    }
+    for remote in remotes.iter_mut() {
+        if remote.push_url.is_empty() {
+            remote.push_url = remote.fetch_url.clone();
+        }
+    }
+    remotes
+}
+
+// 解析 `git clean` 输出中的 `Would remove <path>` / `Removing <path>` 行。
+// 预览与实际删除共用同一组参数；`Would remove`/`Removing` 明细依赖 git_command() 固定的英文提示。
+fn clean_args(include_ignored: bool, dry_run: bool, paths: &[String]) -> Vec<&str> {
+    let mut args = vec!["clean", "-d"];
+    args.push(if dry_run { "-n" } else { "-f" });
+    if include_ignored {
+        args.push("-x");
+    }
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+    args
+}
+
+fn parse_clean_output(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("Would remove ")
+                .or_else(|| line.strip_prefix("Removing "))
+        })
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+// 解析 `git tag -l --format` 输出：name\0type\0object\0peeled\0subject，每行一条。
+fn parse_tag_list_output(output: &str) -> Vec<GitTagItem> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\0').collect();
+            let name = fields.first()?.trim();
+            if name.is_empty() {
+                return None;
+            }
+            let is_annotated = fields.get(1).copied() == Some("tag");
+            let object = fields.get(2).copied().unwrap_or("");
+            let peeled = fields.get(3).copied().unwrap_or("");
+            let subject = fields.get(4).copied().unwrap_or("").trim();
+            Some(GitTagItem {
+                name: name.to_string(),
+                commit: if peeled.is_empty() { object } else { peeled }.to_string(),
+                message: if is_annotated && !subject.is_empty() {
+                    Some(subject.to_string())
+                } else {
+                    None
+                },
+                is_annotated,
+            })
+        })
+        .collect()
+}
+
+// 解析 `git shortlog -sne` 输出（`  次数\t姓名 <邮箱>`），按提交数倒序、姓名升序排列。
+fn parse_shortlog_output(output: &str) -> Vec<GitContributor> {
+    let mut contributors: Vec<GitContributor> = output
+        .lines()
+        .filter_map(|line| {
+            let (count, author) = line.trim().split_once('\t')?;
+            let commit_count = count.trim().parse::<i64>().ok()?;
+            let author = author.trim();
+            let (name, email) = match author.rsplit_once(" <") {
+                Some((name, email)) => (name.trim(), email.trim_end_matches('>').trim()),
+                None => (author, ""),
+            };
+            Some(GitContributor {
+                name: name.to_string(),
+                email: email.to_string(),
+                commit_count,
+            })
+        })
+        .collect();
+    contributors.sort_by(|left, right| {
+        right
+            .commit_count
+            .cmp(&left.commit_count)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    contributors
+}
+
+// 执行 Git 命令并统一输出格式。
+fn execute_git_command(path: &str, args: &[&str]) -> GitCommandResult {
+    git_command_result(git_command().args(args).current_dir(path).output())
+}
+
+// 合并命令输出：成功时取 stdout，失败时附带 stderr。
+fn git_command_result(output: std::io::Result<std::process::Output>) -> GitCommandResult {
+    match output {
+        Ok(output) => {
+            let success = output.status.success();
+            let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            let combined = if success || stderr.is_empty() {
+                stdout
+            } else {
+                format!("{stdout}\n{stderr}").trim().to_string()
+            };
+            GitCommandResult {
+                success,
+                output: combined,
+            }
+        }
+        Err(err) => GitCommandResult {
+            success: false,
+            output: format!("执行命令失败: {err}"),
+        },
+    }
+}
+
+// 将补丁应用到暂存区，先 `--check` 校验可干净应用再执行。
+fn apply_patch_to_index(
+    base_path: &str,
+    relative_path: &str,
+    patch: &str,
+    reverse: bool,
+) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    let relative_path = relative_path.trim();
+    if relative_path.is_empty() {
+        return Err(GitError::raw("路径为空"));
+    }
+    if patch.trim().is_empty() {
+        return Err(GitError::raw("补丁内容为空"));
+    }
+    if !patch_header_paths(patch).contains(&relative_path) {
+        return Err(GitError::raw(format!("补丁与文件不匹配: {relative_path}")));
+    }
+
+    // git apply 要求补丁以换行结尾。
+    let mut patch = patch.to_string();
+    if !patch.ends_with('\n') {
+        patch.push('\n');
+    }
+
+    let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+    if reverse {
+        args.push("--reverse");
+    }
+
+    let mut check_args = args.clone();
+    check_args.push("--check");
+    let check = execute_git_command_with_stdin(base_path, &check_args, patch.as_bytes());
+    if !check.success {
+        return Err(GitError::from_output(format!(
+            "patch 无法应用: {}",
+            check.output
+        )));
+    }
+
+    let result = execute_git_command_with_stdin(base_path, &args, patch.as_bytes());
+    if result.success {
+        Ok(())
+    } else {
+        Err(GitError::from_output(format!(
+            "patch 无法应用: {}",
+            result.output
+        )))
+    }
+}
+
+// 解析补丁各文件头中 `---`/`+++` 行的路径（去掉 a/、b/ 前缀，忽略 /dev/null），
+// 只在文件头区域内识别，避免把以 `--`/`++` 开头的内容行误当作文件头。
+fn patch_header_paths(patch: &str) -> Vec<&str> {
+    let mut paths = Vec::new();
+    let mut in_header = true;
+    for line in patch.lines() {
+        if line.starts_with("diff --git ") {
+            in_header = true;
+            continue;
+        }
+        if line.starts_with("@@") {
+            in_header = false;
+            continue;
+        }
+        if !in_header {
+            continue;
+        }
+        let Some(path) = line
+            .strip_prefix("--- ")
+            .or_else(|| line.strip_prefix("+++ "))
+        else {
+            continue;
+        };
+        let path = path.trim_end_matches('\t').trim().trim_matches('"');
+        if path == "/dev/null" {
+            continue;
+        }
+        let path = path
+            .strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path);
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+// 执行 Git 命令并通过 stdin 写入数据。
+fn execute_git_command_with_stdin(path: &str, args: &[&str], input: &[u8]) -> GitCommandResult {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let child = git_command()
+        .args(args)
+        .current_dir(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            return GitCommandResult {
+                success: false,
+                output: format!("执行命令失败: {err}"),
+            };
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take()
+        && let Err(err) = stdin.write_all(input)
+    {
+        let _ = child.kill();
+        let _ = child.wait();
+        return GitCommandResult {
+            success: false,
+            output: format!("写入 git 输入失败: {err}"),
+        };
+    }
+
+    git_command_result(child.wait_with_output())
+}
+
+fn is_git_show_not_found(stderr: &str) -> bool {
+    let msg = stderr.to_ascii_lowercase();
+    msg.contains("does not exist")
+        || msg.contains("not in the index")
+        || msg.contains("exists on disk, but not in the index")
+        || msg.contains("but not at stage")
+        || msg.contains("invalid object name")
+        || msg.contains("ambiguous argument")
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|b| *b == 0)
+}
+
+// Git LFS 指针文件：体积很小，首行为固定的 spec 版本声明，并包含 sha256 oid。
+fn is_lfs_pointer(bytes: &[u8]) -> bool {
+    const LFS_POINTER_MAX_BYTES: usize = 1024;
+    const LFS_SPEC_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1\n";
+    bytes.len() <= LFS_POINTER_MAX_BYTES
+        && bytes.starts_with(LFS_SPEC_PREFIX)
+        && bytes
+            .split(|byte| *byte == b'\n')
+            .any(|line| line.starts_with(b"oid sha256:"))
+}
+
+fn bytes_to_text(bytes: Option<Vec<u8>>) -> Result<String, String> {
+    match bytes {
+        None => Ok(String::new()),
+        Some(bytes) => {
+            if is_lfs_pointer(&bytes) {
+                return Err("该文件由 Git LFS 管理，暂不支持文本对比。".to_string());
+            }
+            if looks_binary(&bytes) {
+                return Err("检测到二进制文件，无法以文本对比展示。".to_string());
+            }
+            Ok(String::from_utf8_lossy(&bytes).to_string())
+        }
+    }
+}
+
+fn read_git_object_optional(
+    base_path: &str,
+    spec: &str,
+    max_bytes: usize,
+) -> Result<(Option<Vec<u8>>, bool), String> {
+    let output = git_command()
+        .args(["show", spec])
+        .current_dir(base_path)
+        .output()
+        .map_err(|err| format!("执行命令失败: {err}"))?;
+
+    if output.status.success() {
+        let (bytes, truncated) = truncate_bytes(output.stdout, max_bytes);
+        return Ok((Some(bytes), truncated));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if is_git_show_not_found(&stderr) {
+        return Ok((None, false));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stderr.is_empty() {
+        Err(stdout)
+    } else if stdout.is_empty() {
+        Err(stderr)
+    } else {
+        Err(format!("{stdout}\n{stderr}").trim().to_string())
+    }
+}
+
+fn read_worktree_file_optional(
+    base_path: &str,
+    relative_path: &str,
+    max_bytes: usize,
+) -> Result<(Option<Vec<u8>>, bool), String> {
+    use std::io::Read;
+
+    let path = Path::new(base_path).join(relative_path);
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok((None, false)),
+        Err(err) => return Err(format!("读取文件失败: {err}")),
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    let mut handle = file.take((max_bytes as u64) + 1);
+    handle
+        .read_to_end(&mut buf)
+        .map_err(|err| format!("读取文件失败: {err}"))?;
+
+    let truncated = buf.len() > max_bytes;
+    if truncated {
+        buf.truncate(max_bytes);
+    }
+    Ok((Some(buf), truncated))
+}
+
+fn truncate_bytes(mut bytes: Vec<u8>, max_bytes: usize) -> (Vec<u8>, bool) {
+    if bytes.len() <= max_bytes {
+        return (bytes, false);
+    }
+    bytes.truncate(max_bytes);
+    (bytes, true)
+}
+
+fn git_executable_override() -> &'static RwLock<Option<String>> {
+    static OVERRIDE: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+    OVERRIDE.get_or_init(|| RwLock::new(None))
+}
+
+/// 设置 Git 可执行文件路径（来自设置），为空或不存在时回退自动探测。
+pub fn set_git_executable_override(path: Option<&str>) {
+    let path = path.map(str::trim).filter(|value| !value.is_empty());
+    let resolved = match path {
+        Some(path) if Path::new(path).is_file() => Some(path.to_string()),
+        Some(path) => {
+            log::warn!("配置的 Git 路径不存在，使用自动探测: {}", path);
+            None
+        }
+        None => None,
+    };
+    if let Ok(mut guard) = git_executable_override().write() {
+        *guard = resolved;
+    }
+}
+
+fn resolve_git_executable() -> String {
+    if let Some(path) = git_executable_override()
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    {
+        return path;
+    }
+
+    static BIN: OnceLock<String> = OnceLock::new();
+    BIN.get_or_init(|| {
+        GIT_BIN_CANDIDATES
+            .iter()
+            .find(|candidate| Path::new(candidate).is_file())
+            .map(|candidate| candidate.to_string())
+            .unwrap_or_else(|| "git".to_string())
+    })
+    .clone()
+}
+
+/// 构造 Git 命令：使用解析后的可执行文件，并补齐 PATH（打包 App 启动时常缺少 Homebrew 目录）。
+///
+/// 同时固定提示语言为英文：错误归类与 clean/prune 等输出解析都依赖 git 的英文原文，
+/// 本地化的 git（如 zh_CN 下的 Homebrew git）会输出翻译后的文本。
+pub(crate) fn git_command() -> Command {
+    let mut command = Command::new(resolve_git_executable());
+    apply_normalized_path(&mut command);
+    command.env("LC_ALL", "C").env("LANGUAGE", "C");
+    command
+}
+
+// 为 git 及其调用的外部程序（hook、凭据助手等）设置补全后的 PATH。
+fn apply_normalized_path(command: &mut Command) {
+    #[cfg(not(windows))]
+    if let Some(path) = normalized_git_path(std::env::var_os("PATH")) {
+        command.env("PATH", path);
+    }
+}
+
+// 将常见的 Homebrew/本地安装目录补到 PATH 前面（已存在的不重复添加），
+// 使 git 调用的凭据助手、LFS 等外部程序可被找到。
+#[cfg(not(windows))]
+fn normalized_git_path(current: Option<std::ffi::OsString>) -> Option<std::ffi::OsString> {
+    let existing: Vec<PathBuf> = current
+        .map(|value| std::env::split_paths(&value).collect())
+        .unwrap_or_default();
+    let mut paths: Vec<PathBuf> = GIT_PATH_EXTRA_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .filter(|dir| !existing.contains(dir))
+        .collect();
+    paths.extend(existing);
+    std::env::join_paths(paths).ok()
+}
+
+fn run_git_with_paths<const N: usize>(
+    base_path: &str,
+    prefix_args: [&str; N],
+    relative_paths: &[String],
+) -> Result<(), GitError> {
+    if !is_git_repo(base_path) {
+        return Err(GitError::not_a_repo());
+    }
+    if relative_paths.is_empty() {
+        return Ok(());
+    }
+
+    let output = git_command()
+        .args(prefix_args)
+        .args(relative_paths)
+        .current_dir(base_path)
+        .output()
+        .map_err(|err| format!("执行命令失败: {err}"))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.is_empty() {
+        Err(GitError::from_output(stdout))
+    } else if stdout.is_empty() {
+        Err(GitError::from_output(stderr))
+    } else {
+        Err(GitError::from_output(
+            format!("{stdout}\n{stderr}").trim().to_string(),
+        ))
+    }
+}
+
+fn parse_porcelain_v2_status(output: &str) -> Result<GitRepoStatus, String> {
+    let mut branch = String::new();
+    let mut upstream: Option<String> = None;
+    let mut ahead: i32 = 0;
+    let mut behind: i32 = 0;
+    let mut staged: Vec<GitChangedFile> = Vec::new();
+    let mut unstaged: Vec<GitChangedFile> = Vec::new();
+    let mut untracked: Vec<GitChangedFile> = Vec::new();
+
+    let parts: Vec<&str> = output.split('\0').collect();
+    let mut index = 0usize;
+    while index < parts.len() {
+        let record = parts[index];
+        if record.is_empty() {
+            index += 1;
+            continue;
+        }
+
+        if let Some(rest) = record.strip_prefix("# ") {
+            if let Some(value) = rest.strip_prefix("branch.head ") {
+                branch = value.trim().to_string();
+            } else if let Some(value) = rest.strip_prefix("branch.upstream ") {
+                let value = value.trim();
+                if !value.is_empty() {
+                    upstream = Some(value.to_string());
+                }
+            } else if let Some(value) = rest.strip_prefix("branch.ab ") {
+                // Format: +A -B
+                let mut iter = value.split_whitespace();
+                let ahead_token = iter.next().unwrap_or("");
+                let behind_token = iter.next().unwrap_or("");
+                ahead = ahead_token
+                    .trim_start_matches('+')
+                    .parse::<i32>()
+                    .unwrap_or(0);
+                behind = behind_token
+                    .trim_start_matches('-')
+                    .parse::<i32>()
+                    .unwrap_or(0);
+            }
+            index += 1;
+            continue;
+        }
+
+        let record_type = record.chars().next().unwrap_or(' ');
+        match record_type {
+            '1' => {
+                parse_change_record(record, 8, None, &mut staged, &mut unstaged);
+            }
+            '2' => {
+                let old_path = parts.get(index + 1).map(|value| (*value).to_string());
+                parse_change_record(record, 9, old_path, &mut staged, &mut unstaged);
+                // Record type 2 uses an extra NUL-delimited pathname
+                index += 1;
+            }
+            '?' => {
+                // Format: "? <path>"
+                if let Some(path) = record.strip_prefix("? ") {
+                    let path = path.trim();
+                    if !path.is_empty() {
+                        untracked.push(GitChangedFile {
+                            path: path.to_string(),
+                            old_path: None,
+                            status: GitFileStatus::Untracked,
+                            submodule: None,
+                        });
+                    }
+                }
+            }
+            // ignore ignored files: "! <path>"
+            '!' => {}
+            _ => {
+                // Unrecognized record type; ignore to avoid breaking the UI.
+            }
+        }
+
+        index += 1;
+    }
+
+    if branch.is_empty() {
+        branch = "HEAD".to_string();
+    }
+
+    Ok(GitRepoStatus {
+        branch,
+        upstream,
+        ahead,
+        behind,
+        staged,
+        unstaged,
+        untracked,
+    })
+}
+
+fn parse_change_record(
+    record: &str,
+    path_token_start_index: usize,
+    old_path: Option<String>,
+    staged: &mut Vec<GitChangedFile>,
+    unstaged: &mut Vec<GitChangedFile>,
+) {
+    let tokens: Vec<&str> = record.split_whitespace().collect();
+    if tokens.len() < 2 {
+        return;
+    }
+    let xy = tokens[1];
+    let x = xy.chars().next().unwrap_or('.');
+    let y = xy.chars().nth(1).unwrap_or('.');
+    let submodule = tokens.get(2).and_then(|value| parse_submodule_state(value));
+
+    if tokens.len() <= path_token_start_index {
+        return;
+    }
+    let path_tokens = &tokens[path_token_start_index..];
+    let path = path_tokens.join(" ").trim().to_string();
+    if path.is_empty() {
+        return;
+    }
+
+    if x != '.' && x != ' ' {
+        staged.push(GitChangedFile {
+            path: path.clone(),
+            old_path: old_path.clone(),
+            status: map_git_status_char(x),
+            submodule: submodule.clone(),
+        });
+    }
+
+    if y != '.' && y != ' ' {
+        unstaged.push(GitChangedFile {
+            path,
+            old_path,
+            status: map_git_status_char(y),
+            submodule,
+        });
+    }
+}
+
+// 解析 porcelain v2 的子模块字段：`N...` 表示普通文件，`S<c><m><u>` 表示子模块。
+fn parse_submodule_state(token: &str) -> Option<String> {
+    let flags = token.strip_prefix('S')?;
+    let mut states = Vec::new();
+    let mut chars = flags.chars();
+    if chars.next() == Some('C') {
+        states.push("new-commits");
+    }
+    if chars.next() == Some('M') {
+        states.push("modified-content");
+    }
+    if chars.next() == Some('U') {
+        states.push("untracked-content");
+    }
+    Some(states.join(","))
+}
+
+// 解析 `git diff --numstat -z` 输出；二进制文件的增删行数为 `-`。
+// 重命名记录形如 `A\tD\t\0<old>\0<new>\0`。
+fn parse_numstat_output(output: &str) -> Vec<GitDiffStatEntry> {
+    let parts: Vec<&str> = output.split('\0').collect();
+    let mut entries = Vec::new();
+    let mut index = 0usize;
+    while index < parts.len() {
+        let record = parts[index].trim_start_matches('\n');
+        index += 1;
+        if record.is_empty() {
+            continue;
+        }
+
+        let mut fields = record.splitn(3, '\t');
+        let additions_token = fields.next().unwrap_or("");
+        let deletions_token = fields.next().unwrap_or("");
+        let path = fields.next().unwrap_or("");
+        let binary = additions_token == "-" || deletions_token == "-";
+
+        let (relative_path, old_relative_path) = if path.is_empty() {
+            let old = parts.get(index).copied().unwrap_or("").to_string();
+            let new = parts.get(index + 1).copied().unwrap_or("").to_string();
+            index += 2;
+            (new, Some(old))
+        } else {
+            (path.to_string(), None)
+        };
+        if relative_path.is_empty() {
+            continue;
+        }
+
+        entries.push(GitDiffStatEntry {
+            relative_path,
+            old_relative_path,
+            additions: additions_token.parse().unwrap_or(0),
+            deletions: deletions_token.parse().unwrap_or(0),
+            binary,
+        });
+    }
+    entries
+}
+
+// 解析冲突标记（<<<<<<< / ||||||| / ======= / >>>>>>>），不完整的区块会被忽略。
+fn parse_conflict_regions(text: &str) -> Vec<GitConflictRegion> {
+    fn is_marker(line: &str, marker: &str) -> bool {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    }
+
+    let mut regions = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut base: Option<usize> = None;
+    let mut separator: Option<usize> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim_end_matches('\r');
+        if is_marker(line, "<<<<<<<") {
+            start = Some(line_number);
+            base = None;
+            separator = None;
+        } else if start.is_some() && separator.is_none() && is_marker(line, "|||||||") {
+            base = Some(line_number);
+        } else if start.is_some() && separator.is_none() && line == "=======" {
+            separator = Some(line_number);
+        } else if is_marker(line, ">>>>>>>")
+            && let (Some(start_line), Some(separator_line)) = (start, separator)
+        {
+            regions.push(GitConflictRegion {
+                start_line,
+                base_line: base,
+                separator_line,
+                end_line: line_number,
+            });
+            start = None;
+            base = None;
+            separator = None;
+        }
+    }
+
+    regions
+}
+
+// 解析 `git diff --name-status -z`：每条记录为状态字段 + 路径，重命名/复制额外包含旧路径在前。
+fn parse_name_status_output(output: &str) -> Vec<GitChangedFile> {
+    let mut files = Vec::new();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let Some(code) = fields.next() {
+        let Some(status_char) = code.chars().next() else {
+            continue;
+        };
+        let status = map_git_status_char(status_char);
+        let (old_path, path) = if matches!(status_char, 'R' | 'C') {
+            let old_path = fields.next().map(str::to_string);
+            (old_path, fields.next())
+        } else {
+            (None, fields.next())
+        };
+        let Some(path) = path else {
+            break;
+        };
+        files.push(GitChangedFile {
+            path: path.to_string(),
+            old_path,
+            status,
+            submodule: None,
+        });
+    }
+    files
+}
+
+fn map_git_status_char(value: char) -> GitFileStatus {
+    match value {
+        'A' => GitFileStatus::Added,
+        'M' => GitFileStatus::Modified,
+        'D' => GitFileStatus::Deleted,
+        'R' => GitFileStatus::Renamed,
+        'C' => GitFileStatus::Copied,
+        '?' => GitFileStatus::Untracked,
+        _ => GitFileStatus::Modified,
+    }
+}
+
+fn resolve_default_branch(branches: &[String], base_path: &str) -> Option<String> {
+    let symbolic = execute_git_command(base_path, &["symbolic-ref", "refs/remotes/origin/HEAD"]);
+    if symbolic.success {
+        if let Some(name) = symbolic.output.split('/').last() {
+            let name = name.trim();
+            if branches.iter().any(|branch| branch == name) {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    if branches.iter().any(|branch| branch == "main") {
+        return Some("main".to_string());
+    }
+    if branches.iter().any(|branch| branch == "master") {
+        return Some("master".to_string());
+    }
+
+    None
+}
+
+struct GitCommandResult {
+    success: bool,
+    output: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CommitMessageRules, abort_operation, add_remote, add_worktree, ahead_behind, bytes_to_text,
+        checkout_branch, classify_git_error, clean, clean_args, continue_operation, contributors,
+        delete_branch, diff_against_base, discard_all, find_worktree, get_repo_status, git_command,
+        init_repo, is_git_repo, is_valid_email, list_worktrees, mark_resolved, normalize_diff_text,
+        operation_state, parse_clean_output, parse_config_scope_output, parse_conflict_regions,
+        parse_name_status_output, parse_numstat_output, parse_porcelain_v2_status,
+        parse_remote_list_output, parse_shortlog_output, parse_tag_list_output,
+        parse_worktree_list_output, parse_worktree_prune_output, patch_header_paths,
+        prune_worktrees, remove_index_lock, remove_worktree, repo_health, repo_stats, reset,
+        resolve_create_branch_start_point, resolve_git_executable, run_hook, set_remote_url,
+        stage_all, stage_hunk, unstage_all, unstage_hunk, validate_commit_message,
+        worktree_divergence, worktree_prune_args,
+    };
+    use crate::models::{
+        CommitValidationCode, GitConfigScope, GitDiffOptions, GitError, GitErrorKind,
+        GitFileStatus, GitIdentity, GitOperation, GitRenameDetection, GitResetMode,
+        GitUntrackedMode,
+    };
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(path: &Path, args: &[&str]) -> Result<String, String> {
+        let output = Command::new(resolve_git_executable())
+            .args(args)
+            .current_dir(path)
+            .output()
+            .map_err(|err| format!("执行 git 失败: {err}"))?;
+
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            Err(stdout)
+        } else if stdout.is_empty() {
+            Err(stderr)
+        } else {
+            Err(format!("{stdout}\n{stderr}").trim().to_string())
+        }
+    }
+
+    // 在 dir 下生成只含给定条目的 git 中文翻译（.mo），返回让 git 使用该翻译的环境变量。
+    // LC_MESSAGES 使用总是存在的 C.UTF-8，避免依赖系统是否安装 zh_CN locale。
+    fn fake_zh_git_locale(dir: &Path, entries: &[(&str, &str)]) -> Vec<(&'static str, String)> {
+        let mut entries = entries.to_vec();
+        entries.sort();
+        let count = entries.len() as u32;
+        let mut strings: Vec<u8> = Vec::new();
+        let mut offsets: Vec<(u32, u32)> = Vec::new();
+        let strings_start = 28 + count * 16;
+        for text in entries
+            .iter()
+            .map(|(id, _)| *id)
+            .chain(entries.iter().map(|(_, value)| *value))
+        {
+            offsets.push((text.len() as u32, strings_start + strings.len() as u32));
+            strings.extend_from_slice(text.as_bytes());
+            strings.push(0);
+        }
+        let mut mo: Vec<u8> = Vec::new();
+        for value in [0x9504_12de_u32, 0, count, 28, 28 + count * 8, 0, 0] {
+            mo.extend_from_slice(&value.to_le_bytes());
+        }
+        for (length, offset) in offsets {
+            mo.extend_from_slice(&length.to_le_bytes());
+            mo.extend_from_slice(&offset.to_le_bytes());
+        }
+        mo.extend_from_slice(&strings);
+
+        let messages_dir = dir.join("zh_CN").join("LC_MESSAGES");
+        fs::create_dir_all(&messages_dir).expect("create locale dir");
+        fs::write(messages_dir.join("git.mo"), mo).expect("write git.mo");
+        vec![
+            ("GIT_TEXTDOMAINDIR", dir.to_string_lossy().to_string()),
+            ("LANG", "zh_CN.UTF-8".to_string()),
+            ("LC_MESSAGES", "C.UTF-8".to_string()),
+            ("LANGUAGE", "zh_CN".to_string()),
+        ]
+    }
+
+    // 确认未固定语言的 git 确实会使用伪造的翻译；不支持 gettext 的 git 返回 false，测试据此跳过。
+    fn git_uses_fake_locale(
+        path: &Path,
+        env: &[(&'static str, String)],
+        args: &[&str],
+        english: &str,
+    ) -> bool {
+        let output = Command::new(resolve_git_executable())
+            .args(args)
+            .envs(env.iter().map(|(key, value)| (*key, value)))
+            .current_dir(path)
+            .output()
+            .expect("run git");
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        !text.contains(english)
+    }
+
+    #[test]
+    fn git_command_keeps_english_messages_under_localized_env() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let plain = root.join("plain");
+        fs::create_dir_all(&plain).expect("create dir");
+        let env = fake_zh_git_locale(
+            &root.join("locale"),
+            &[(
+                "not a git repository (or any of the parent directories): %s",
+                "不是 git 仓库（或者任何父目录）：%s",
+            )],
+        );
+        if !git_uses_fake_locale(&plain, &env, &["status"], "not a git repository") {
+            eprintln!("git 未启用 gettext，跳过本地化测试");
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
+
+        let output = git_command()
+            .arg("status")
+            .envs(env.iter().map(|(key, value)| (*key, value)))
+            .current_dir(&plain)
+            .output()
+            .expect("run git");
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(classify_git_error(&stderr), GitErrorKind::NotARepo);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn add_worktree_creates_directory_with_new_branch() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let worktree = root.with_file_name(format!(
+            "{}-wt",
+            root.file_name().unwrap().to_string_lossy()
+        ));
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let root_str = root.to_string_lossy().to_string();
+        let worktree_str = worktree.to_string_lossy().to_string();
+        let result = add_worktree(
+            &root_str,
+            Some(&worktree_str),
+            "feature/worktree",
+            true,
+            None,
+        )
+        .expect("create worktree");
+
+        assert_eq!(result.path, worktree_str);
+        assert_eq!(result.branch, "feature/worktree");
+        assert!(worktree.join(".git").exists());
+        assert!(is_git_repo(&worktree.to_string_lossy()));
+        assert_eq!(
+            git(&worktree, &["branch", "--show-current"]).expect("read current branch"),
+            "feature/worktree"
+        );
+
+        let _ = fs::remove_dir_all(&worktree);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn add_worktree_supports_explicit_start_point() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let worktree = root.with_file_name(format!(
+            "{}-wt-explicit",
+            root.file_name().unwrap().to_string_lossy()
+        ));
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "main\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
+        git(&root, &["checkout", "-b", "develop"]).expect("create develop");
+        fs::write(root.join("BASELINE.txt"), "develop\n").expect("write baseline marker");
+        git(&root, &["add", "."]).expect("git add develop");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "develop baseline",
+            ],
+        )
+        .expect("git commit develop");
+        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+
+        let root_str = root.to_string_lossy().to_string();
+        let worktree_str = worktree.to_string_lossy().to_string();
+        add_worktree(
+            &root_str,
+            Some(&worktree_str),
+            "feature/from-develop",
+            true,
+            Some("develop"),
+        )
+        .expect("create worktree with explicit start point");
+
+        assert_eq!(
+            git(
+                &worktree,
+                &["merge-base", "--is-ancestor", "develop", "HEAD"]
+            ),
+            Ok(String::new())
+        );
+        assert!(worktree.join("BASELINE.txt").exists());
+
+        let _ = fs::remove_dir_all(&worktree);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn resolve_create_branch_start_point_prefers_origin_ref() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let remote = root.with_file_name(format!(
+            "{}-remote.git",
+            root.file_name().unwrap().to_string_lossy()
+        ));
+
+        fs::create_dir_all(&root).expect("create root");
+        Command::new(resolve_git_executable())
+            .args(["init", "--bare", remote.to_string_lossy().as_ref()])
+            .output()
+            .expect("init bare remote");
+
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
+        git(
+            &root,
+            &["remote", "add", "origin", remote.to_string_lossy().as_ref()],
+        )
+        .expect("add origin");
+        git(&root, &["checkout", "-b", "develop"]).expect("create develop");
+        fs::write(root.join("DEVELOP.md"), "develop\n").expect("write develop marker");
+        git(&root, &["add", "."]).expect("git add develop");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "develop",
+            ],
+        )
+        .expect("git commit develop");
+        git(&root, &["push", "-u", "origin", "develop"]).expect("push develop");
+        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+
+        let start_point =
+            resolve_create_branch_start_point(root.to_string_lossy().as_ref(), "develop")
+                .expect("resolve start point");
+        assert_eq!(start_point, "origin/develop");
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&remote);
+    }
+
+    #[test]
+    fn resolve_create_branch_start_point_returns_error_when_branch_missing() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let error = resolve_create_branch_start_point(root.to_string_lossy().as_ref(), "develop")
+            .expect_err("missing base branch should fail");
+        assert!(error.message.contains("基线分支不可用"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn remove_worktree_removes_directory_and_unregisters() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let worktree = root.with_file_name(format!(
+            "{}-wt",
+            root.file_name().unwrap().to_string_lossy()
+        ));
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let root_str = root.to_string_lossy().to_string();
+        let worktree_str = worktree.to_string_lossy().to_string();
+        add_worktree(&root_str, Some(&worktree_str), "feature/remove", true, None)
+            .expect("create worktree");
+
+        remove_worktree(&root_str, &worktree_str, false).expect("remove worktree");
+
+        assert!(!worktree.exists());
+        assert!(
+            list_worktrees(&root_str)
+                .expect("list worktrees")
+                .is_empty()
+        );
+
+        let _ = fs::remove_dir_all(&worktree);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn delete_branch_removes_local_branch() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+
+        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
+        git(&root, &["checkout", "-b", "feature/delete-me"]).expect("create feature branch");
+        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+
+        let root_str = root.to_string_lossy().to_string();
+        delete_branch(&root_str, "feature/delete-me", false).expect("delete branch");
+
+        assert_eq!(
+            git(&root, &["branch", "--list", "feature/delete-me"]).expect("list branch"),
+            ""
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn add_worktree_rejects_non_repo_path() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let worktree = root.join("worktree");
+        let root_str = root.to_string_lossy().to_string();
+        let worktree_str = worktree.to_string_lossy().to_string();
+
+        fs::create_dir_all(&root).expect("create root");
+
+        let err = add_worktree(&root_str, Some(&worktree_str), "feature/x", true, None)
+            .expect_err("should reject non git repo");
+        assert_eq!(err, GitError::not_a_repo());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn add_worktree_rejects_existing_target_directory() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let worktree = root.join("worktree");
+        let root_str = root.to_string_lossy().to_string();
+        let worktree_str = worktree.to_string_lossy().to_string();
+
+        fs::create_dir_all(&root).expect("create root");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        git(&root, &["add", "."]).expect("git add");
+        git(
+            &root,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("git commit");
+        fs::create_dir_all(&worktree).expect("create existing worktree dir");
+
+        let err = add_worktree(&root_str, Some(&worktree_str), "feature/x", true, None)
+            .expect_err("should reject existing target");
+        assert_eq!(err.message, "目标目录已存在，无法创建 worktree");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_worktree_list_output_filters_base_and_detached() {
+        let output = r#"
+worktree /repo/main
+HEAD 111111
+branch refs/heads/main
+
+worktree /repo/feature-a
+HEAD 222222
+branch refs/heads/feature/a
+
+worktree /repo/detached
+HEAD 333333
+detached
+"#;
+
+        let items = parse_worktree_list_output("/repo/main", output);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, "/repo/feature-a");
+        assert_eq!(items[0].branch, "feature/a");
+    }
+
+    #[test]
+    fn parse_worktree_list_output_accepts_branch_without_refs_prefix() {
+        let output = r#"
+worktree /repo/main
+HEAD 111111
+branch refs/heads/main
+
+worktree /repo/release
+HEAD 222222
+branch release/1.0
+"#;
+
+        let items = parse_worktree_list_output("/repo/main", output);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, "/repo/release");
+        assert_eq!(items[0].branch, "release/1.0");
+    }
+
+    #[test]
+    fn parse_numstat_output_handles_binary_and_renames() {
+        let output = [
+            "3\t1\tsrc/main.rs",
+            "-\t-\tassets/logo.png",
+            "2\t0\t",
+            "old name.txt",
+            "new name.txt",
+            "",
+        ]
+        .join("\0");
+        let entries = parse_numstat_output(&output);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].relative_path, "src/main.rs");
+        assert_eq!((entries[0].additions, entries[0].deletions), (3, 1));
+        assert!(!entries[0].binary);
+        assert_eq!(entries[1].relative_path, "assets/logo.png");
+        assert!(entries[1].binary);
+        assert_eq!(entries[2].relative_path, "new name.txt");
+        assert_eq!(
+            entries[2].old_relative_path.as_deref(),
+            Some("old name.txt")
+        );
+        assert_eq!(entries[2].additions, 2);
+    }
 
-        if output.status.success() {
-            return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
-        }
+    #[test]
+    fn patch_header_paths_reads_file_headers_only() {
+        let patch = "diff --git a/data.rs b/data.rs\n--- a/data.rs\n+++ b/data.rs\n@@ -1,2 +1,1 @@\n--- a.rs\n keep\n";
+        assert_eq!(patch_header_paths(patch), vec!["data.rs"]);
+        assert!(!patch_header_paths(patch).contains(&"a.rs"));
 
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        if stderr.is_empty() {
-            Err(stdout)
-        } else if stdout.is_empty() {
-            Err(stderr)
-        } else {
-            Err(format!("{stdout}\n{stderr}").trim().to_string())
-        }
+        let patch = "diff --git a/new.rs b/new.rs\nnew file mode 100644\n--- /dev/null\n+++ b/new.rs\n@@ -0,0 +1 @@\n+fn main() {}\n";
+        assert_eq!(patch_header_paths(patch), vec!["new.rs"]);
+
+        let patch = "--- a/old name.rs\n+++ b/new name.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        assert_eq!(
+            patch_header_paths(patch),
+            vec!["old name.rs", "new name.rs"]
+        );
     }
 
     #[test]
-    fn add_worktree_creates_directory_with_new_branch() {
+    fn stage_hunk_applies_partial_patch_and_reverts() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let worktree = root.with_file_name(format!(
-            "{}-wt",
-            root.file_name().unwrap().to_string_lossy()
-        ));
 
         fs::create_dir_all(&root).expect("create root");
         git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
+        let original: String = (1..=10).map(|index| format!("line{index}\n")).collect();
+        fs::write(root.join("a.txt"), &original).expect("write file");
         git(&root, &["add", "."]).expect("git add");
         git(
             &root,
@@ -1135,41 +3672,186 @@ mod tests {
         )
         .expect("git commit");
 
+        let modified = original
+            .replace("line2\n", "LINE2\n")
+            .replace("line9\n", "LINE9\n");
+        fs::write(root.join("a.txt"), modified).expect("modify file");
+
+        let patch = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,3 +1,3 @@\n line1\n-line2\n+LINE2\n line3\n";
         let root_str = root.to_string_lossy().to_string();
-        let worktree_str = worktree.to_string_lossy().to_string();
-        let result = add_worktree(
+        stage_hunk(&root_str, "a.txt", patch).expect("stage hunk");
+
+        let cached = git(&root, &["diff", "--cached"]).expect("diff cached");
+        assert!(cached.contains("+LINE2"));
+        assert!(!cached.contains("+LINE9"));
+
+        let error = stage_hunk(&root_str, "a.txt", patch).expect_err("patch already applied");
+        assert!(error.message.starts_with("patch 无法应用"));
+
+        unstage_hunk(&root_str, "a.txt", patch).expect("unstage hunk");
+        assert_eq!(git(&root, &["diff", "--cached"]).expect("diff cached"), "");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn get_repo_status_respects_untracked_mode() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(root.join("vendor")).expect("create vendor dir");
+        git(&root, &["init"]).expect("git init");
+        fs::write(root.join("vendor").join("a.js"), "a\n").expect("write a");
+        fs::write(root.join("vendor").join("b.js"), "b\n").expect("write b");
+
+        let root_str = root.to_string_lossy().to_string();
+        let normal = get_repo_status(
             &root_str,
-            Some(&worktree_str),
-            "feature/worktree",
-            true,
-            None,
+            GitUntrackedMode::Normal,
+            GitRenameDetection::default(),
         )
-        .expect("create worktree");
+        .expect("status normal");
+        let paths: Vec<&str> = normal
+            .untracked
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["vendor/"]);
+
+        let all = get_repo_status(
+            &root_str,
+            GitUntrackedMode::All,
+            GitRenameDetection::default(),
+        )
+        .expect("status all");
+        assert_eq!(all.untracked.len(), 2);
 
-        assert_eq!(result.path, worktree_str);
-        assert_eq!(result.branch, "feature/worktree");
-        assert!(worktree.join(".git").exists());
-        assert!(is_git_repo(&worktree.to_string_lossy()));
+        let none = get_repo_status(
+            &root_str,
+            GitUntrackedMode::No,
+            GitRenameDetection::default(),
+        )
+        .expect("status no");
+        assert!(none.untracked.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_conflict_regions_reads_markers_with_optional_base() {
+        let text = [
+            "keep",
+            "<<<<<<< HEAD",
+            "ours",
+            "=======",
+            "theirs",
+            ">>>>>>> feature",
+            "<<<<<<< HEAD",
+            "ours2",
+            "||||||| base",
+            "base2",
+            "=======",
+            ">>>>>>> feature",
+            "<<<<<<< dangling",
+        ]
+        .join("\n");
+
+        let regions = parse_conflict_regions(&text);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].start_line, 2);
+        assert_eq!(regions[0].base_line, None);
+        assert_eq!(regions[0].separator_line, 4);
+        assert_eq!(regions[0].end_line, 6);
+        assert_eq!(regions[1].base_line, Some(9));
+        assert_eq!(regions[1].end_line, 12);
+        assert!(parse_conflict_regions("a\n=======\nb\n").is_empty());
+    }
+
+    #[test]
+    fn mark_resolved_rejects_files_with_conflict_markers() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init"]).expect("git init");
+        fs::write(
+            root.join("a.txt"),
+            "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n",
+        )
+        .expect("write conflicted file");
+
+        let root_str = root.to_string_lossy().to_string();
+        let files = vec!["a.txt".to_string()];
+        let err = mark_resolved(&root_str, &files).expect_err("markers should block staging");
+        assert!(err.message.contains("a.txt"));
+        let status = get_repo_status(
+            &root_str,
+            GitUntrackedMode::All,
+            GitRenameDetection::default(),
+        )
+        .expect("status");
+        assert!(status.staged.is_empty());
+
+        fs::write(root.join("a.txt"), "ours\n").expect("write resolved file");
+        mark_resolved(&root_str, &files).expect("mark resolved");
+        let status = get_repo_status(
+            &root_str,
+            GitUntrackedMode::All,
+            GitRenameDetection::default(),
+        )
+        .expect("status");
+        assert_eq!(status.staged.len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_porcelain_v2_status_marks_submodule_changes() {
+        let hash = "0".repeat(40);
+        let output = [
+            format!("1 .M SC.. 160000 160000 160000 {hash} {hash} libs/core"),
+            format!("1 .M S.MU 160000 160000 160000 {hash} {hash} libs/ui"),
+            format!("1 M. N... 100644 100644 100644 {hash} {hash} src/main.rs"),
+            String::new(),
+        ]
+        .join("\0");
+
+        let status = parse_porcelain_v2_status(&output).expect("parse status");
+        assert_eq!(status.unstaged.len(), 2);
+        assert_eq!(status.unstaged[0].submodule.as_deref(), Some("new-commits"));
         assert_eq!(
-            git(&worktree, &["branch", "--show-current"]).expect("read current branch"),
-            "feature/worktree"
+            status.unstaged[1].submodule.as_deref(),
+            Some("modified-content,untracked-content")
         );
+        assert_eq!(status.staged.len(), 1);
+        assert!(status.staged[0].submodule.is_none());
+    }
 
-        let _ = fs::remove_dir_all(&worktree);
-        let _ = fs::remove_dir_all(&root);
+    #[test]
+    fn parse_tag_list_output_distinguishes_annotated_tags() {
+        let output = [
+            "v1\0tag\0aaaa\0bbbb\0release one",
+            "light\0commit\0bbbb\0\0init",
+        ]
+        .join("\n");
+        let tags = parse_tag_list_output(&output);
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name, "v1");
+        assert!(tags[0].is_annotated);
+        assert_eq!(tags[0].commit, "bbbb");
+        assert_eq!(tags[0].message.as_deref(), Some("release one"));
+        assert_eq!(tags[1].name, "light");
+        assert!(!tags[1].is_annotated);
+        assert_eq!(tags[1].commit, "bbbb");
+        assert!(tags[1].message.is_none());
     }
 
     #[test]
-    fn add_worktree_supports_explicit_start_point() {
+    fn reset_hard_requires_confirmation() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let worktree = root.with_file_name(format!(
-            "{}-wt-explicit",
-            root.file_name().unwrap().to_string_lossy()
-        ));
 
         fs::create_dir_all(&root).expect("create root");
         git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "main\n").expect("write readme");
+        fs::write(root.join("README.md"), "init\n").expect("write readme");
         git(&root, &["add", "."]).expect("git add");
         git(
             &root,
@@ -1184,123 +3866,514 @@ mod tests {
             ],
         )
         .expect("git commit");
+        fs::write(root.join("README.md"), "changed\n").expect("modify readme");
 
-        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
-        git(&root, &["checkout", "-b", "develop"]).expect("create develop");
-        fs::write(root.join("BASELINE.txt"), "develop\n").expect("write baseline marker");
-        git(&root, &["add", "."]).expect("git add develop");
-        git(
-            &root,
-            &[
-                "-c",
-                "user.name=DevHaven",
-                "-c",
-                "user.email=devhaven@example.com",
-                "commit",
-                "-m",
-                "develop baseline",
-            ],
-        )
-        .expect("git commit develop");
-        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+        let root_str = root.to_string_lossy().to_string();
+        let preview = reset(&root_str, "HEAD", GitResetMode::Hard, false).expect("preview reset");
+        assert!(!preview.performed);
+        assert_eq!(preview.discarded_files, vec!["README.md".to_string()]);
+        assert_eq!(
+            fs::read_to_string(root.join("README.md")).expect("read readme"),
+            "changed\n"
+        );
+
+        let done = reset(&root_str, "HEAD", GitResetMode::Hard, true).expect("hard reset");
+        assert!(done.performed);
+        assert_eq!(
+            fs::read_to_string(root.join("README.md")).expect("read readme"),
+            "init\n"
+        );
+
+        assert!(reset(&root_str, "no-such-ref", GitResetMode::Soft, false).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn validate_commit_message_applies_rules_only_when_configured() {
+        let unrestricted = CommitMessageRules::default();
+        let long_subject = "feat: ".to_string() + &"x".repeat(100);
+        assert!(validate_commit_message(&long_subject, &unrestricted).is_ok());
+        assert_eq!(
+            validate_commit_message("  ", &unrestricted)
+                .expect_err("empty message")
+                .code,
+            CommitValidationCode::EmptyMessage
+        );
+
+        let rules =
+            CommitMessageRules::from_settings(Some("<type>: <subject>\n\n<body>\n# 注释行"), 50);
+        assert!(rules.require_body);
+        assert_eq!(
+            validate_commit_message(&long_subject, &rules)
+                .expect_err("subject too long")
+                .code,
+            CommitValidationCode::SubjectTooLong
+        );
+        assert_eq!(
+            validate_commit_message("feat: add tags", &rules)
+                .expect_err("missing body")
+                .code,
+            CommitValidationCode::MissingBody
+        );
+        assert!(validate_commit_message("feat: add tags\n\nlist and create", &rules).is_ok());
+
+        let subject_only = CommitMessageRules::from_settings(Some("<type>: <subject>\n# hint"), 0);
+        assert!(!subject_only.require_body);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn git_command_should_include_homebrew_path() {
+        let command = git_command();
+        let path = command
+            .get_envs()
+            .find(|(key, _)| *key == "PATH")
+            .and_then(|(_, value)| value)
+            .expect("PATH should be set");
+        let dirs: Vec<_> = std::env::split_paths(path).collect();
+        assert!(dirs.contains(&Path::new("/opt/homebrew/bin").to_path_buf()));
+        assert_eq!(
+            dirs.iter()
+                .filter(|dir| dir.as_path() == Path::new("/usr/local/bin"))
+                .count(),
+            1
+        );
+    }
 
+    #[cfg(unix)]
+    #[test]
+    fn run_hook_uses_normalized_path_and_reports_failure() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
+        git(&root, &["init", "-b", "main"]).expect("git init");
         let root_str = root.to_string_lossy().to_string();
-        let worktree_str = worktree.to_string_lossy().to_string();
-        add_worktree(
+
+        assert!(run_hook(&root_str, "pre-commit").is_err());
+        assert!(run_hook(&root_str, "../evil").is_err());
+
+        let hook = root.join(".git").join("hooks").join("pre-commit");
+        fs::write(&hook, "#!/bin/sh\necho \"$PATH\"\n").expect("write hook");
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).expect("chmod hook");
+        let output = run_hook(&root_str, "pre-commit").expect("run hook");
+        assert!(std::env::split_paths(&output).any(|dir| dir == Path::new("/opt/homebrew/bin")));
+
+        fs::write(&hook, "#!/bin/sh\necho lint failed >&2\nexit 1\n").expect("rewrite hook");
+        let error = run_hook(&root_str, "pre-commit").expect_err("hook should fail");
+        assert!(error.message.contains("lint failed"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn bytes_to_text_should_reject_lfs_pointer() {
+        let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        let error = bytes_to_text(Some(pointer.to_vec())).expect_err("pointer should be rejected");
+        assert!(error.contains("Git LFS"));
+
+        let text = b"version https://git-lfs.github.com/spec/v1\nnot a pointer\n";
+        assert!(bytes_to_text(Some(text.to_vec())).is_ok());
+    }
+
+    #[test]
+    fn normalize_diff_text_should_apply_eol_and_whitespace_options() {
+        let text = "a  \r\nb\t\nc ".to_string();
+        let eol = GitDiffOptions {
+            ignore_eol: true,
+            ignore_trailing_whitespace: false,
+        };
+        assert_eq!(normalize_diff_text(text.clone(), &eol), "a  \nb\t\nc ");
+
+        let whitespace = GitDiffOptions {
+            ignore_eol: false,
+            ignore_trailing_whitespace: true,
+        };
+        assert_eq!(normalize_diff_text(text.clone(), &whitespace), "a\r\nb\nc");
+
+        assert_eq!(
+            normalize_diff_text(text.clone(), &GitDiffOptions::default()),
+            text
+        );
+    }
+
+    #[test]
+    fn get_repo_status_can_disable_rename_detection() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init"]).expect("git init");
+        git(&root, &["config", "user.email", "test@example.com"]).expect("config email");
+        git(&root, &["config", "user.name", "Test"]).expect("config name");
+        fs::write(root.join("a.txt"), "line1\nline2\nline3\n").expect("write a");
+        git(&root, &["add", "a.txt"]).expect("git add");
+        git(&root, &["commit", "-m", "init"]).expect("git commit");
+        git(&root, &["mv", "a.txt", "b.txt"]).expect("git mv");
+
+        let root_str = root.to_string_lossy().to_string();
+        let detected = get_repo_status(
             &root_str,
-            Some(&worktree_str),
-            "feature/from-develop",
-            true,
-            Some("develop"),
+            GitUntrackedMode::All,
+            GitRenameDetection::default(),
         )
-        .expect("create worktree with explicit start point");
+        .expect("status with renames");
+        assert_eq!(detected.staged.len(), 1);
+        assert!(matches!(detected.staged[0].status, GitFileStatus::Renamed));
+        assert_eq!(detected.staged[0].old_path.as_deref(), Some("a.txt"));
+
+        let plain = get_repo_status(
+            &root_str,
+            GitUntrackedMode::All,
+            GitRenameDetection {
+                detect_renames: false,
+                rename_threshold: None,
+            },
+        )
+        .expect("status without renames");
+        let mut paths: Vec<&str> = plain.staged.iter().map(|file| file.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["a.txt", "b.txt"]);
+        assert!(
+            plain
+                .staged
+                .iter()
+                .all(|file| !matches!(file.status, GitFileStatus::Renamed))
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_name_status_output_reads_renames_and_plain_entries() {
+        let output = [
+            "M",
+            "src/main.rs",
+            "R087",
+            "old.txt",
+            "new.txt",
+            "D",
+            "gone.txt",
+            "",
+        ]
+        .join("\0");
+        let files = parse_name_status_output(&output);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/main.rs");
+        assert!(matches!(files[0].status, GitFileStatus::Modified));
+        assert_eq!(files[1].path, "new.txt");
+        assert_eq!(files[1].old_path.as_deref(), Some("old.txt"));
+        assert!(matches!(files[1].status, GitFileStatus::Renamed));
+        assert!(matches!(files[2].status, GitFileStatus::Deleted));
+    }
+
+    #[test]
+    fn parse_config_scope_output_reads_scope_and_value() {
+        assert_eq!(
+            parse_config_scope_output("local\tJane Doe"),
+            (Some(GitConfigScope::Local), "Jane Doe".to_string())
+        );
+        assert_eq!(
+            parse_config_scope_output("global\tjane@example.com"),
+            (Some(GitConfigScope::Global), "jane@example.com".to_string())
+        );
+        assert_eq!(
+            parse_config_scope_output("command\tci"),
+            (None, "ci".to_string())
+        );
+    }
+
+    #[test]
+    fn is_valid_email_accepts_minimal_addresses() {
+        assert!(is_valid_email("jane@example.com"));
+        assert!(is_valid_email("jane+work@mail.example.co"));
+        assert!(!is_valid_email("jane"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("jane@localhost"));
+        assert!(!is_valid_email("jane@@example.com"));
+        assert!(!is_valid_email("jane doe@example.com"));
+    }
+
+    #[test]
+    fn remove_index_lock_only_removes_stale_lock() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init"]).expect("git init");
+        let root_str = root.to_string_lossy().to_string();
+
+        let health = repo_health(&root_str).expect("health");
+        assert!(!health.has_index_lock);
+        assert!(health.fsck_errors.is_empty());
+        assert!(!remove_index_lock(&root_str).expect("no lock"));
+
+        let lock_path = root.join(".git").join("index.lock");
+        let lock = fs::File::create(&lock_path).expect("create lock");
+        assert!(repo_health(&root_str).expect("health").has_index_lock);
+        assert!(remove_index_lock(&root_str).is_err());
+
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+        lock.set_modified(old).expect("set lock mtime");
+        drop(lock);
+        assert!(remove_index_lock(&root_str).expect("remove stale lock"));
+        assert!(!lock_path.exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn repo_stats_counts_commits_and_contributors() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init"]).expect("git init");
+        let root_str = root.to_string_lossy().to_string();
+
+        let empty = repo_stats(&root_str).expect("empty repo stats");
+        assert_eq!(empty.commit_count, 0);
+        assert_eq!(empty.last_commit_ts, 0);
+        assert_eq!(empty.contributor_count, 0);
+        assert!(
+            contributors(&root_str, None)
+                .expect("empty contributors")
+                .is_empty()
+        );
+
+        for (index, author) in ["alice", "bob", "alice"].iter().enumerate() {
+            fs::write(root.join("README.md"), format!("{index}\n")).expect("write readme");
+            git(&root, &["add", "."]).expect("git add");
+            let name = format!("user.name={author}");
+            let email = format!("user.email={author}@example.com");
+            git(
+                &root,
+                &["-c", &name, "-c", &email, "commit", "-m", "update"],
+            )
+            .expect("git commit");
+        }
+
+        let stats = repo_stats(&root_str).expect("repo stats");
+        assert_eq!(stats.commit_count, 3);
+        assert_eq!(stats.contributor_count, 2);
+        assert!(stats.last_commit_ts > 0);
+        let active = contributors(&root_str, Some("1.day")).expect("contributors");
+        assert_eq!(active[0].name, "alice");
+        assert_eq!(active[0].commit_count, 2);
+        assert!(repo_stats(&root.join("missing").to_string_lossy()).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_shortlog_output_reads_counts_and_sorts() {
+        let output =
+            "     2\tBob <bob@example.com>\n     5\tAlice Chen <alice@example.com>\n     2\tAnn\n";
+        let parsed = parse_shortlog_output(output);
+        let summary: Vec<(&str, &str, i64)> = parsed
+            .iter()
+            .map(|item| (item.name.as_str(), item.email.as_str(), item.commit_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Alice Chen", "alice@example.com", 5),
+                ("Ann", "", 2),
+                ("Bob", "bob@example.com", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn init_repo_creates_branch_and_initial_commit() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
+        let root_str = root.to_string_lossy().to_string();
+        let identity = GitIdentity {
+            name: "DevHaven".to_string(),
+            email: "devhaven@example.com".to_string(),
+        };
+
+        assert!(init_repo(&root_str, "bad..name", true, Some(&identity)).is_err());
+        let status = init_repo(&root_str, "trunk", true, Some(&identity)).expect("init repo");
+        assert_eq!(status.branch, "trunk");
+        assert!(status.untracked.is_empty());
+        assert_eq!(
+            git(&root, &["log", "-1", "--format=%an"]).expect("git log"),
+            "DevHaven"
+        );
+        assert!(init_repo(&root_str, "main", false, None).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_remote_list_output_merges_fetch_and_push_urls() {
+        let output = "origin\thttps://example.com/a.git (fetch)\norigin\tgit@example.com:a.git (push)\nupstream\thttps://example.com/b.git (fetch)\n";
+        let remotes = parse_remote_list_output(output);
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(remotes[0].name, "origin");
+        assert_eq!(remotes[0].fetch_url, "https://example.com/a.git");
+        assert_eq!(remotes[0].push_url, "git@example.com:a.git");
+        assert_eq!(remotes[1].push_url, "https://example.com/b.git");
+    }
+
+    #[test]
+    fn ahead_behind_reports_upstream_counts() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let origin = root.join("origin");
+        let clone = root.join("clone");
+        fs::create_dir_all(&origin).expect("create origin");
+        git(&origin, &["init"]).expect("git init");
+        let commit = |path: &Path, content: &str| {
+            fs::write(path.join("README.md"), content).expect("write readme");
+            git(path, &["add", "."]).expect("git add");
+            git(
+                path,
+                &[
+                    "-c",
+                    "user.name=DevHaven",
+                    "-c",
+                    "user.email=devhaven@example.com",
+                    "commit",
+                    "-m",
+                    content,
+                ],
+            )
+            .expect("git commit");
+        };
+        commit(&origin, "init");
+
+        let origin_str = origin.to_string_lossy().to_string();
+        let no_upstream = ahead_behind(&origin_str).expect("no upstream");
+        assert!(!no_upstream.has_upstream);
+        assert_eq!((no_upstream.ahead, no_upstream.behind), (0, 0));
+
+        git(&root, &["clone", "origin", "clone"]).expect("git clone");
+        commit(&clone, "local");
+        commit(&origin, "remote");
+        git(&clone, &["fetch"]).expect("git fetch");
+
+        let counts = ahead_behind(&clone.to_string_lossy()).expect("ahead behind");
+        assert!(counts.has_upstream);
+        assert_eq!((counts.ahead, counts.behind), (1, 1));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn operation_state_detects_merge_and_detached_head() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init", "-b", "main"]).expect("git init");
+        let commit = |content: &str| {
+            fs::write(root.join("README.md"), content).expect("write readme");
+            git(&root, &["add", "."]).expect("git add");
+            git(
+                &root,
+                &[
+                    "-c",
+                    "user.name=DevHaven",
+                    "-c",
+                    "user.email=devhaven@example.com",
+                    "commit",
+                    "-m",
+                    content,
+                ],
+            )
+            .expect("git commit");
+        };
+        commit("base\n");
+        git(&root, &["checkout", "-b", "feature"]).expect("create feature");
+        commit("feature\n");
+        git(&root, &["checkout", "main"]).expect("checkout main");
+        commit("main\n");
+        let root_str = root.to_string_lossy().to_string();
+
+        let clean = operation_state(&root_str).expect("clean state");
+        assert_eq!(clean.operation, GitOperation::None);
+        assert!(!clean.detached);
 
-        assert_eq!(
+        assert!(
             git(
-                &worktree,
-                &["merge-base", "--is-ancestor", "develop", "HEAD"]
-            ),
-            Ok(String::new())
+                &root,
+                &[
+                    "-c",
+                    "user.name=DevHaven",
+                    "-c",
+                    "user.email=devhaven@example.com",
+                    "merge",
+                    "feature",
+                ],
+            )
+            .is_err()
         );
-        assert!(worktree.join("BASELINE.txt").exists());
+        assert_eq!(
+            operation_state(&root_str).expect("merge state").operation,
+            GitOperation::Merge
+        );
+        git(&root, &["merge", "--abort"]).expect("merge abort");
+
+        git(&root, &["checkout", "--detach", "HEAD"]).expect("detach");
+        assert!(operation_state(&root_str).expect("detached state").detached);
 
-        let _ = fs::remove_dir_all(&worktree);
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn resolve_create_branch_start_point_prefers_origin_ref() {
+    fn continue_and_abort_operation_dispatch_on_state() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let remote = root.with_file_name(format!(
-            "{}-remote.git",
-            root.file_name().unwrap().to_string_lossy()
-        ));
-
-        fs::create_dir_all(&root).expect("create root");
-        Command::new(resolve_git_executable())
-            .args(["init", "--bare", remote.to_string_lossy().as_ref()])
-            .output()
-            .expect("init bare remote");
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init", "-b", "main"]).expect("git init");
+        git(&root, &["config", "user.name", "DevHaven"]).expect("config name");
+        git(&root, &["config", "user.email", "devhaven@example.com"]).expect("config email");
+        let commit = |content: &str| {
+            fs::write(root.join("README.md"), content).expect("write readme");
+            git(&root, &["add", "."]).expect("git add");
+            git(&root, &["commit", "-m", content]).expect("git commit");
+        };
+        commit("base\n");
+        git(&root, &["checkout", "-b", "feature"]).expect("create feature");
+        commit("feature\n");
+        git(&root, &["checkout", "main"]).expect("checkout main");
+        commit("main\n");
+        let root_str = root.to_string_lossy().to_string();
 
-        git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
-        git(&root, &["add", "."]).expect("git add");
-        git(
-            &root,
-            &[
-                "-c",
-                "user.name=DevHaven",
-                "-c",
-                "user.email=devhaven@example.com",
-                "commit",
-                "-m",
-                "init",
-            ],
-        )
-        .expect("git commit");
+        assert!(continue_operation(&root_str).is_err());
+        assert!(abort_operation(&root_str).is_err());
 
-        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
-        git(
-            &root,
-            &["remote", "add", "origin", remote.to_string_lossy().as_ref()],
-        )
-        .expect("add origin");
-        git(&root, &["checkout", "-b", "develop"]).expect("create develop");
-        fs::write(root.join("DEVELOP.md"), "develop\n").expect("write develop marker");
-        git(&root, &["add", "."]).expect("git add develop");
-        git(
-            &root,
-            &[
-                "-c",
-                "user.name=DevHaven",
-                "-c",
-                "user.email=devhaven@example.com",
-                "commit",
-                "-m",
-                "develop",
-            ],
-        )
-        .expect("git commit develop");
-        git(&root, &["push", "-u", "origin", "develop"]).expect("push develop");
-        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+        assert!(git(&root, &["merge", "feature"]).is_err());
+        assert!(continue_operation(&root_str).is_err());
+        abort_operation(&root_str).expect("abort merge");
+        assert_eq!(
+            operation_state(&root_str).expect("state").operation,
+            GitOperation::None
+        );
 
-        let start_point =
-            resolve_create_branch_start_point(root.to_string_lossy().as_ref(), "develop")
-                .expect("resolve start point");
-        assert_eq!(start_point, "origin/develop");
+        assert!(git(&root, &["merge", "feature"]).is_err());
+        fs::write(root.join("README.md"), "resolved\n").expect("resolve");
+        git(&root, &["add", "README.md"]).expect("mark resolved");
+        let status = continue_operation(&root_str).expect("continue merge");
+        assert!(status.staged.is_empty() && status.unstaged.is_empty());
+        assert_eq!(
+            operation_state(&root_str).expect("state").operation,
+            GitOperation::None
+        );
 
         let _ = fs::remove_dir_all(&root);
-        let _ = fs::remove_dir_all(&remote);
     }
 
     #[test]
-    fn resolve_create_branch_start_point_returns_error_when_branch_missing() {
+    fn stage_unstage_and_discard_all() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-
-        fs::create_dir_all(&root).expect("create root");
+        fs::create_dir_all(&root).expect("create repo dir");
         git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
-        git(&root, &["add", "."]).expect("git add");
+        let root_str = root.to_string_lossy().to_string();
+
+        fs::write(root.join("a.txt"), "a\n").expect("write a");
+        let status = stage_all(&root_str).expect("stage all without head");
+        assert_eq!(status.staged.len(), 1);
+        let status = unstage_all(&root_str).expect("unstage all without head");
+        assert!(status.staged.is_empty());
+
+        stage_all(&root_str).expect("stage all");
         git(
             &root,
             &[
@@ -1315,180 +4388,330 @@ mod tests {
         )
         .expect("git commit");
 
-        let error = resolve_create_branch_start_point(root.to_string_lossy().as_ref(), "develop")
-            .expect_err("missing base branch should fail");
-        assert!(error.contains("基线分支不可用"));
+        fs::write(root.join("a.txt"), "changed\n").expect("modify a");
+        fs::write(root.join("b.txt"), "b\n").expect("write b");
+        let status = stage_all(&root_str).expect("stage all");
+        assert_eq!(status.staged.len(), 2);
+        let status = unstage_all(&root_str).expect("unstage all");
+        assert!(status.staged.is_empty());
+        assert_eq!(status.unstaged.len(), 1);
+
+        assert!(discard_all(&root_str, false, true).is_err());
+        let status = discard_all(&root_str, true, false).expect("discard tracked");
+        assert!(status.unstaged.is_empty());
+        assert_eq!(status.untracked.len(), 1);
+        let status = discard_all(&root_str, true, true).expect("discard all");
+        assert!(status.untracked.is_empty());
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).expect("read a"),
+            "a\n"
+        );
 
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn remove_worktree_removes_directory_and_unregisters() {
+    fn clean_previews_and_requires_confirmation() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let worktree = root.with_file_name(format!(
-            "{}-wt",
-            root.file_name().unwrap().to_string_lossy()
-        ));
-
-        fs::create_dir_all(&root).expect("create root");
+        fs::create_dir_all(root.join("build")).expect("create repo dir");
         git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
-        git(&root, &["add", "."]).expect("git add");
-        git(
-            &root,
-            &[
-                "-c",
-                "user.name=DevHaven",
-                "-c",
-                "user.email=devhaven@example.com",
-                "commit",
-                "-m",
-                "init",
-            ],
-        )
-        .expect("git commit");
-
+        fs::write(root.join(".gitignore"), "build/\n").expect("write gitignore");
+        fs::write(root.join("build").join("out.o"), "o").expect("write ignored");
+        fs::write(root.join("notes.txt"), "n").expect("write untracked");
         let root_str = root.to_string_lossy().to_string();
-        let worktree_str = worktree.to_string_lossy().to_string();
-        add_worktree(&root_str, Some(&worktree_str), "feature/remove", true, None)
-            .expect("create worktree");
 
-        remove_worktree(&root_str, &worktree_str, false).expect("remove worktree");
+        let preview = clean(&root_str, false, true, &[], false).expect("preview");
+        assert!(!preview.performed);
+        assert!(preview.files.contains(&"notes.txt".to_string()));
+        assert!(!preview.files.contains(&"build/".to_string()));
+        let preview = clean(&root_str, true, true, &[], false).expect("preview ignored");
+        assert!(preview.files.contains(&"build/".to_string()));
 
-        assert!(!worktree.exists());
-        assert!(
-            list_worktrees(&root_str)
-                .expect("list worktrees")
-                .is_empty()
-        );
+        assert!(clean(&root_str, true, false, &[], false).is_err());
+        assert!(root.join("notes.txt").exists());
+
+        let removed = clean(&root_str, true, false, &["build".to_string()], true).expect("clean");
+        assert!(removed.performed);
+        assert_eq!(removed.files, vec!["build/".to_string()]);
+        assert!(!root.join("build").exists());
+        assert!(root.join("notes.txt").exists());
 
-        let _ = fs::remove_dir_all(&worktree);
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn delete_branch_removes_local_branch() {
+    fn classify_git_error_maps_common_git_failures() {
+        assert_eq!(
+            classify_git_error(
+                "fatal: not a git repository (or any of the parent directories): .git"
+            ),
+            GitErrorKind::NotARepo
+        );
+        assert_eq!(
+            classify_git_error(
+                "error: Your local changes to the following files would be overwritten by checkout:\n\ta.txt"
+            ),
+            GitErrorKind::DirtyWorktree
+        );
+        assert_eq!(
+            classify_git_error("CONFLICT (content): Merge conflict in a.txt"),
+            GitErrorKind::Conflict
+        );
+        assert_eq!(
+            classify_git_error("fatal: Could not read from remote repository."),
+            GitErrorKind::RemoteError
+        );
+        assert_eq!(
+            classify_git_error("error: pathspec 'nope' did not match any file(s) known to git"),
+            GitErrorKind::BranchNotFound
+        );
+        assert_eq!(
+            classify_git_error("执行命令失败: No such file or directory"),
+            GitErrorKind::Io
+        );
+        assert_eq!(classify_git_error("分支名不能为空"), GitErrorKind::Raw);
+    }
+
+    #[test]
+    fn checkout_branch_reports_missing_branch_and_dirty_worktree() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
 
-        fs::create_dir_all(&root).expect("create root");
-        git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
-        git(&root, &["add", "."]).expect("git add");
-        git(
-            &root,
-            &[
-                "-c",
-                "user.name=DevHaven",
-                "-c",
-                "user.email=devhaven@example.com",
-                "commit",
-                "-m",
-                "init",
-            ],
-        )
-        .expect("git commit");
+        fs::create_dir_all(&root).expect("create repo dir");
+        git(&root, &["init", "-b", "main"]).expect("git init");
+        git(&root, &["config", "user.name", "DevHaven"]).expect("config name");
+        git(&root, &["config", "user.email", "devhaven@example.com"]).expect("config email");
+        fs::write(root.join("a.txt"), "base\n").expect("write file");
+        git(&root, &["add", "a.txt"]).expect("git add");
+        git(&root, &["commit", "-m", "base"]).expect("git commit");
+        git(&root, &["checkout", "-b", "feature"]).expect("create feature");
+        fs::write(root.join("a.txt"), "feature\n").expect("write feature");
+        git(&root, &["commit", "-am", "feature"]).expect("commit feature");
+        fs::write(root.join("a.txt"), "dirty\n").expect("dirty file");
+        let root_str = root.to_string_lossy().to_string();
 
-        let default_branch = git(&root, &["branch", "--show-current"]).expect("default branch");
-        git(&root, &["checkout", "-b", "feature/delete-me"]).expect("create feature branch");
-        git(&root, &["checkout", &default_branch]).expect("checkout default branch");
+        let missing = checkout_branch(&root_str, "nope").expect_err("missing branch");
+        assert_eq!(missing.kind, GitErrorKind::BranchNotFound);
+
+        let dirty = checkout_branch(&root_str, "main").expect_err("dirty worktree");
+        assert_eq!(dirty.kind, GitErrorKind::DirtyWorktree);
+        assert!(!dirty.message.is_empty());
 
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn remote_edits_report_not_a_repo_kind() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create dir");
         let root_str = root.to_string_lossy().to_string();
-        delete_branch(&root_str, "feature/delete-me", false).expect("delete branch");
 
-        assert_eq!(
-            git(&root, &["branch", "--list", "feature/delete-me"]).expect("list branch"),
-            ""
-        );
+        let error =
+            add_remote(&root_str, "origin", "https://example.com/a.git").expect_err("not a repo");
+        assert_eq!(error.kind, GitErrorKind::NotARepo);
+        let error = set_remote_url(&root_str, "origin", "https://example.com/a.git")
+            .expect_err("not a repo");
+        assert_eq!(error.kind, GitErrorKind::NotARepo);
+
+        git(&root, &["init"]).expect("git init");
+        let error =
+            add_remote(&root_str, " ", "https://example.com/a.git").expect_err("empty name");
+        assert_eq!(error.kind, GitErrorKind::Raw);
+        assert_eq!(error.message, "远程名称不能为空");
 
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn add_worktree_rejects_non_repo_path() {
+    fn find_worktree_locates_linked_worktree_for_review() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let worktree = root.join("worktree");
-        let root_str = root.to_string_lossy().to_string();
+        let repo = root.join("repo");
+        let worktree = root.join("feature");
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init", "-b", "main"]).expect("git init");
+        git(&repo, &["config", "user.name", "DevHaven"]).expect("config name");
+        git(&repo, &["config", "user.email", "devhaven@example.com"]).expect("config email");
+        git(&repo, &["commit", "--allow-empty", "-m", "init"]).expect("init commit");
+
+        let repo_str = repo.to_string_lossy().to_string();
         let worktree_str = worktree.to_string_lossy().to_string();
+        git(&repo, &["worktree", "add", "-b", "feature", &worktree_str]).expect("add worktree");
+        fs::write(worktree.join("feature.txt"), "feature\n").expect("write feature file");
+        git(&worktree, &["add", "feature.txt"]).expect("stage feature file");
+        git(&worktree, &["commit", "-m", "feature"]).expect("feature commit");
 
-        fs::create_dir_all(&root).expect("create root");
+        let found = find_worktree(&repo_str, &format!("{worktree_str}/")).expect("find worktree");
+        assert_eq!(found.branch, "feature");
+        let diff = diff_against_base(&found.path, "main").expect("diff against base");
+        let paths: Vec<&str> = diff.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["feature.txt"]);
 
-        let err = add_worktree(&root_str, Some(&worktree_str), "feature/x", true, None)
-            .expect_err("should reject non git repo");
-        assert_eq!(err, "不是 Git 仓库");
+        assert!(find_worktree(&repo_str, &repo_str).is_err());
+        assert!(find_worktree(&repo_str, &root.join("missing").to_string_lossy()).is_err());
 
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn add_worktree_rejects_existing_target_directory() {
+    fn worktree_divergence_counts_commits_against_base_branch() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
-        let worktree = root.join("worktree");
-        let root_str = root.to_string_lossy().to_string();
+        let repo = root.join("repo");
+        let worktree = root.join("feature");
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init", "-b", "main"]).expect("git init");
+        git(&repo, &["config", "user.name", "DevHaven"]).expect("config name");
+        git(&repo, &["config", "user.email", "devhaven@example.com"]).expect("config email");
+        git(&repo, &["commit", "--allow-empty", "-m", "init"]).expect("init commit");
+
+        let repo_str = repo.to_string_lossy().to_string();
         let worktree_str = worktree.to_string_lossy().to_string();
+        git(&repo, &["worktree", "add", "-b", "feature", &worktree_str]).expect("add worktree");
+        git(&worktree, &["commit", "--allow-empty", "-m", "feature 1"]).expect("feature 1");
+        git(&worktree, &["commit", "--allow-empty", "-m", "feature 2"]).expect("feature 2");
+        git(&repo, &["commit", "--allow-empty", "-m", "main 1"]).expect("main 1");
 
-        fs::create_dir_all(&root).expect("create root");
-        git(&root, &["init"]).expect("git init");
-        fs::write(root.join("README.md"), "init\n").expect("write readme");
-        git(&root, &["add", "."]).expect("git add");
+        let divergence = worktree_divergence(&repo_str, &worktree_str, "main").expect("divergence");
+        assert_eq!(divergence.branch, "feature");
+        assert_eq!((divergence.ahead, divergence.behind), (2, 1));
+
+        let missing = worktree_divergence(&repo_str, &worktree_str, "nope").expect_err("missing");
+        assert_eq!(missing.kind, GitErrorKind::BranchNotFound);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parse_worktree_prune_output_reads_removed_entries() {
+        let entries = parse_worktree_prune_output(
+            "Removing worktrees/feature: gitdir file points to non-existent location\nRemoving worktrees/tmp: not a valid directory\n",
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "worktrees/feature");
+        assert_eq!(
+            entries[0].reason,
+            "gitdir file points to non-existent location"
+        );
+        assert_eq!(entries[1].name, "worktrees/tmp");
+        assert!(parse_worktree_prune_output("").is_empty());
+    }
+
+    #[test]
+    fn prune_worktrees_previews_then_removes_stale_entries() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        let worktree = root.join("stale");
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init", "-b", "main"]).expect("git init");
         git(
-            &root,
+            &repo,
             &[
                 "-c",
                 "user.name=DevHaven",
                 "-c",
                 "user.email=devhaven@example.com",
                 "commit",
+                "--allow-empty",
                 "-m",
                 "init",
             ],
         )
-        .expect("git commit");
-        fs::create_dir_all(&worktree).expect("create existing worktree dir");
+        .expect("init commit");
+        let worktree_str = worktree.to_string_lossy().to_string();
+        git(&repo, &["worktree", "add", "-b", "stale", &worktree_str]).expect("add worktree");
+        fs::remove_dir_all(&worktree).expect("remove worktree dir");
 
-        let err = add_worktree(&root_str, Some(&worktree_str), "feature/x", true, None)
-            .expect_err("should reject existing target");
-        assert_eq!(err, "目标目录已存在，无法创建 worktree");
+        let repo_str = repo.to_string_lossy().to_string();
+        let preview = prune_worktrees(&repo_str, true).expect("dry run");
+        assert!(!preview.performed);
+        assert_eq!(preview.entries.len(), 1);
+        assert_eq!(list_worktrees(&repo_str).expect("list").len(), 1);
+
+        let pruned = prune_worktrees(&repo_str, false).expect("prune");
+        assert!(pruned.performed);
+        assert_eq!(pruned.entries, preview.entries);
+        assert!(list_worktrees(&repo_str).expect("list").is_empty());
 
         let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn parse_worktree_list_output_filters_base_and_detached() {
-        let output = r#"
-worktree /repo/main
-HEAD 111111
-branch refs/heads/main
+    fn prune_worktrees_output_is_parsed_under_localized_env() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        let worktree = root.join("stale");
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init", "-b", "main"]).expect("git init");
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=DevHaven",
+                "-c",
+                "user.email=devhaven@example.com",
+                "commit",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        )
+        .expect("init commit");
+        let worktree_str = worktree.to_string_lossy().to_string();
+        git(&repo, &["worktree", "add", "-b", "stale", &worktree_str]).expect("add worktree");
+        fs::remove_dir_all(&worktree).expect("remove worktree dir");
 
-worktree /repo/feature-a
-HEAD 222222
-branch refs/heads/feature/a
+        let env = fake_zh_git_locale(
+            &root.join("locale"),
+            &[("Removing %s/%s: %s", "删除 %s/%s：%s")],
+        );
+        if !git_uses_fake_locale(&repo, &env, &worktree_prune_args(true), "Removing ") {
+            eprintln!("git 未启用 gettext，跳过本地化测试");
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
 
-worktree /repo/detached
-HEAD 333333
-detached
-"#;
+        let output = git_command()
+            .args(worktree_prune_args(true))
+            .envs(env.iter().map(|(key, value)| (*key, value)))
+            .current_dir(&repo)
+            .output()
+            .expect("run prune");
+        assert!(output.status.success());
+        let entries = parse_worktree_prune_output(&String::from_utf8_lossy(&output.stderr));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "worktrees/stale");
 
-        let items = parse_worktree_list_output("/repo/main", output);
-        assert_eq!(items.len(), 1);
-        assert_eq!(items[0].path, "/repo/feature-a");
-        assert_eq!(items[0].branch, "feature/a");
+        let _ = fs::remove_dir_all(&root);
     }
 
     #[test]
-    fn parse_worktree_list_output_accepts_branch_without_refs_prefix() {
-        let output = r#"
-worktree /repo/main
-HEAD 111111
-branch refs/heads/main
+    fn clean_preview_is_parsed_under_localized_env() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        fs::create_dir_all(&repo).expect("create repo dir");
+        git(&repo, &["init"]).expect("git init");
+        fs::write(repo.join("notes.txt"), "n").expect("write untracked");
+
+        let env = fake_zh_git_locale(
+            &root.join("locale"),
+            &[("Would remove %s\n", "将删除 %s\n")],
+        );
+        let args = clean_args(false, true, &[]);
+        if !git_uses_fake_locale(&repo, &env, &args, "Would remove ") {
+            eprintln!("git 未启用 gettext，跳过本地化测试");
+            let _ = fs::remove_dir_all(&root);
+            return;
+        }
 
-worktree /repo/release
-HEAD 222222
-branch release/1.0
-"#;
+        let output = git_command()
+            .args(&args)
+            .envs(env.iter().map(|(key, value)| (*key, value)))
+            .current_dir(&repo)
+            .output()
+            .expect("run clean");
+        assert!(output.status.success());
+        let files = parse_clean_output(&String::from_utf8_lossy(&output.stdout));
+        assert_eq!(files, vec!["notes.txt".to_string()]);
+        assert!(repo.join("notes.txt").exists());
 
-        let items = parse_worktree_list_output("/repo/main", output);
-        assert_eq!(items.len(), 1);
-        assert_eq!(items[0].path, "/repo/release");
-        assert_eq!(items[0].branch, "release/1.0");
+        let _ = fs::remove_dir_all(&root);
     }
 }
@@ -1,5 +1,8 @@
 mod codex_monitor;
+mod diagnostics;
+mod file_tail;
 mod filesystem;
+mod git_clone;
 mod git_daily;
 mod git_ops;
 mod interaction_lock;
@@ -7,6 +10,8 @@ mod markdown;
 mod models;
 mod notes;
 mod project_loader;
+mod project_watcher;
+mod script_runner;
 mod storage;
 mod system;
 mod terminal;
@@ -14,30 +19,55 @@ mod time_utils;
 mod worktree_init;
 mod worktree_setup;
 
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 use tauri::AppHandle;
+use tauri::Emitter;
 use tauri::Manager;
 use tauri::State;
 use tauri_plugin_log::{Target, TargetKind};
 
 use crate::models::{
-    AppStateFile, BranchListItem, CodexMonitorSnapshot, FsListResponse, FsReadResponse,
-    FsWriteResponse, GitDailyResult, GitDiffContents, GitIdentity, GitRepoStatus,
-    GitWorktreeAddResult, GitWorktreeListItem, HeatmapCacheFile, InteractionLockPayload,
-    MarkdownFileEntry, Project, ProjectNotesPreview, TerminalCodexPaneOverlay, TerminalWorkspace,
+    AppStateFile, AppWindowInfo, BranchListItem, CodexMonitorSnapshot, CodexSessionFilter,
+    CodexSessionOpenResult, CommitValidationError, DataBackupResult, DetectedEditor,
+    FsListResponse, FsReadResponse, FsWriteResponse, FullscreenAuxiliaryResult, GitAheadBehind,
+    GitBaseDiff, GitBranchCheckedOutPayload, GitCleanResult, GitCloneJobStatus,
+    GitCloneStartRequest, GitConfigScope, GitConflictContents, GitContributor, GitDailyResult,
+    GitDiffContents, GitDiffOptions, GitDiffStatEntry, GitError, GitHeadInfo, GitIdentity,
+    GitOperationState, GitRemote, GitRenameDetection, GitRepoHealth, GitRepoIdentity, GitRepoStats,
+    GitRepoStatus, GitResetMode, GitResetResult, GitTagItem, GitUntrackedMode,
+    GitWorktreeAddResult, GitWorktreeDivergence, GitWorktreeListItem, GitWorktreePruneResult,
+    GitWorktreeReview, HeatmapCacheFile, InteractionLockPayload, LogFileInfo, MarkdownFileEntry,
+    OpenToolSettings, OpenToolValidation, Project, ProjectNotesPreview, ProjectPage,
+    ProjectPruneResult, ProjectScriptAction, ProjectScriptRunResult, ProjectSearchResult,
+    ProjectSortKey, RecycleBinResult, RunningScriptStatus, TagAssignAction, TagData,
+    TagUpdateResult, TerminalCodexPaneOverlay, TerminalPaneCommandStatus, TerminalWorkspace,
     TerminalWorkspaceSummary, WorktreeInitCancelResult, WorktreeInitCreateBlockingResult,
     WorktreeInitJobStatus, WorktreeInitRetryRequest, WorktreeInitStartRequest,
     WorktreeInitStartResult, WorktreeInitStatusQuery, WorktreeInitStep,
 };
 use crate::system::EditorOpenParams;
 use crate::terminal::{
-    TerminalState, terminal_create_session, terminal_get_codex_pane_overlay, terminal_kill,
-    terminal_resize, terminal_write,
+    TerminalState, terminal_create_session, terminal_get_codex_pane_overlay,
+    terminal_get_pane_command_status, terminal_get_session_metadata, terminal_kill,
+    terminal_resize, terminal_run_command, terminal_write,
 };
 
 const INTERACTION_LOCK_REASON_WORKTREE_CREATE: &str = "worktree-create";
+const LOG_LEVEL_ENV: &str = "DEVHAVEN_LOG_LEVEL";
+// 在 debug/trace 级别输出量很大的依赖库。
+const NOISY_LOG_TARGETS: &[&str] = &[
+    "hyper", "mio", "notify", "reqwest", "tao", "tokio", "tracing", "wry",
+];
+
+/// 记录各窗口期望的全屏辅助状态，便于窗口聚焦或重新加载后自动重新应用。
+#[derive(Default)]
+struct FullscreenAuxiliaryState {
+    desired: Mutex<HashMap<String, bool>>,
+}
 
 #[tauri::command]
 /// 读取应用状态。
@@ -48,7 +78,11 @@ fn load_app_state(app: AppHandle) -> Result<AppStateFile, String> {
 #[tauri::command]
 /// 保存应用状态。
 fn save_app_state(app: AppHandle, state: AppStateFile) -> Result<(), String> {
-    log_command_result("save_app_state", || storage::save_app_state(&app, &state))
+    log_command_result("save_app_state", || {
+        storage::save_app_state(&app, &state)?;
+        git_ops::set_git_executable_override(state.settings.git_path.as_deref());
+        Ok(())
+    })
 }
 
 #[tauri::command]
@@ -57,12 +91,354 @@ fn load_projects(app: AppHandle) -> Result<Vec<Project>, String> {
     log_command_result("load_projects", || storage::load_projects(&app))
 }
 
+#[tauri::command]
+/// 读取项目缓存并在后端排序，默认按修改时间倒序。
+fn list_projects_sorted(
+    app: AppHandle,
+    sort_by: Option<ProjectSortKey>,
+    descending: Option<bool>,
+) -> Result<Vec<Project>, String> {
+    log_command_result("list_projects_sorted", || {
+        let sort_by = sort_by.unwrap_or_default();
+        let descending = descending.unwrap_or(true);
+        log::info!(
+            "list_projects_sorted sort_by={:?} descending={}",
+            sort_by,
+            descending
+        );
+        let mut projects = storage::load_projects(&app)?;
+        project_loader::sort_projects(&mut projects, sort_by, descending);
+        Ok(projects)
+    })
+}
+
+#[tauri::command]
+/// 分页读取项目：先按搜索词模糊过滤并排序，再按 offset/limit 截取，同时返回总数。
+fn list_projects_page(
+    app: AppHandle,
+    offset: usize,
+    limit: usize,
+    sort_by: Option<ProjectSortKey>,
+    descending: Option<bool>,
+    query: Option<String>,
+) -> Result<ProjectPage, String> {
+    log_command_result("list_projects_page", || {
+        let query = query.unwrap_or_default();
+        let descending = descending.unwrap_or(true);
+        log::info!(
+            "list_projects_page offset={} limit={} sort_by={:?} descending={} query_len={}",
+            offset,
+            limit,
+            sort_by,
+            descending,
+            query.chars().count()
+        );
+        let projects = storage::load_projects(&app)?;
+        Ok(project_loader::paginate_projects(
+            projects, &query, sort_by, descending, offset, limit,
+        ))
+    })
+}
+
+#[tauri::command]
+/// 重新统计单个项目的 Git 提交信息并写回项目缓存，返回更新后的项目。
+fn refresh_project(app: AppHandle, project_id: String) -> Result<Project, String> {
+    log_command_result("refresh_project", || {
+        log::info!("refresh_project id={}", project_id);
+        let mut projects = storage::load_projects(&app)?;
+        let project = project_loader::refresh_project_git_stats(&mut projects, &project_id)?;
+        storage::save_projects(&app, &projects)?;
+        Ok(project)
+    })
+}
+
 #[tauri::command]
 /// 保存项目缓存列表。
 fn save_projects(app: AppHandle, projects: Vec<Project>) -> Result<(), String> {
     log_command_result("save_projects", || storage::save_projects(&app, &projects))
 }
 
+#[tauri::command]
+/// 清理项目缓存中路径已失效或 id 重复的条目（同步清理回收站），返回被移除与回收站中的项目。
+fn prune_missing_projects(app: AppHandle) -> Result<ProjectPruneResult, String> {
+    log_command_result("prune_missing_projects", || {
+        let mut state = storage::load_app_state(&app)?;
+        let projects = storage::load_projects(&app)?;
+        let recycle_bin_len = state.recycle_bin.len();
+        let (kept, result) =
+            project_loader::prune_missing_projects(projects, &mut state.recycle_bin);
+        log::info!(
+            "prune_missing_projects removed={} duplicates={} recycled={}",
+            result.removed.len(),
+            result.duplicates.len(),
+            result.recycled.len()
+        );
+        if !result.removed.is_empty() || !result.duplicates.is_empty() {
+            storage::save_projects(&app, &kept)?;
+        }
+        if state.recycle_bin.len() != recycle_bin_len {
+            storage::save_app_state(&app, &state)?;
+        }
+        Ok(result)
+    })
+}
+
+#[tauri::command]
+/// 将项目移入回收站并落盘，返回最新的回收站与项目缓存。
+fn recycle_project(app: AppHandle, project_id: String) -> Result<RecycleBinResult, String> {
+    log_command_result("recycle_project", || {
+        log::info!("recycle_project id={}", project_id);
+        let mut state = storage::load_app_state(&app)?;
+        let projects = storage::load_projects(&app)?;
+        project_loader::recycle_project(&mut state.recycle_bin, &projects, &project_id)?;
+        storage::save_app_state(&app, &state)?;
+        Ok(RecycleBinResult {
+            recycle_bin: state.recycle_bin,
+            directories: state.directories,
+            projects,
+            purged: Vec::new(),
+            errors: Vec::new(),
+        })
+    })
+}
+
+#[tauri::command]
+/// 从回收站恢复项目路径，项目缓存中缺失时重新构建条目，路径不在扫描目录范围内时加入目录列表。
+fn restore_from_recycle_bin(app: AppHandle, path: String) -> Result<RecycleBinResult, String> {
+    log_command_result("restore_from_recycle_bin", || {
+        log::info!("restore_from_recycle_bin path={}", path);
+        let mut state = storage::load_app_state(&app)?;
+        let mut projects = storage::load_projects(&app)?;
+        project_loader::restore_recycled_path(
+            &mut state.recycle_bin,
+            &mut state.directories,
+            &mut projects,
+            &path,
+        )?;
+        storage::save_projects(&app, &projects)?;
+        storage::save_app_state(&app, &state)?;
+        Ok(RecycleBinResult {
+            recycle_bin: state.recycle_bin,
+            directories: state.directories,
+            projects,
+            purged: Vec::new(),
+            errors: Vec::new(),
+        })
+    })
+}
+
+#[tauri::command]
+/// 从回收站彻底移除指定路径并删除项目缓存；move_to_trash 为 true 时同时将目录移到系统废纸篓。
+fn purge_recycle_bin(
+    app: AppHandle,
+    paths: Vec<String>,
+    move_to_trash: Option<bool>,
+) -> Result<RecycleBinResult, String> {
+    log_command_result("purge_recycle_bin", || {
+        log::info!(
+            "purge_recycle_bin paths={} move_to_trash={:?}",
+            paths.len(),
+            move_to_trash
+        );
+        purge_recycle_bin_paths(&app, &paths, move_to_trash.unwrap_or(false))
+    })
+}
+
+#[tauri::command]
+/// 清空回收站；move_to_trash 为 true 时同时将目录移到系统废纸篓。
+fn empty_recycle_bin(
+    app: AppHandle,
+    move_to_trash: Option<bool>,
+) -> Result<RecycleBinResult, String> {
+    log_command_result("empty_recycle_bin", || {
+        log::info!("empty_recycle_bin move_to_trash={:?}", move_to_trash);
+        let paths = storage::load_app_state(&app)?.recycle_bin;
+        purge_recycle_bin_paths(&app, &paths, move_to_trash.unwrap_or(false))
+    })
+}
+
+#[tauri::command]
+/// 重命名标签并同步更新所有项目中的引用。
+fn rename_tag(
+    app: AppHandle,
+    old_name: String,
+    new_name: String,
+) -> Result<TagUpdateResult, String> {
+    log_command_result("rename_tag", || {
+        log::info!("rename_tag from={} to={}", old_name, new_name);
+        update_tags_and_projects(&app, |tags, projects| {
+            project_loader::rename_tag(tags, projects, &old_name, &new_name)
+        })
+    })
+}
+
+#[tauri::command]
+/// 将标签 from 合并到 into，并同步更新所有项目中的引用。
+fn merge_tags(app: AppHandle, from: String, into: String) -> Result<TagUpdateResult, String> {
+    log_command_result("merge_tags", || {
+        log::info!("merge_tags from={} into={}", from, into);
+        update_tags_and_projects(&app, |tags, projects| {
+            project_loader::merge_tags(tags, projects, &from, &into)
+        })
+    })
+}
+
+#[tauri::command]
+/// 删除标签，可选同时移除项目中的引用。
+fn delete_tag(
+    app: AppHandle,
+    name: String,
+    remove_from_projects: bool,
+) -> Result<TagUpdateResult, String> {
+    log_command_result("delete_tag", || {
+        log::info!(
+            "delete_tag name={} remove_from_projects={}",
+            name,
+            remove_from_projects
+        );
+        update_tags_and_projects(&app, |tags, projects| {
+            project_loader::delete_tag(tags, projects, &name, remove_from_projects)
+        })
+    })
+}
+
+#[tauri::command]
+/// 为一组项目批量添加或移除标签，返回发生变化的项目。
+fn assign_tag(
+    app: AppHandle,
+    project_ids: Vec<String>,
+    tag_name: String,
+    action: TagAssignAction,
+) -> Result<Vec<Project>, String> {
+    log_command_result("assign_tag", || {
+        log::info!(
+            "assign_tag tag={} projects={} action={:?}",
+            tag_name,
+            project_ids.len(),
+            action
+        );
+        let state = storage::load_app_state(&app)?;
+        let mut projects = storage::load_projects(&app)?;
+        let updated = project_loader::assign_tag(
+            &state.tags,
+            &mut projects,
+            &project_ids,
+            &tag_name,
+            action,
+        )?;
+        if !updated.is_empty() {
+            storage::save_projects(&app, &projects)?;
+        }
+        Ok(updated)
+    })
+}
+
+#[tauri::command]
+/// 按名称、路径与标签模糊搜索项目，返回带命中区间的排序结果。
+fn search_projects(
+    projects: Vec<Project>,
+    query: String,
+    tags_filter: Option<Vec<String>>,
+) -> Vec<ProjectSearchResult> {
+    log_command("search_projects", || {
+        let tags_filter = tags_filter.unwrap_or_default();
+        log::info!(
+            "search_projects projects={} query_len={} tags={}",
+            projects.len(),
+            query.chars().count(),
+            tags_filter.len()
+        );
+        project_loader::search_projects(&projects, &query, &tags_filter)
+    })
+}
+
+// 切换分支后广播新分支与 HEAD，订阅的日志/状态视图据此刷新而无需轮询。
+fn emit_branch_checked_out(app: &AppHandle, path: &str, branch: &str) {
+    let head = match git_ops::head_info(path) {
+        Ok(head) => head,
+        Err(error) => {
+            log::warn!("读取切换后的 HEAD 失败 path={}: {}", path, error);
+            return;
+        }
+    };
+    let payload = GitBranchCheckedOutPayload {
+        path: path.to_string(),
+        branch: head.branch.unwrap_or_else(|| branch.trim().to_string()),
+        commit: head.commit,
+        short_commit: head.short_commit,
+    };
+    if let Err(error) = app.emit(git_ops::BRANCH_CHECKED_OUT_EVENT, payload) {
+        log::warn!("发送 branch-checked-out 失败: {}", error);
+    }
+}
+
+// 在同一次读写中更新标签列表与项目缓存，保证标签引用一致。
+fn update_tags_and_projects(
+    app: &AppHandle,
+    update: impl FnOnce(&mut Vec<TagData>, &mut [Project]) -> Result<usize, String>,
+) -> Result<TagUpdateResult, String> {
+    let mut state = storage::load_app_state(app)?;
+    let mut projects = storage::load_projects(app)?;
+    let affected_projects = update(&mut state.tags, &mut projects)?;
+    if affected_projects > 0 {
+        storage::save_projects(app, &projects)?;
+    }
+    storage::save_app_state(app, &state)?;
+    Ok(TagUpdateResult {
+        tags: state.tags,
+        projects,
+        affected_projects,
+    })
+}
+
+// 彻底移除回收站中的路径；需要移到废纸篓时，移动失败的路径保留在回收站中并记录错误。
+fn purge_recycle_bin_paths(
+    app: &AppHandle,
+    paths: &[String],
+    move_to_trash: bool,
+) -> Result<RecycleBinResult, String> {
+    let mut state = storage::load_app_state(app)?;
+    let mut projects = storage::load_projects(app)?;
+
+    let mut errors = Vec::new();
+    let targets: Vec<String> = if move_to_trash {
+        paths
+            .iter()
+            .filter(|path| state.recycle_bin.contains(path))
+            .filter(|path| {
+                if !std::path::Path::new(path.as_str()).exists() {
+                    return true;
+                }
+                match system::move_to_trash(path) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        log::warn!("移到废纸篓失败 path={} err={}", path, error);
+                        errors.push(format!("{path}: {error}"));
+                        false
+                    }
+                }
+            })
+            .cloned()
+            .collect()
+    } else {
+        paths.to_vec()
+    };
+
+    let purged =
+        project_loader::purge_recycled_paths(&mut state.recycle_bin, &mut projects, &targets);
+    if !purged.is_empty() {
+        storage::save_projects(app, &projects)?;
+        storage::save_app_state(app, &state)?;
+    }
+    Ok(RecycleBinResult {
+        recycle_bin: state.recycle_bin,
+        directories: state.directories,
+        projects,
+        purged,
+        errors,
+    })
+}
+
 #[tauri::command]
 /// 扫描工作目录，发现项目路径。
 fn discover_projects(directories: Vec<String>) -> Vec<String> {
@@ -85,6 +461,79 @@ fn build_projects(paths: Vec<String>, existing: Vec<Project>) -> Vec<Project> {
     })
 }
 
+#[tauri::command]
+/// 计算项目占用大小（默认排除 .git 与被 .gitignore 忽略的文件）。
+fn compute_project_size(
+    path: String,
+    exclude_git: Option<bool>,
+    respect_gitignore: Option<bool>,
+) -> u64 {
+    let exclude_git = exclude_git.unwrap_or(true);
+    let respect_gitignore = respect_gitignore.unwrap_or(true);
+    log_command("compute_project_size", || {
+        log::info!(
+            "compute_project_size path={} exclude_git={} respect_gitignore={}",
+            path,
+            exclude_git,
+            respect_gitignore
+        );
+        project_loader::compute_project_size(&path, exclude_git, respect_gitignore)
+    })
+}
+
+#[tauri::command]
+/// 开始监听项目目录，文件变更时广播 `project-changed` 事件。
+fn watch_project(
+    app: AppHandle,
+    state: State<project_watcher::ProjectWatcherState>,
+    path: String,
+) -> Result<(), String> {
+    log_command_result("watch_project", || {
+        log::info!("watch_project path={}", path);
+        state.watch(&app, &path)
+    })
+}
+
+#[tauri::command]
+/// 停止监听项目目录，返回是否存在对应监听。
+fn unwatch_project(
+    state: State<project_watcher::ProjectWatcherState>,
+    path: String,
+) -> Result<bool, String> {
+    log_command_result("unwatch_project", || {
+        log::info!("unwatch_project path={}", path);
+        state.unwatch(&path)
+    })
+}
+
+#[tauri::command]
+/// 启动或停止项目脚本；输出通过 `project-script-output` 事件推送。
+fn run_project_script(
+    app: AppHandle,
+    state: State<script_runner::ProjectScriptState>,
+    project_path: String,
+    script_id: String,
+    action: ProjectScriptAction,
+) -> Result<ProjectScriptRunResult, String> {
+    log_command_result("run_project_script", || {
+        log::info!(
+            "run_project_script path={} script={} action={:?}",
+            project_path,
+            script_id,
+            action
+        );
+        state.run(&app, &project_path, &script_id, action)
+    })
+}
+
+#[tauri::command]
+/// 列出正在运行的项目脚本，并回收已退出的进程。
+fn list_running_scripts(
+    state: State<script_runner::ProjectScriptState>,
+) -> Result<Vec<RunningScriptStatus>, String> {
+    log_command_result("list_running_scripts", || state.list_running())
+}
+
 #[tauri::command]
 /// 获取分支列表。
 fn list_branches(base_path: String) -> Vec<BranchListItem> {
@@ -104,101 +553,535 @@ fn git_is_repo(path: String) -> bool {
 }
 
 #[tauri::command]
-/// 获取 Git 仓库状态（分支 + staged/unstaged/untracked）。
-fn git_get_status(path: String) -> Result<GitRepoStatus, String> {
-    log_command_result("git_get_status", || {
-        log::info!("git_get_status path={}", path);
-        git_ops::get_repo_status(&path)
+/// 在普通目录中初始化 Git 仓库，可选写入 README 并使用指定身份完成首次提交。
+fn git_init(
+    path: String,
+    initial_branch: Option<String>,
+    create_readme: Option<bool>,
+    identity: Option<GitIdentity>,
+) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_init", || {
+        let initial_branch = initial_branch.unwrap_or_else(|| "main".to_string());
+        let create_readme = create_readme.unwrap_or(false);
+        log::info!(
+            "git_init path={} branch={} create_readme={}",
+            path,
+            initial_branch,
+            create_readme
+        );
+        git_ops::init_repo(&path, &initial_branch, create_readme, identity.as_ref())
+    })
+}
+
+#[tauri::command]
+/// 获取 Git 仓库状态（分支 + staged/unstaged/untracked），1 秒内重复请求复用缓存，force 时强制刷新。
+fn git_get_status(
+    path: String,
+    untracked_mode: Option<GitUntrackedMode>,
+    force: Option<bool>,
+    renames: Option<GitRenameDetection>,
+) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_get_status", || {
+        let untracked_mode = untracked_mode.unwrap_or_default();
+        let force = force.unwrap_or(false);
+        let renames = renames.unwrap_or_default();
+        log::info!(
+            "git_get_status path={} untracked={:?} force={} renames={:?}",
+            path,
+            untracked_mode,
+            force,
+            renames
+        );
+        git_ops::get_repo_status_cached(&path, untracked_mode, renames, force)
+    })
+}
+
+#[tauri::command]
+/// 仅查询当前分支相对上游的领先/落后数（未设置上游时 hasUpstream=false）。
+fn git_ahead_behind(path: String) -> Result<GitAheadBehind, GitError> {
+    log_command_result("git_ahead_behind", || git_ops::ahead_behind(&path))
+}
+
+#[tauri::command]
+/// 检测仓库是否处于 rebase/merge/cherry-pick 等进行中的操作，以及 HEAD 是否游离。
+fn git_operation_state(path: String) -> Result<GitOperationState, GitError> {
+    log_command_result("git_operation_state", || git_ops::operation_state(&path))
+}
+
+#[tauri::command]
+/// 继续进行中的 rebase/merge/cherry-pick/revert，返回最新仓库状态。
+fn git_operation_continue(path: String) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_operation_continue", || {
+        log::info!("git_operation_continue path={}", path);
+        git_ops::continue_operation(&path)
+    })
+}
+
+#[tauri::command]
+/// 中止进行中的 rebase/merge/cherry-pick/revert 或结束 bisect，返回最新仓库状态。
+fn git_operation_abort(path: String) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_operation_abort", || {
+        log::info!("git_operation_abort path={}", path);
+        git_ops::abort_operation(&path)
+    })
+}
+
+#[tauri::command]
+/// 获取 HEAD 信息（提交、分支、是否游离）。
+fn git_head_info(path: String) -> Result<GitHeadInfo, GitError> {
+    log_command_result("git_head_info", || {
+        log::info!("git_head_info path={}", path);
+        git_ops::head_info(&path)
+    })
+}
+
+#[tauri::command]
+/// 获取当前 HEAD 相对于与基准分支分叉点的变更文件，用于分支评审。
+fn git_diff_against_base(path: String, base_branch: String) -> Result<GitBaseDiff, GitError> {
+    log_command_result("git_diff_against_base", || {
+        log::info!("git_diff_against_base path={} base={}", path, base_branch);
+        git_ops::diff_against_base(&path, &base_branch)
+    })
+}
+
+#[tauri::command]
+/// 获取仓库提交时生效的身份（user.name/user.email）及其配置层级。
+fn git_get_identity(path: String) -> Result<GitRepoIdentity, GitError> {
+    log_command_result("git_get_identity", || {
+        log::info!("git_get_identity path={}", path);
+        git_ops::get_identity(&path)
+    })
+}
+
+#[tauri::command]
+/// 设置仓库或全局的提交身份（scope 仅支持 local/global）。
+fn git_set_identity(
+    path: String,
+    identity: GitIdentity,
+    scope: GitConfigScope,
+) -> Result<(), GitError> {
+    log_command_result("git_set_identity", || {
+        log::info!(
+            "git_set_identity path={} email={} scope={:?}",
+            path,
+            identity.email,
+            scope
+        );
+        git_ops::set_identity(&path, &identity, scope)
+    })
+}
+
+#[tauri::command]
+/// 检查仓库健康状况（index.lock 残留、fsck 连通性错误）。
+fn git_repo_health(path: String) -> Result<GitRepoHealth, GitError> {
+    log_command_result("git_repo_health", || {
+        log::info!("git_repo_health path={}", path);
+        git_ops::repo_health(&path)
+    })
+}
+
+#[tauri::command]
+/// 统计仓库提交数、最后提交时间与贡献者数量，空仓库返回 0。
+fn git_repo_stats(path: String) -> Result<GitRepoStats, GitError> {
+    log_command_result("git_repo_stats", || {
+        log::info!("git_repo_stats path={}", path);
+        git_ops::repo_stats(&path)
+    })
+}
+
+#[tauri::command]
+/// 按提交数倒序列出仓库贡献者，可用 since 限定起始日期。
+fn git_contributors(path: String, since: Option<String>) -> Result<Vec<GitContributor>, GitError> {
+    log_command_result("git_contributors", || {
+        log::info!("git_contributors path={} since={:?}", path, since);
+        git_ops::contributors(&path, since.as_deref())
+    })
+}
+
+#[tauri::command]
+/// 删除残留的 index.lock，返回是否实际删除。
+fn git_remove_index_lock(path: String) -> Result<bool, GitError> {
+    log_command_result("git_remove_index_lock", || {
+        log::info!("git_remove_index_lock path={}", path);
+        git_ops::remove_index_lock(&path)
+    })
+}
+
+#[tauri::command]
+/// 获取单文件对比内容（original/modified），用于 UI 渲染对比视图。
+fn git_get_diff_contents(
+    path: String,
+    relative_path: String,
+    staged: bool,
+    old_relative_path: Option<String>,
+    options: Option<GitDiffOptions>,
+) -> Result<GitDiffContents, GitError> {
+    log_command_result("git_get_diff_contents", || {
+        log::info!(
+            "git_get_diff_contents path={} file={} staged={}",
+            path,
+            relative_path,
+            staged
+        );
+        git_ops::get_diff_contents(
+            &path,
+            &relative_path,
+            staged,
+            old_relative_path.as_deref(),
+            &options.unwrap_or_default(),
+        )
+    })
+}
+
+#[tauri::command]
+/// 获取冲突文件的 base/ours/theirs 三方内容，用于三方合并编辑。
+fn git_get_conflict_contents(
+    path: String,
+    relative_path: String,
+) -> Result<GitConflictContents, GitError> {
+    log_command_result("git_get_conflict_contents", || {
+        log::info!(
+            "git_get_conflict_contents path={} file={}",
+            path,
+            relative_path
+        );
+        git_ops::get_conflict_contents(&path, &relative_path)
+    })
+}
+
+#[tauri::command]
+/// 获取改动统计（每个文件的新增/删除行数）。
+fn git_diff_stat(path: String, staged: bool) -> Result<Vec<GitDiffStatEntry>, GitError> {
+    log_command_result("git_diff_stat", || {
+        log::info!("git_diff_stat path={} staged={}", path, staged);
+        git_ops::diff_stat(&path, staged)
+    })
+}
+
+#[tauri::command]
+/// 暂存文件（git add）。
+fn git_stage_files(path: String, relative_paths: Vec<String>) -> Result<(), GitError> {
+    log_command_result("git_stage_files", || {
+        log::info!(
+            "git_stage_files path={} files={}",
+            path,
+            relative_paths.len()
+        );
+        git_ops::stage_files(&path, &relative_paths)
+    })
+}
+
+#[tauri::command]
+/// 标记冲突已解决（检查冲突标记后暂存）。
+fn git_mark_resolved(path: String, relative_paths: Vec<String>) -> Result<(), GitError> {
+    log_command_result("git_mark_resolved", || {
+        log::info!(
+            "git_mark_resolved path={} files={}",
+            path,
+            relative_paths.len()
+        );
+        git_ops::mark_resolved(&path, &relative_paths)
+    })
+}
+
+#[tauri::command]
+/// 暂存单个 hunk（git apply --cached）。
+fn git_stage_hunk(path: String, relative_path: String, patch: String) -> Result<(), GitError> {
+    log_command_result("git_stage_hunk", || {
+        log::info!("git_stage_hunk path={} file={}", path, relative_path);
+        git_ops::stage_hunk(&path, &relative_path, &patch)
+    })
+}
+
+#[tauri::command]
+/// 取消暂存单个 hunk（git apply --cached --reverse）。
+fn git_unstage_hunk(path: String, relative_path: String, patch: String) -> Result<(), GitError> {
+    log_command_result("git_unstage_hunk", || {
+        log::info!("git_unstage_hunk path={} file={}", path, relative_path);
+        git_ops::unstage_hunk(&path, &relative_path, &patch)
+    })
+}
+
+#[tauri::command]
+/// 取消暂存（git reset HEAD --）。
+fn git_unstage_files(path: String, relative_paths: Vec<String>) -> Result<(), GitError> {
+    log_command_result("git_unstage_files", || {
+        log::info!(
+            "git_unstage_files path={} files={}",
+            path,
+            relative_paths.len()
+        );
+        git_ops::unstage_files(&path, &relative_paths)
+    })
+}
+
+#[tauri::command]
+/// 丢弃未暂存修改（git checkout --）。
+fn git_discard_files(path: String, relative_paths: Vec<String>) -> Result<(), GitError> {
+    log_command_result("git_discard_files", || {
+        log::info!(
+            "git_discard_files path={} files={}",
+            path,
+            relative_paths.len()
+        );
+        git_ops::discard_files(&path, &relative_paths)
+    })
+}
+
+#[tauri::command]
+/// 暂存全部改动（git add -A），返回最新仓库状态。
+fn git_stage_all(path: String) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_stage_all", || {
+        log::info!("git_stage_all path={}", path);
+        git_ops::stage_all(&path)
+    })
+}
+
+#[tauri::command]
+/// 取消暂存全部改动（git reset），返回最新仓库状态。
+fn git_unstage_all(path: String) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_unstage_all", || {
+        log::info!("git_unstage_all path={}", path);
+        git_ops::unstage_all(&path)
+    })
+}
+
+#[tauri::command]
+/// 丢弃全部未暂存修改（需 confirm=true），include_untracked 时同时清理未跟踪文件。
+fn git_discard_all(
+    path: String,
+    confirm: bool,
+    include_untracked: Option<bool>,
+) -> Result<GitRepoStatus, GitError> {
+    log_command_result("git_discard_all", || {
+        let include_untracked = include_untracked.unwrap_or(false);
+        log::info!(
+            "git_discard_all path={} confirm={} include_untracked={}",
+            path,
+            confirm,
+            include_untracked
+        );
+        git_ops::discard_all(&path, confirm, include_untracked)
+    })
+}
+
+#[tauri::command]
+/// 删除未跟踪文件（git clean -fd [-x]）；dry_run 时仅返回将被删除的列表，实际删除需 confirm=true。
+fn git_clean(
+    path: String,
+    include_ignored: Option<bool>,
+    dry_run: Option<bool>,
+    paths: Option<Vec<String>>,
+    confirm: Option<bool>,
+) -> Result<GitCleanResult, GitError> {
+    log_command_result("git_clean", || {
+        let include_ignored = include_ignored.unwrap_or(false);
+        let dry_run = dry_run.unwrap_or(true);
+        let paths = paths.unwrap_or_default();
+        log::info!(
+            "git_clean path={} include_ignored={} dry_run={} paths={}",
+            path,
+            include_ignored,
+            dry_run,
+            paths.len()
+        );
+        git_ops::clean(
+            &path,
+            include_ignored,
+            dry_run,
+            &paths,
+            confirm.unwrap_or(false),
+        )
+    })
+}
+
+#[tauri::command]
+/// 将文件恢复到 HEAD 状态（可分别选择暂存区/工作区）。
+fn git_restore_file(
+    path: String,
+    relative_path: String,
+    staged: bool,
+    worktree: bool,
+) -> Result<(), GitError> {
+    log_command_result("git_restore_file", || {
+        log::info!(
+            "git_restore_file path={} file={} staged={} worktree={}",
+            path,
+            relative_path,
+            staged,
+            worktree
+        );
+        git_ops::restore_file(&path, &relative_path, staged, worktree)
+    })
+}
+
+#[tauri::command]
+/// 从其他分支/提交检出单个文件。
+fn git_checkout_file(
+    path: String,
+    reference: String,
+    relative_path: String,
+    force: Option<bool>,
+) -> Result<(), GitError> {
+    let force = force.unwrap_or(false);
+    log_command_result("git_checkout_file", || {
+        log::info!(
+            "git_checkout_file path={} ref={} file={} force={}",
+            path,
+            reference,
+            relative_path,
+            force
+        );
+        git_ops::checkout_file(&path, &reference, &relative_path, force)
+    })
+}
+
+#[tauri::command]
+/// 提交已暂存改动。
+fn git_commit(
+    app: AppHandle,
+    path: String,
+    message: String,
+    skip_hooks: Option<bool>,
+) -> Result<(), CommitValidationError> {
+    log_command_result("git_commit", || {
+        let skip_hooks = skip_hooks.unwrap_or(false);
+        log::info!(
+            "git_commit path={} message_size={} skip_hooks={}",
+            path,
+            message.len(),
+            skip_hooks
+        );
+        let rules = match storage::load_app_state(&app) {
+            Ok(state) => git_ops::CommitMessageRules::from_settings(
+                state.settings.commit_message_template.as_deref(),
+                state.settings.commit_subject_max_len,
+            ),
+            Err(error) => {
+                log::warn!("读取提交规则失败，跳过校验: {}", error);
+                git_ops::CommitMessageRules::default()
+            }
+        };
+        git_ops::commit(&path, &message, &rules, skip_hooks)
+    })
+}
+
+#[tauri::command]
+/// 手动执行 Git hook（pre-commit/pre-push 等）。
+fn run_git_hook(path: String, hook_name: String) -> Result<String, GitError> {
+    log_command_result("run_git_hook", || {
+        log::info!("run_git_hook path={} hook={}", path, hook_name);
+        git_ops::run_hook(&path, &hook_name)
+    })
+}
+
+#[tauri::command]
+/// 重置当前分支（soft/mixed/hard，hard 需确认）。
+fn git_reset(
+    path: String,
+    reference: String,
+    mode: GitResetMode,
+    confirm_hard: Option<bool>,
+) -> Result<GitResetResult, GitError> {
+    log_command_result("git_reset", || {
+        let confirm_hard = confirm_hard.unwrap_or(false);
+        log::info!(
+            "git_reset path={} ref={} mode={:?} confirm_hard={}",
+            path,
+            reference,
+            mode,
+            confirm_hard
+        );
+        git_ops::reset(&path, &reference, mode, confirm_hard)
+    })
+}
+
+#[tauri::command]
+/// 切换分支（git checkout <branch>），成功后广播 branch-checked-out 事件。
+fn git_checkout_branch(app: AppHandle, path: String, branch: String) -> Result<(), GitError> {
+    log_command_result("git_checkout_branch", || {
+        log::info!("git_checkout_branch path={} branch={}", path, branch);
+        git_ops::checkout_branch(&path, &branch)?;
+        emit_branch_checked_out(&app, &path, &branch);
+        Ok(())
     })
 }
 
 #[tauri::command]
-/// 获取单文件对比内容（original/modified），用于 UI 渲染对比视图。
-fn git_get_diff_contents(
-    path: String,
-    relative_path: String,
-    staged: bool,
-    old_relative_path: Option<String>,
-) -> Result<GitDiffContents, String> {
-    log_command_result("git_get_diff_contents", || {
+/// 删除本地分支（git branch -d/-D）。
+fn git_delete_branch(path: String, branch: String, force: bool) -> Result<(), GitError> {
+    log_command_result("git_delete_branch", || {
         log::info!(
-            "git_get_diff_contents path={} file={} staged={}",
+            "git_delete_branch path={} branch={} force={}",
             path,
-            relative_path,
-            staged
+            branch,
+            force
         );
-        git_ops::get_diff_contents(&path, &relative_path, staged, old_relative_path.as_deref())
+        git_ops::delete_branch(&path, &branch, force)
     })
 }
 
 #[tauri::command]
-/// 暂存文件（git add）。
-fn git_stage_files(path: String, relative_paths: Vec<String>) -> Result<(), String> {
-    log_command_result("git_stage_files", || {
-        log::info!(
-            "git_stage_files path={} files={}",
-            path,
-            relative_paths.len()
-        );
-        git_ops::stage_files(&path, &relative_paths)
+/// 列出仓库 tag。
+fn git_list_tags(path: String) -> Result<Vec<GitTagItem>, GitError> {
+    log_command_result("git_list_tags", || {
+        log::info!("git_list_tags path={}", path);
+        git_ops::list_tags(&path)
     })
 }
 
 #[tauri::command]
-/// 取消暂存（git reset HEAD --）。
-fn git_unstage_files(path: String, relative_paths: Vec<String>) -> Result<(), String> {
-    log_command_result("git_unstage_files", || {
+/// 创建 tag（轻量或附注）。
+fn git_create_tag(
+    path: String,
+    name: String,
+    message: Option<String>,
+    annotated: bool,
+) -> Result<(), GitError> {
+    log_command_result("git_create_tag", || {
         log::info!(
-            "git_unstage_files path={} files={}",
+            "git_create_tag path={} name={} annotated={}",
             path,
-            relative_paths.len()
+            name,
+            annotated
         );
-        git_ops::unstage_files(&path, &relative_paths)
+        git_ops::create_tag(&path, &name, message.as_deref(), annotated)
     })
 }
 
 #[tauri::command]
-/// 丢弃未暂存修改（git checkout --）。
-fn git_discard_files(path: String, relative_paths: Vec<String>) -> Result<(), String> {
-    log_command_result("git_discard_files", || {
-        log::info!(
-            "git_discard_files path={} files={}",
-            path,
-            relative_paths.len()
-        );
-        git_ops::discard_files(&path, &relative_paths)
+/// 删除本地 tag。
+fn git_delete_tag(path: String, name: String) -> Result<(), GitError> {
+    log_command_result("git_delete_tag", || {
+        log::info!("git_delete_tag path={} name={}", path, name);
+        git_ops::delete_tag(&path, &name)
     })
 }
 
 #[tauri::command]
-/// 提交已暂存改动。
-fn git_commit(path: String, message: String) -> Result<(), String> {
-    log_command_result("git_commit", || {
-        log::info!("git_commit path={} message_size={}", path, message.len());
-        git_ops::commit(&path, &message)
+/// 列出仓库的远程及其 fetch/push 地址。
+fn git_list_remotes(path: String) -> Result<Vec<GitRemote>, GitError> {
+    log_command_result("git_list_remotes", || {
+        log::info!("git_list_remotes path={}", path);
+        git_ops::list_remotes(&path)
     })
 }
 
 #[tauri::command]
-/// 切换分支（git checkout <branch>）。
-fn git_checkout_branch(path: String, branch: String) -> Result<(), String> {
-    log_command_result("git_checkout_branch", || {
-        log::info!("git_checkout_branch path={} branch={}", path, branch);
-        git_ops::checkout_branch(&path, &branch)
+/// 添加远程仓库。
+fn git_add_remote(path: String, name: String, url: String) -> Result<(), GitError> {
+    log_command_result("git_add_remote", || {
+        log::info!("git_add_remote path={} name={}", path, name);
+        git_ops::add_remote(&path, &name, &url)
     })
 }
 
 #[tauri::command]
-/// 删除本地分支（git branch -d/-D）。
-fn git_delete_branch(path: String, branch: String, force: bool) -> Result<(), String> {
-    log_command_result("git_delete_branch", || {
-        log::info!(
-            "git_delete_branch path={} branch={} force={}",
-            path,
-            branch,
-            force
-        );
-        git_ops::delete_branch(&path, &branch, force)
+/// 修改远程仓库地址。
+fn git_set_remote_url(path: String, name: String, url: String) -> Result<(), GitError> {
+    log_command_result("git_set_remote_url", || {
+        log::info!("git_set_remote_url path={} name={}", path, name);
+        git_ops::set_remote_url(&path, &name, &url)
     })
 }
 
@@ -209,7 +1092,7 @@ fn git_worktree_add(
     branch: String,
     create_branch: bool,
     target_path: Option<String>,
-) -> Result<GitWorktreeAddResult, String> {
+) -> Result<GitWorktreeAddResult, GitError> {
     log_command_result("git_worktree_add", || {
         log::info!(
             "git_worktree_add path={} target_path={} branch={} create_branch={}",
@@ -224,16 +1107,28 @@ fn git_worktree_add(
 
 #[tauri::command]
 /// 列出仓库下已有 worktree（不包含主仓库目录）。
-fn git_worktree_list(path: String) -> Result<Vec<GitWorktreeListItem>, String> {
+fn git_worktree_list(path: String) -> Result<Vec<GitWorktreeListItem>, GitError> {
     log_command_result("git_worktree_list", || {
         log::info!("git_worktree_list path={}", path);
         git_ops::list_worktrees(&path)
     })
 }
 
+#[tauri::command]
+/// 统计 worktree 分支相对基线分支的领先/落后提交数。
+fn git_worktree_divergence(
+    path: String,
+    worktree_path: String,
+    base_branch: String,
+) -> Result<GitWorktreeDivergence, GitError> {
+    log_command_result("git_worktree_divergence", || {
+        git_ops::worktree_divergence(&path, &worktree_path, &base_branch)
+    })
+}
+
 #[tauri::command]
 /// 删除 Git worktree（git worktree remove）。
-fn git_worktree_remove(path: String, worktree_path: String, force: bool) -> Result<(), String> {
+fn git_worktree_remove(path: String, worktree_path: String, force: bool) -> Result<(), GitError> {
     log_command_result("git_worktree_remove", || {
         log::info!(
             "git_worktree_remove path={} worktree_path={} force={}",
@@ -245,6 +1140,68 @@ fn git_worktree_remove(path: String, worktree_path: String, force: bool) -> Resu
     })
 }
 
+#[tauri::command]
+/// 清理失效 worktree 记录（git worktree prune）；dry_run（默认开启）时仅返回将被清理的记录。
+fn git_worktree_prune(
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<GitWorktreePruneResult, GitError> {
+    log_command_result("git_worktree_prune", || {
+        let dry_run = dry_run.unwrap_or(true);
+        log::info!("git_worktree_prune path={} dry_run={}", path, dry_run);
+        git_ops::prune_worktrees(&path, dry_run)
+    })
+}
+
+#[tauri::command]
+/// 评审 worktree：计算相对基线分支的变更文件，并用设置中的编辑器打开 worktree 目录。
+fn open_worktree_review(
+    app: AppHandle,
+    path: String,
+    worktree_path: String,
+    base_branch: String,
+) -> Result<GitWorktreeReview, GitError> {
+    log_command_result("open_worktree_review", || {
+        log::info!(
+            "open_worktree_review path={} worktree={} base={}",
+            path,
+            worktree_path,
+            base_branch
+        );
+        let worktree = git_ops::find_worktree(&path, &worktree_path)?;
+        let diff = git_ops::diff_against_base(&worktree.path, &base_branch)?;
+
+        // 编辑器相关失败（包括读取设置失败）只记录在结果中，不影响评审结果。
+        let editor_result = storage::load_app_state(&app)
+            .map_err(|error| format!("读取设置失败: {}", error))
+            .and_then(|state| {
+                let tool = state.settings.editor_open_tool;
+                if tool.command_path.trim().is_empty() {
+                    return Err("未配置编辑器".to_string());
+                }
+                system::open_in_editor(EditorOpenParams {
+                    path: worktree.path.clone(),
+                    app_name: None,
+                    bundle_id: None,
+                    command_path: Some(tool.command_path),
+                    arguments: Some(tool.arguments),
+                })
+            });
+        if let Err(error) = &editor_result {
+            log::warn!("open_worktree_review 打开编辑器失败: {}", error);
+        }
+
+        Ok(GitWorktreeReview {
+            worktree_path: worktree.path,
+            branch: worktree.branch,
+            base_branch: base_branch.trim().to_string(),
+            diff,
+            editor_opened: editor_result.is_ok(),
+            editor_error: editor_result.err(),
+        })
+    })
+}
+
 #[tauri::command]
 /// 查询当前全局交互锁状态。
 fn get_interaction_lock_state(
@@ -262,12 +1219,13 @@ fn worktree_init_start(
 ) -> Result<WorktreeInitStartResult, String> {
     log_command_result("worktree_init_start", || {
         log::info!(
-            "worktree_init_start project_id={} path={} branch={} base_branch={} create_branch={}",
+            "worktree_init_start project_id={} path={} branch={} base_branch={} create_branch={} dry_run={}",
             request.project_id,
             request.project_path,
             request.branch,
             request.base_branch.as_deref().unwrap_or("<none>"),
-            request.create_branch
+            request.create_branch,
+            request.dry_run
         );
         state.start(&app, request)
     })
@@ -292,6 +1250,9 @@ fn worktree_init_create(
         );
 
         let started = state.start(&app, request)?;
+        if started.dry_run || started.reused {
+            return Ok(started);
+        }
         let job_id = started.job_id.clone();
         let query = WorktreeInitStatusQuery {
             project_id: Some(started.project_id.clone()),
@@ -355,12 +1316,28 @@ fn worktree_init_create_blocking(
     request: WorktreeInitStartRequest,
 ) -> Result<WorktreeInitCreateBlockingResult, String> {
     log_command_result("worktree_init_create_blocking", || {
+        if request.dry_run {
+            return Err("阻塞式创建不支持 dry-run，请使用 worktree_init_start".to_string());
+        }
         let _lock_guard = interaction_lock.lock(
             &app,
             Some(INTERACTION_LOCK_REASON_WORKTREE_CREATE.to_string()),
         );
 
         let started = state.start(&app, request)?;
+        if started.reused {
+            return Ok(WorktreeInitCreateBlockingResult {
+                job_id: started.job_id,
+                project_id: started.project_id,
+                project_path: started.project_path,
+                worktree_path: started.worktree_path,
+                branch: started.branch,
+                base_branch: started.base_branch,
+                message: started.message,
+                warning: None,
+                reused: true,
+            });
+        }
         let job_id = started.job_id.clone();
         let query = WorktreeInitStatusQuery {
             project_id: Some(started.project_id.clone()),
@@ -386,6 +1363,7 @@ fn worktree_init_create_blocking(
                         base_branch: status.base_branch,
                         message: status.message,
                         warning: status.error,
+                        reused: false,
                     });
                 }
                 WorktreeInitStep::Failed => {
@@ -443,11 +1421,48 @@ fn worktree_init_status(
 }
 
 #[tauri::command]
-/// 在文件管理器中定位路径。
-fn open_in_finder(path: String) -> Result<(), String> {
+/// 启动后台 git clone 任务（快速返回任务状态），进度通过 git-clone-progress 事件推送。
+fn git_clone(
+    app: AppHandle,
+    state: State<git_clone::GitCloneState>,
+    request: GitCloneStartRequest,
+) -> Result<GitCloneJobStatus, String> {
+    log_command_result("git_clone", || {
+        log::info!(
+            "git_clone url={} dest={} branch={} register_as_project={}",
+            request.url,
+            request.dest,
+            request.branch.as_deref().unwrap_or("<default>"),
+            request.register_as_project
+        );
+        state.start(&app, request)
+    })
+}
+
+#[tauri::command]
+/// 取消 git clone 任务，已创建的目标目录会被清理。
+fn git_clone_cancel(state: State<git_clone::GitCloneState>, job_id: String) -> Result<(), String> {
+    log_command_result("git_clone_cancel", || {
+        log::info!("git_clone_cancel job_id={}", job_id);
+        state.cancel(&job_id)
+    })
+}
+
+#[tauri::command]
+/// 查询 git clone 任务状态。
+fn git_clone_status(
+    state: State<git_clone::GitCloneState>,
+) -> Result<Vec<GitCloneJobStatus>, String> {
+    log_command_result("git_clone_status", || state.query_status())
+}
+
+#[tauri::command]
+/// 在文件管理器中打开路径；`reveal` 默认为 true（选中目标文件）。
+fn open_in_finder(path: String, reveal: Option<bool>) -> Result<(), String> {
     log_command_result("open_in_finder", || {
-        log::info!("open_in_finder path={}", path);
-        system::open_in_finder(&path)
+        let reveal = reveal.unwrap_or(true);
+        log::info!("open_in_finder path={} reveal={}", path, reveal);
+        system::open_in_finder(&path, reveal)
     })
 }
 
@@ -460,27 +1475,145 @@ fn open_in_editor(params: EditorOpenParams) -> Result<(), String> {
     })
 }
 
+#[tauri::command]
+/// 使用外部终端打开路径（读取设置中的终端工具）。
+fn open_in_terminal(app: AppHandle, path: String) -> Result<(), String> {
+    log_command_result("open_in_terminal", || {
+        log::info!("open_in_terminal path={}", path);
+        let state = storage::load_app_state(&app)?;
+        system::open_in_terminal(&path, &state.settings.terminal_open_tool)
+    })
+}
+
+#[tauri::command]
+/// 探测本机已安装的常见编辑器。
+fn detect_editors() -> Vec<DetectedEditor> {
+    log_command("detect_editors", || {
+        let editors = system::detect_editors();
+        log::info!("detect_editors found={}", editors.len());
+        editors
+    })
+}
+
+#[tauri::command]
+/// 运行时调整日志级别（off/error/warn/info/debug/trace），返回生效的级别。
+fn set_log_level(level: String) -> Result<String, String> {
+    log_command_result("set_log_level", || {
+        let filter = parse_log_level(&level)?;
+        log::set_max_level(filter);
+        log::info!("log level set to {}", filter);
+        Ok(filter.to_string().to_ascii_lowercase())
+    })
+}
+
+#[tauri::command]
+/// 获取日志目录与当前日志文件路径。
+fn get_log_path(app: AppHandle) -> Result<LogFileInfo, String> {
+    log_command_result("get_log_path", || diagnostics::resolve_log_file(&app))
+}
+
+#[tauri::command]
+/// 读取当前日志文件的最后若干行，用于应用内诊断视图。
+fn read_recent_logs(app: AppHandle, lines: Option<usize>) -> Result<Vec<String>, String> {
+    log_command_result("read_recent_logs", || {
+        diagnostics::read_recent_logs(&app, lines)
+    })
+}
+
+#[tauri::command]
+/// 校验外部编辑器/终端工具配置是否可用。
+fn validate_open_tool(settings: OpenToolSettings) -> OpenToolValidation {
+    log_command("validate_open_tool", || {
+        log::info!("validate_open_tool command={}", settings.command_path);
+        system::validate_open_tool(&settings)
+    })
+}
+
 #[tauri::command]
 /// 设置指定窗口可在 macOS 全屏空间中作为辅助窗口展示。
 fn set_window_fullscreen_auxiliary(
     app: AppHandle,
     window_label: String,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<FullscreenAuxiliaryResult, String> {
+    if let Ok(mut desired) = app.state::<FullscreenAuxiliaryState>().desired.lock() {
+        desired.insert(window_label.clone(), enabled);
+    }
     #[cfg(target_os = "macos")]
     {
         let window = app
             .get_webview_window(&window_label)
             .ok_or_else(|| "窗口不存在".to_string())?;
-        return apply_fullscreen_auxiliary(&window, enabled);
+        let result = apply_fullscreen_auxiliary(&window, enabled)?;
+        if !result.supported {
+            log::warn!(
+                "全屏辅助模式不可用 window={}: {}",
+                window_label,
+                result.message.as_deref().unwrap_or_default()
+            );
+        }
+        return Ok(result);
     }
     #[cfg(not(target_os = "macos"))]
     {
-        let _ = (app, window_label, enabled);
-        Ok(())
+        let _ = (app, window_label);
+        Ok(FullscreenAuxiliaryResult {
+            supported: false,
+            enabled,
+            message: Some("仅 macOS 支持全屏辅助模式".to_string()),
+        })
     }
 }
 
+#[tauri::command]
+/// 列出当前应用的所有窗口及其可见/聚焦状态。
+fn list_windows(app: AppHandle) -> Vec<AppWindowInfo> {
+    log_command("list_windows", || {
+        let mut windows: Vec<AppWindowInfo> = app
+            .webview_windows()
+            .into_iter()
+            .map(|(label, window)| AppWindowInfo {
+                label,
+                visible: window.is_visible().unwrap_or(false),
+                focused: window.is_focused().unwrap_or(false),
+                minimized: window.is_minimized().unwrap_or(false),
+            })
+            .collect();
+        windows.sort_by(|left, right| left.label.cmp(&right.label));
+        windows
+    })
+}
+
+#[tauri::command]
+/// 将指定窗口前置并聚焦。
+fn focus_window(app: AppHandle, label: String) -> Result<(), String> {
+    log_command_result("focus_window", || {
+        log::info!("focus_window label={}", label);
+        let window = app
+            .get_webview_window(&label)
+            .ok_or_else(|| "窗口不存在".to_string())?;
+        if window.is_minimized().unwrap_or(false) {
+            window.unminimize().map_err(|err| err.to_string())?;
+        }
+        window.show().map_err(|err| err.to_string())?;
+        window.set_focus().map_err(|err| err.to_string())?;
+        #[cfg(target_os = "macos")]
+        {
+            // 辅助面板不会随 set_focus 激活，需要强制前置。
+            let auxiliary = app
+                .state::<FullscreenAuxiliaryState>()
+                .desired
+                .lock()
+                .map(|desired| desired.get(&label).copied().unwrap_or(false))
+                .unwrap_or(false);
+            if auxiliary {
+                order_front_regardless(&window)?;
+            }
+        }
+        Ok(())
+    })
+}
+
 #[tauri::command]
 /// 复制文本到剪贴板。
 fn copy_to_clipboard(app: AppHandle, content: String) -> Result<(), String> {
@@ -490,6 +1623,19 @@ fn copy_to_clipboard(app: AppHandle, content: String) -> Result<(), String> {
     })
 }
 
+#[tauri::command]
+/// 读取剪贴板文本。
+fn read_from_clipboard(app: AppHandle) -> Option<String> {
+    log_command("read_from_clipboard", || {
+        let content = system::read_from_clipboard(&app);
+        log::info!(
+            "read_from_clipboard size={}",
+            content.as_ref().map(|value| value.len()).unwrap_or(0)
+        );
+        content
+    })
+}
+
 #[tauri::command]
 /// 读取项目备注内容。
 fn read_project_notes(path: String) -> Result<Option<String>, String> {
@@ -589,6 +1735,28 @@ fn collect_git_daily(paths: Vec<String>, identities: Vec<GitIdentity>) -> Vec<Gi
     })
 }
 
+#[tauri::command]
+/// 计算每日提交统计的签名（路径 + 各仓库 HEAD），用于判断是否需要重新统计。
+fn compute_git_daily_signature(paths: Vec<String>) -> String {
+    log_command("compute_git_daily_signature", || {
+        log::info!("compute_git_daily_signature paths={}", paths.len());
+        git_daily::compute_git_daily_signature(&paths)
+    })
+}
+
+#[tauri::command]
+/// 统计指定日期各小时的提交数（24 个槽位），遵循身份过滤。
+fn git_hourly_activity(
+    paths: Vec<String>,
+    date: String,
+    identities: Vec<GitIdentity>,
+) -> Result<Vec<i64>, String> {
+    log_command_result("git_hourly_activity", || {
+        log::info!("git_hourly_activity paths={} date={}", paths.len(), date);
+        git_daily::collect_hourly_activity(&paths, &date, &identities)
+    })
+}
+
 #[tauri::command]
 fn load_heatmap_cache(app: AppHandle) -> Result<HeatmapCacheFile, String> {
     log_command_result("load_heatmap_cache", || storage::load_heatmap_cache(&app))
@@ -642,12 +1810,80 @@ fn list_terminal_workspace_summaries(
 }
 
 #[tauri::command]
-fn get_codex_monitor_snapshot(app: AppHandle) -> Result<CodexMonitorSnapshot, String> {
+/// 将数据目录备份为 zip。
+fn backup_data_dir(app: AppHandle, dest: String) -> Result<DataBackupResult, String> {
+    log_command_result("backup_data_dir", || {
+        log::info!("backup_data_dir dest={}", dest);
+        storage::backup_data_dir(&app, &dest)
+    })
+}
+
+#[tauri::command]
+/// 从 zip 备份恢复数据目录。
+fn restore_data_dir(app: AppHandle, src: String) -> Result<DataBackupResult, String> {
+    log_command_result("restore_data_dir", || {
+        log::info!("restore_data_dir src={}", src);
+        storage::restore_data_dir(&app, &src)
+    })
+}
+
+#[tauri::command]
+/// 获取 Codex 监控快照，可按模型/推理强度筛选会话。
+fn get_codex_monitor_snapshot(
+    app: AppHandle,
+    filter: Option<CodexSessionFilter>,
+) -> Result<CodexMonitorSnapshot, String> {
     log_command_result("get_codex_monitor_snapshot", || {
         if let Err(error) = codex_monitor::ensure_monitoring_started(&app) {
             log::warn!("启动 Codex 监控失败: {}", error);
         }
-        codex_monitor::get_snapshot(&app)
+        match filter {
+            Some(filter) => codex_monitor::get_filtered_snapshot(&app, &filter),
+            None => codex_monitor::get_snapshot(&app),
+        }
+    })
+}
+
+#[tauri::command]
+/// 打开 Codex 会话的工作目录：匹配到项目时通知前端聚焦，否则在文件管理器中打开。
+fn open_codex_session_project(
+    app: AppHandle,
+    session_id: String,
+) -> Result<CodexSessionOpenResult, String> {
+    log_command_result("open_codex_session_project", || {
+        log::info!("open_codex_session_project session={}", session_id);
+        codex_monitor::open_session_project(&app, &session_id)
+    })
+}
+
+#[tauri::command]
+/// 结束卡住的 Codex 会话进程（发送 SIGTERM），需显式确认；返回是否找到并通知了进程。
+fn kill_codex_session(app: AppHandle, session_id: String, confirm: bool) -> Result<bool, String> {
+    log_command_result("kill_codex_session", || {
+        log::info!(
+            "kill_codex_session session={} confirm={}",
+            session_id,
+            confirm
+        );
+        codex_monitor::kill_session(&app, &session_id, confirm)
+    })
+}
+
+#[tauri::command]
+/// 开始实时跟随 Codex 会话，新追加的记录以 `codex-session-line` 事件推送。
+fn watch_codex_session(app: AppHandle, session_id: String) -> Result<(), String> {
+    log_command_result("watch_codex_session", || {
+        log::info!("watch_codex_session session={}", session_id);
+        codex_monitor::watch_session(&app, &session_id)
+    })
+}
+
+#[tauri::command]
+/// 停止跟随 Codex 会话，返回此前是否在监听。
+fn stop_watching_codex_session(session_id: String) -> Result<bool, String> {
+    log_command_result("stop_watching_codex_session", || {
+        log::info!("stop_watching_codex_session session={}", session_id);
+        codex_monitor::stop_watching_session(&session_id)
     })
 }
 
@@ -663,27 +1899,35 @@ fn get_terminal_codex_pane_overlay(
     })
 }
 
+#[tauri::command]
+/// 获取终端 pane 的前台命令与最近一条命令的退出码。
+fn get_terminal_pane_command_status(
+    state: State<TerminalState>,
+    window_label: String,
+    session_ids: Vec<String>,
+) -> Result<Vec<TerminalPaneCommandStatus>, String> {
+    log_command_result("get_terminal_pane_command_status", || {
+        terminal_get_pane_command_status(state, window_label, session_ids)
+    })
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 /// 启动 Tauri 应用。
 pub fn run() {
     let app = tauri::Builder::default()
-        .plugin(
-            tauri_plugin_log::Builder::new()
-                .targets([
-                    Target::new(TargetKind::Stdout),
-                    Target::new(TargetKind::LogDir { file_name: None }),
-                ])
-                .level(log::LevelFilter::Info)
-                .build(),
-        )
+        .plugin(log_plugin_builder().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(TerminalState::default())
         .manage(worktree_init::WorktreeInitState::default())
+        .manage(git_clone::GitCloneState::default())
         .manage(interaction_lock::InteractionLockState::default())
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        .manage(FullscreenAuxiliaryState::default())
+        .manage(project_watcher::ProjectWatcherState::default())
+        .manage(script_runner::ProjectScriptState::default())
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
                 let locked = window
                     .app_handle()
                     .state::<interaction_lock::InteractionLockState>()
@@ -692,8 +1936,20 @@ pub fn run() {
                     api.prevent_close();
                 }
             }
+            tauri::WindowEvent::Focused(true) => {
+                // 任一窗口聚焦（含主窗口进入全屏）后，重新应用所有辅助窗口的状态。
+                reapply_fullscreen_auxiliary(window.app_handle(), None);
+            }
+            _ => {}
+        })
+        .on_page_load(|webview, payload| {
+            if matches!(payload.event(), tauri::webview::PageLoadEvent::Finished) {
+                // 窗口重建后页面重新加载，按记录的期望状态恢复。
+                reapply_fullscreen_auxiliary(webview.app_handle(), Some(webview.label()));
+            }
         })
         .setup(|app| {
+            log::set_max_level(resolve_startup_log_level());
             log::info!(
                 "app start name={} version={}",
                 app.package_info().name,
@@ -703,6 +1959,12 @@ pub fn run() {
                 log::info!("log dir={}", path.display());
             }
             let app_handle = app.handle();
+            match storage::load_app_state(app_handle) {
+                Ok(state) => {
+                    git_ops::set_git_executable_override(state.settings.git_path.as_deref())
+                }
+                Err(error) => log::warn!("读取应用设置失败: {}", error),
+            }
             if let Err(error) = codex_monitor::ensure_monitoring_started(&app_handle) {
                 log::warn!("启动 Codex 监控失败: {}", error);
             }
@@ -712,22 +1974,75 @@ pub fn run() {
             load_app_state,
             save_app_state,
             load_projects,
+            list_projects_sorted,
+            list_projects_page,
+            refresh_project,
             save_projects,
+            prune_missing_projects,
+            recycle_project,
+            restore_from_recycle_bin,
+            purge_recycle_bin,
+            empty_recycle_bin,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            assign_tag,
+            search_projects,
             discover_projects,
             build_projects,
+            compute_project_size,
+            watch_project,
+            unwatch_project,
+            run_project_script,
+            list_running_scripts,
             list_branches,
             git_is_repo,
+            git_init,
             git_get_status,
+            git_ahead_behind,
+            git_operation_state,
+            git_operation_continue,
+            git_operation_abort,
+            git_head_info,
+            git_diff_against_base,
+            git_get_identity,
+            git_set_identity,
+            git_repo_health,
+            git_repo_stats,
+            git_contributors,
+            git_remove_index_lock,
             git_get_diff_contents,
+            git_get_conflict_contents,
+            git_diff_stat,
             git_stage_files,
+            git_mark_resolved,
             git_unstage_files,
+            git_stage_hunk,
+            git_unstage_hunk,
             git_discard_files,
+            git_stage_all,
+            git_unstage_all,
+            git_discard_all,
+            git_clean,
+            git_restore_file,
+            git_checkout_file,
             git_commit,
+            run_git_hook,
+            git_reset,
             git_checkout_branch,
             git_delete_branch,
+            git_list_tags,
+            git_create_tag,
+            git_delete_tag,
+            git_list_remotes,
+            git_add_remote,
+            git_set_remote_url,
             git_worktree_add,
             git_worktree_list,
+            git_worktree_divergence,
             git_worktree_remove,
+            git_worktree_prune,
+            open_worktree_review,
             get_interaction_lock_state,
             worktree_init_start,
             worktree_init_create,
@@ -735,10 +2050,22 @@ pub fn run() {
             worktree_init_cancel,
             worktree_init_retry,
             worktree_init_status,
+            git_clone,
+            git_clone_cancel,
+            git_clone_status,
             open_in_finder,
             open_in_editor,
+            open_in_terminal,
+            detect_editors,
+            set_log_level,
+            get_log_path,
+            read_recent_logs,
+            validate_open_tool,
             set_window_fullscreen_auxiliary,
+            list_windows,
+            focus_window,
             copy_to_clipboard,
+            read_from_clipboard,
             read_project_notes,
             read_project_notes_previews,
             write_project_notes,
@@ -748,16 +2075,27 @@ pub fn run() {
             read_project_file,
             write_project_file,
             collect_git_daily,
+            compute_git_daily_signature,
+            git_hourly_activity,
             load_heatmap_cache,
             save_heatmap_cache,
             load_terminal_workspace,
             save_terminal_workspace,
             delete_terminal_workspace,
             list_terminal_workspace_summaries,
+            backup_data_dir,
+            restore_data_dir,
             get_codex_monitor_snapshot,
+            open_codex_session_project,
+            kill_codex_session,
+            watch_codex_session,
+            stop_watching_codex_session,
             get_terminal_codex_pane_overlay,
+            get_terminal_pane_command_status,
             terminal_create_session,
+            terminal_get_session_metadata,
             terminal_write,
+            terminal_run_command,
             terminal_resize,
             terminal_kill,
         ])
@@ -771,13 +2109,72 @@ pub fn run() {
                 .is_locked();
             if locked {
                 api.prevent_exit();
+            } else {
+                shutdown_background_processes(app_handle);
+            }
+            #[cfg(target_os = "macos")]
+            if !locked {
+                for window in app_handle.webview_windows().values() {
+                    reset_window_class(window);
+                }
             }
         }
     });
 }
 
+// 退出前清理子进程：终端 shell 总是结束；项目脚本按设置决定是否结束，避免残留 node/构建进程。
+fn shutdown_background_processes(app: &AppHandle) {
+    let terminals = terminal::kill_all_sessions(&app.state::<TerminalState>());
+    let stop_scripts = storage::load_app_state(app)
+        .map(|state| state.settings.stop_scripts_on_exit)
+        .unwrap_or(true);
+    let scripts = if stop_scripts {
+        app.state::<script_runner::ProjectScriptState>().stop_all()
+    } else {
+        0
+    };
+    log::info!(
+        "shutdown background processes terminals={} scripts={}",
+        terminals,
+        scripts
+    );
+}
+
+// 按记录的期望状态重新应用全屏辅助设置；`label` 为空时处理所有已启用的窗口。
+fn reapply_fullscreen_auxiliary(app: &AppHandle, label: Option<&str>) {
+    let targets: Vec<String> = match app.state::<FullscreenAuxiliaryState>().desired.lock() {
+        Ok(desired) => desired
+            .iter()
+            .filter(|(key, enabled)| **enabled && label.is_none_or(|value| value == key.as_str()))
+            .map(|(key, _)| key.clone())
+            .collect(),
+        Err(_) => return,
+    };
+
+    #[cfg(target_os = "macos")]
+    for target in targets {
+        let Some(window) = app.get_webview_window(&target) else {
+            continue;
+        };
+        match apply_fullscreen_auxiliary(&window, true) {
+            Ok(result) if !result.supported => log::warn!(
+                "全屏辅助模式不可用 window={}: {}",
+                target,
+                result.message.as_deref().unwrap_or_default()
+            ),
+            Ok(_) => {}
+            Err(error) => log::warn!("重新应用全屏辅助失败 window={}: {}", target, error),
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = targets;
+}
+
 #[cfg(target_os = "macos")]
-fn apply_fullscreen_auxiliary(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+fn apply_fullscreen_auxiliary(
+    window: &tauri::WebviewWindow,
+    enabled: bool,
+) -> Result<FullscreenAuxiliaryResult, String> {
     use objc2::runtime::AnyObject;
     use objc2_app_kit::{
         NSNormalWindowLevel, NSPanel, NSScreenSaverWindowLevel, NSWindow,
@@ -794,15 +2191,18 @@ fn apply_fullscreen_auxiliary(window: &tauri::WebviewWindow, enabled: bool) -> R
         let ns_window_obj = &*(ns_window as *const NSWindow as *const AnyObject);
         let mut behavior = ns_window.collectionBehavior();
         if enabled {
+            // 类切换失败时保持窗口原样，避免出现半配置状态。
             if let Err(error) = try_set_window_class(ns_window_obj, "NSPanel") {
-                log::warn!("升级为 NSPanel 失败: {}", error);
-            }
-            if ns_window_obj.class().name().to_bytes() == b"NSPanel" {
-                let panel = &*(ns_window as *const NSWindow as *const NSPanel);
-                panel.setFloatingPanel(true);
-                panel.setBecomesKeyOnlyIfNeeded(true);
-                panel.setWorksWhenModal(true);
+                return Ok(FullscreenAuxiliaryResult {
+                    supported: false,
+                    enabled: false,
+                    message: Some(format!("升级为 NSPanel 失败: {error}")),
+                });
             }
+            let panel = &*(ns_window as *const NSWindow as *const NSPanel);
+            panel.setFloatingPanel(true);
+            panel.setBecomesKeyOnlyIfNeeded(true);
+            panel.setWorksWhenModal(true);
             let mut style = ns_window.styleMask();
             style |= NSWindowStyleMask::NonactivatingPanel;
             style |= NSWindowStyleMask::UtilityWindow;
@@ -830,9 +2230,46 @@ fn apply_fullscreen_auxiliary(window: &tauri::WebviewWindow, enabled: bool) -> R
         ns_window.setCollectionBehavior(behavior);
     }
 
+    Ok(FullscreenAuxiliaryResult {
+        supported: true,
+        enabled,
+        message: None,
+    })
+}
+
+// 强制将窗口置于最前（不激活应用）。
+#[cfg(target_os = "macos")]
+fn order_front_regardless(window: &tauri::WebviewWindow) -> Result<(), String> {
+    use objc2_app_kit::NSWindow;
+
+    let ns_window = window.ns_window().map_err(|error| error.to_string())?;
+    if ns_window.is_null() {
+        return Err("获取 NSWindow 失败".to_string());
+    }
+    unsafe {
+        let ns_window = &*(ns_window as *mut NSWindow);
+        ns_window.orderFrontRegardless();
+    }
     Ok(())
 }
 
+/// 应用退出前将所有窗口还原为 NSWindow，避免以 NSPanel 身份销毁。
+#[cfg(target_os = "macos")]
+fn reset_window_class(window: &tauri::WebviewWindow) {
+    use objc2::runtime::AnyObject;
+
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    if ns_window.is_null() {
+        return;
+    }
+    let ns_window_obj = unsafe { &*(ns_window as *const AnyObject) };
+    if let Err(error) = try_set_window_class(ns_window_obj, "NSWindow") {
+        log::warn!("还原 NSWindow 失败 window={}: {}", window.label(), error);
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn try_set_window_class(
     target: &objc2::runtime::AnyObject,
@@ -863,6 +2300,13 @@ fn try_set_window_class(
     unsafe {
         objc2::runtime::AnyObject::set_class(target, target_class);
     }
+    // 校验切换结果，失败时回退到原始类。
+    if target.class().name() != target_class.name() {
+        unsafe {
+            objc2::runtime::AnyObject::set_class(target, current_class);
+        }
+        return Err(format!("切换窗口类失败: {}", class_name));
+    }
     Ok(())
 }
 
@@ -874,6 +2318,43 @@ fn log_command<T, F: FnOnce() -> T>(name: &str, action: F) -> T {
     result
 }
 
+// 应用日志放行全部级别，实际过滤由 log::set_max_level 控制，便于运行时调整；
+// 噪声较大的依赖库固定在 info 及以上。
+fn log_plugin_builder() -> tauri_plugin_log::Builder {
+    let builder = tauri_plugin_log::Builder::new()
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::LogDir { file_name: None }),
+        ])
+        .level(log::LevelFilter::Trace);
+    NOISY_LOG_TARGETS.iter().fold(builder, |builder, target| {
+        builder.level_for(*target, log::LevelFilter::Info)
+    })
+}
+
+// 读取启动时的日志级别（环境变量 DEVHAVEN_LOG_LEVEL），未设置或不合法时使用 info。
+fn resolve_startup_log_level() -> log::LevelFilter {
+    startup_log_level(std::env::var(LOG_LEVEL_ENV).ok().as_deref())
+}
+
+fn startup_log_level(value: Option<&str>) -> log::LevelFilter {
+    let Some(value) = value else {
+        return log::LevelFilter::Info;
+    };
+    parse_log_level(value).unwrap_or_else(|_| {
+        log::warn!("{}={} 不合法，使用 info", LOG_LEVEL_ENV, value);
+        log::LevelFilter::Info
+    })
+}
+
+// 解析日志级别名称（大小写不敏感，忽略首尾空白）。
+fn parse_log_level(level: &str) -> Result<log::LevelFilter, String> {
+    level
+        .trim()
+        .parse::<log::LevelFilter>()
+        .map_err(|_| format!("日志级别不合法: {level}"))
+}
+
 fn log_command_result<T, E: std::fmt::Display, F: FnOnce() -> Result<T, E>>(
     name: &str,
     action: F,
@@ -892,3 +2373,31 @@ fn log_command_result<T, E: std::fmt::Display, F: FnOnce() -> Result<T, E>>(
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_log_level, startup_log_level};
+    use log::LevelFilter;
+
+    #[test]
+    fn parse_log_level_accepts_known_levels_case_insensitively() {
+        assert_eq!(parse_log_level("debug"), Ok(LevelFilter::Debug));
+        assert_eq!(parse_log_level(" WARN "), Ok(LevelFilter::Warn));
+        assert_eq!(parse_log_level("off"), Ok(LevelFilter::Off));
+        assert_eq!(parse_log_level("Trace"), Ok(LevelFilter::Trace));
+        assert_eq!(
+            parse_log_level("verbose"),
+            Err("日志级别不合法: verbose".to_string())
+        );
+        assert!(parse_log_level("").is_err());
+    }
+
+    #[test]
+    fn startup_log_level_falls_back_to_info() {
+        assert_eq!(startup_log_level(None), LevelFilter::Info);
+        assert_eq!(startup_log_level(Some("error")), LevelFilter::Error);
+        assert_eq!(startup_log_level(Some(" debug\n")), LevelFilter::Debug);
+        assert_eq!(startup_log_level(Some("loud")), LevelFilter::Info);
+        assert_eq!(startup_log_level(Some("")), LevelFilter::Info);
+    }
+}
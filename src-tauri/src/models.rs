@@ -74,6 +74,30 @@ pub struct AppSettings {
     pub git_identities: Vec<GitIdentity>,
     #[serde(default = "default_project_list_view_mode")]
     pub project_list_view_mode: ProjectListViewMode,
+    /// 提交信息模板；模板包含正文时提交需填写正文。
+    #[serde(default)]
+    pub commit_message_template: Option<String>,
+    /// 提交标题最大长度，0 表示不限制。
+    #[serde(default)]
+    pub commit_subject_max_len: u32,
+    /// 新建 worktree 时从主仓库复制的文件（支持 `*`/`?` 通配，如 `.env`、`config/*.local.json`）。
+    #[serde(default)]
+    pub worktree_inherit_files: Vec<String>,
+    /// 单个终端 pane 每秒最多转发的输出字节数，超出部分以提示代替，0（默认）表示不限制。
+    #[serde(default)]
+    pub terminal_max_output_bytes_per_sec: u64,
+    /// Git 可执行文件路径，为空时自动探测（优先 Homebrew）。
+    #[serde(default)]
+    pub git_path: Option<String>,
+    /// 退出应用时结束仍在运行的项目脚本进程。
+    #[serde(default = "default_stop_scripts_on_exit")]
+    pub stop_scripts_on_exit: bool,
+    /// Codex 会话目录，为空时使用 `$CODEX_HOME/sessions` 或 `~/.codex/sessions`。
+    #[serde(default)]
+    pub codex_sessions_dir: Option<String>,
+    /// Codex 监控首次推送事件时，为仍在进行中的会话补发当前状态事件。
+    #[serde(default)]
+    pub codex_monitor_bootstrap_events: bool,
 }
 
 impl Default for AppSettings {
@@ -86,6 +110,14 @@ impl Default for AppSettings {
             show_monitor_window: false,
             git_identities: Vec::new(),
             project_list_view_mode: default_project_list_view_mode(),
+            commit_message_template: None,
+            commit_subject_max_len: 0,
+            worktree_inherit_files: Vec::new(),
+            terminal_max_output_bytes_per_sec: 0,
+            git_path: None,
+            stop_scripts_on_exit: default_stop_scripts_on_exit(),
+            codex_sessions_dir: None,
+            codex_monitor_bootstrap_events: false,
         }
     }
 }
@@ -105,6 +137,14 @@ fn default_terminal_use_webgl_renderer() -> bool {
     true
 }
 
+fn default_inherit_config() -> bool {
+    true
+}
+
+fn default_stop_scripts_on_exit() -> bool {
+    true
+}
+
 fn default_terminal_theme() -> String {
     "DevHaven Dark".to_string()
 }
@@ -124,6 +164,32 @@ impl Default for OpenToolSettings {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OpenToolFailureReason {
+    Empty,
+    NotFound,
+    NotAFile,
+    NotExecutable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenToolValidation {
+    pub ok: bool,
+    pub resolved_path: Option<String>,
+    pub reason: Option<OpenToolFailureReason>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedEditor {
+    pub name: String,
+    pub command_path: String,
+    pub default_args: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitIdentity {
@@ -131,6 +197,25 @@ pub struct GitIdentity {
     pub email: String,
 }
 
+/// Git 配置项的来源层级，对应 `git config --show-scope`。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitConfigScope {
+    System,
+    Global,
+    Local,
+    Worktree,
+}
+
+/// 仓库提交时实际生效的身份；未配置的字段为空字符串，对应 scope 为 None。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoIdentity {
+    pub identity: GitIdentity,
+    pub name_scope: Option<GitConfigScope>,
+    pub email_scope: Option<GitConfigScope>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagData {
     pub name: String,
@@ -164,6 +249,12 @@ pub struct Project {
     pub git_daily: Option<String>,
     pub created: SwiftDate,
     pub checked: SwiftDate,
+    /// 项目终端使用的 shell（路径或命令名），为空时使用用户默认 shell。
+    #[serde(default)]
+    pub terminal_shell: Option<String>,
+    /// 项目终端额外注入的环境变量（如 NODE_ENV），覆盖同名基础环境变量。
+    #[serde(default)]
+    pub terminal_env: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -202,6 +293,35 @@ pub struct ProjectScript {
     pub stop: Option<String>,
 }
 
+/// 项目脚本操作：启动或停止。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectScriptAction {
+    Start,
+    Stop,
+}
+
+/// 项目脚本启动/停止结果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectScriptRunResult {
+    pub project_id: String,
+    pub script_id: String,
+    pub pid: Option<u32>,
+    pub running: bool,
+}
+
+/// 已登记脚本进程的状态；started_at 为 Unix 毫秒时间戳。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningScriptStatus {
+    pub project_id: String,
+    pub script_id: String,
+    pub pid: Option<u32>,
+    pub started_at: i64,
+    pub alive: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarkdownFileEntry {
@@ -305,6 +425,9 @@ pub struct HeatmapCacheEntry {
     pub commit_count: i64,
     #[serde(rename = "projectIds")]
     pub project_ids: Vec<String>,
+    /// 当天各项目的提交数（project_id -> count）。
+    #[serde(default, rename = "projectCounts")]
+    pub project_counts: HashMap<String, i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -318,6 +441,9 @@ pub struct HeatmapCacheFile {
     pub project_count: i64,
     #[serde(default, rename = "gitDailySignature")]
     pub git_daily_signature: String,
+    /// 上次统计时的仓库 HEAD 签名（见 `compute_git_daily_signature`），用于跳过未变化的重新统计。
+    #[serde(default, rename = "gitHeadSignature")]
+    pub git_head_signature: String,
 }
 
 impl Default for HeatmapCacheFile {
@@ -328,6 +454,7 @@ impl Default for HeatmapCacheFile {
             daily_activity: HashMap::new(),
             project_count: 0,
             git_daily_signature: String::new(),
+            git_head_signature: String::new(),
         }
     }
 }
@@ -353,6 +480,22 @@ pub struct GitWorktreeListItem {
     pub branch: String,
 }
 
+/// 日志目录与当前日志文件路径。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFileInfo {
+    pub log_dir: String,
+    pub log_file: String,
+    pub exists: bool,
+}
+
+/// 项目目录文件变更事件（去抖后发送）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectChangedPayload {
+    pub path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InteractionLockPayload {
@@ -394,6 +537,18 @@ pub struct WorktreeInitStartRequest {
     pub base_branch: Option<String>,
     #[serde(default)]
     pub target_path: Option<String>,
+    /// 创建成功后由后端写入项目缓存的 worktrees 列表。
+    #[serde(default)]
+    pub register_as_project: bool,
+    /// 是否继承主仓库配置（对应 ProjectWorktree.inherit_config），关闭时不复制继承文件。
+    #[serde(default = "default_inherit_config")]
+    pub inherit_config: bool,
+    /// 环境准备阶段执行的创建后脚本（如 `pnpm install`），失败仅作为告警。
+    #[serde(default)]
+    pub post_create_script: Option<String>,
+    /// 仅校验并返回创建计划，不实际创建 worktree。
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -408,6 +563,17 @@ pub struct WorktreeInitStartResult {
     pub base_branch: Option<String>,
     pub step: WorktreeInitStep,
     pub message: String,
+    /// 是否为 dry-run 预览（此时 job_id 为空）。
+    #[serde(default)]
+    pub dry_run: bool,
+    /// 新建分支时解析出的起点引用。
+    #[serde(default)]
+    pub start_point: Option<String>,
+    #[serde(default)]
+    pub branch_exists: bool,
+    /// 分支已在其他 worktree 检出时直接复用（此时 job_id 为空，worktree_path 为已有路径）。
+    #[serde(default)]
+    pub reused: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -423,6 +589,8 @@ pub struct WorktreeInitCreateBlockingResult {
     pub message: String,
     #[serde(default)]
     pub warning: Option<String>,
+    #[serde(default)]
+    pub reused: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -439,6 +607,9 @@ pub struct WorktreeInitProgressPayload {
     pub message: String,
     #[serde(default)]
     pub error: Option<String>,
+    /// 后端已登记到项目缓存的 worktree 条目（仅 register_as_project 且创建成功时存在）。
+    #[serde(default)]
+    pub worktree: Option<ProjectWorktree>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -483,6 +654,72 @@ pub struct WorktreeInitJobStatus {
     pub cancel_requested: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitCloneStep {
+    Pending,
+    Cloning,
+    Registering,
+    Ready,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCloneStartRequest {
+    pub url: String,
+    pub dest: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// 克隆完成后是否登记到项目缓存。
+    #[serde(default)]
+    pub register_as_project: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCloneProgressPayload {
+    pub job_id: String,
+    pub url: String,
+    pub dest: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    pub step: GitCloneStep,
+    pub message: String,
+    /// 当前阶段（如 `Receiving objects`）及其百分比，来自 `git clone --progress` 输出。
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub percent: Option<u8>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 已登记到项目缓存的项目（仅 register_as_project 且克隆成功时存在）。
+    #[serde(default)]
+    pub project: Option<Project>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCloneJobStatus {
+    pub job_id: String,
+    pub url: String,
+    pub dest: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    pub step: GitCloneStep,
+    pub message: String,
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub percent: Option<u8>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub updated_at: i64,
+    pub is_running: bool,
+    pub cancel_requested: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum GitFileStatus {
@@ -494,6 +731,44 @@ pub enum GitFileStatus {
     Untracked,
 }
 
+/// 未跟踪文件的展示粒度，对应 `git status -u<mode>`。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitUntrackedMode {
+    /// 不列出未跟踪文件（-uno）。
+    No,
+    /// 未跟踪目录折叠为单个目录条目（-unormal，路径以 `/` 结尾）。
+    #[default]
+    Normal,
+    /// 逐个列出未跟踪文件（-uall）。
+    All,
+}
+
+/// 读取状态时的重命名检测选项，对应 `git status --find-renames[=<n>]` / `--no-renames`。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRenameDetection {
+    /// 关闭后重命名以删除 + 新增的形式展示。
+    #[serde(default = "default_detect_renames")]
+    pub detect_renames: bool,
+    /// 相似度阈值（1-100），为空时使用 git 默认值。
+    #[serde(default)]
+    pub rename_threshold: Option<u8>,
+}
+
+impl Default for GitRenameDetection {
+    fn default() -> Self {
+        Self {
+            detect_renames: default_detect_renames(),
+            rename_threshold: None,
+        }
+    }
+}
+
+fn default_detect_renames() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitChangedFile {
@@ -501,6 +776,17 @@ pub struct GitChangedFile {
     #[serde(default)]
     pub old_path: Option<String>,
     pub status: GitFileStatus,
+    /// 子模块状态（逗号分隔：new-commits/modified-content/untracked-content），普通文件为 None。
+    #[serde(default)]
+    pub submodule: Option<String>,
+}
+
+/// HEAD 相对于与基准分支分叉点（merge-base）的变更文件。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBaseDiff {
+    pub merge_base: String,
+    pub files: Vec<GitChangedFile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -525,6 +811,287 @@ pub struct GitDiffContents {
     pub original_truncated: bool,
     #[serde(default)]
     pub modified_truncated: bool,
+    /// 工作区文件是否包含冲突标记。
+    #[serde(default)]
+    pub conflict: bool,
+    #[serde(default)]
+    pub conflict_regions: Vec<GitConflictRegion>,
+    /// 两侧内容仅存在行尾（CRLF/LF）差异。
+    #[serde(default)]
+    pub eol_only: bool,
+}
+
+/// 文件对比的归一化选项，对应 git 的 `--ignore-space-at-eol` 等行为。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffOptions {
+    /// 统一行尾为 LF。
+    #[serde(default)]
+    pub ignore_eol: bool,
+    /// 忽略行尾空白（空格/制表符）差异。
+    #[serde(default)]
+    pub ignore_trailing_whitespace: bool,
+}
+
+/// 冲突文件的三方内容，索引中缺失的一方为 None（如 add/add 冲突没有 base）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConflictContents {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    #[serde(default)]
+    pub base_truncated: bool,
+    #[serde(default)]
+    pub ours_truncated: bool,
+    #[serde(default)]
+    pub theirs_truncated: bool,
+}
+
+/// 冲突区块，行号从 1 开始并指向各标记行；ours 位于 start 与 base/separator 之间，theirs 位于 separator 与 end 之间。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConflictRegion {
+    pub start_line: usize,
+    pub base_line: Option<usize>,
+    pub separator_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommitValidationCode {
+    EmptyMessage,
+    SubjectTooLong,
+    MissingBody,
+    CommitFailed,
+}
+
+/// 提交失败的结构化错误（校验失败或 git commit 执行失败）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitValidationError {
+    pub code: CommitValidationCode,
+    pub message: String,
+}
+
+impl std::fmt::Display for CommitValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Git 错误类别，前端据此决定后续操作（如提供“强制”选项），无需匹配文案。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitErrorKind {
+    NotARepo,
+    BranchNotFound,
+    Conflict,
+    DirtyWorktree,
+    RemoteError,
+    Io,
+    Raw,
+}
+
+/// Git 命令失败的结构化错误，message 保留可读文案。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitError {
+    pub kind: GitErrorKind,
+    pub message: String,
+}
+
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<GitError> for String {
+    fn from(error: GitError) -> Self {
+        error.message
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitResetMode {
+    Soft,
+    Mixed,
+    Hard,
+}
+
+/// 仓库健康检查结果：index.lock 残留与 `git fsck --connectivity-only` 报告的问题。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoHealth {
+    pub has_index_lock: bool,
+    #[serde(default)]
+    pub index_lock_path: Option<String>,
+    pub fsck_errors: Vec<String>,
+}
+
+/// 单仓库提交统计；空仓库各项为 0，last_commit_ts 为 Unix 毫秒时间戳。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoStats {
+    pub commit_count: i64,
+    pub last_commit_ts: i64,
+    pub contributor_count: usize,
+}
+
+/// 仓库贡献者摘要，来自 `git shortlog -sne`。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitContributor {
+    pub name: String,
+    pub email: String,
+    pub commit_count: i64,
+}
+
+/// `git clean` 结果：performed=false 时为预览（dry run），files 为将被删除的路径。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCleanResult {
+    pub performed: bool,
+    pub files: Vec<String>,
+}
+
+/// `git worktree prune` 清理的失效 worktree 记录。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreePruneEntry {
+    /// `.git/worktrees` 下的记录名，如 `worktrees/feature`。
+    pub name: String,
+    pub reason: String,
+}
+
+/// `git worktree prune` 结果：performed=false 时为预览（dry run）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreePruneResult {
+    pub performed: bool,
+    pub entries: Vec<GitWorktreePruneEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitResetResult {
+    /// 是否已执行 reset（hard 模式未确认时为 false，仅返回受影响文件）。
+    pub performed: bool,
+    pub commit: String,
+    /// hard 模式下会被丢弃改动的文件。
+    pub discarded_files: Vec<String>,
+}
+
+/// 当前分支相对上游的领先/落后提交数；未设置上游时 has_upstream=false 且计数为 0。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitAheadBehind {
+    pub ahead: i32,
+    pub behind: i32,
+    pub has_upstream: bool,
+    #[serde(default)]
+    pub upstream: Option<String>,
+}
+
+/// worktree 分支相对基线分支的领先/落后提交数。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreeDivergence {
+    pub worktree_path: String,
+    pub branch: String,
+    pub base_branch: String,
+    pub ahead: i32,
+    pub behind: i32,
+}
+
+/// worktree 评审信息：相对基线分支的变更文件，以及编辑器是否已打开。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreeReview {
+    pub worktree_path: String,
+    pub branch: String,
+    pub base_branch: String,
+    pub diff: GitBaseDiff,
+    pub editor_opened: bool,
+    /// 编辑器未配置或打开失败时的原因，不影响变更列表返回。
+    #[serde(default)]
+    pub editor_error: Option<String>,
+}
+
+/// 远程仓库配置，来自 `git remote -v`；未单独配置 push 地址时与 fetch 地址相同。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemote {
+    pub name: String,
+    pub fetch_url: String,
+    pub push_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTagItem {
+    pub name: String,
+    pub commit: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    pub is_annotated: bool,
+}
+
+/// 仓库中进行到一半的 Git 操作。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitOperation {
+    #[default]
+    None,
+    Rebase,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+/// 仓库特殊状态：进行中的操作（rebase/merge/cherry-pick 等）以及 HEAD 是否游离。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitOperationState {
+    pub operation: GitOperation,
+    pub detached: bool,
+}
+
+/// `branch-checked-out` 事件载荷：切换分支后的仓库路径、分支与 HEAD 提交。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchCheckedOutPayload {
+    pub path: String,
+    pub branch: String,
+    pub commit: String,
+    pub short_commit: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHeadInfo {
+    pub commit: String,
+    pub short_commit: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    pub detached: bool,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffStatEntry {
+    pub relative_path: String,
+    #[serde(default)]
+    pub old_relative_path: Option<String>,
+    pub additions: u32,
+    pub deletions: u32,
+    pub binary: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -593,6 +1160,53 @@ pub struct CodexMonitorSnapshot {
     pub sessions: Vec<CodexMonitorSession>,
     pub is_codex_running: bool,
     pub updated_at: i64,
+    /// 按模型与推理强度聚合的会话数量，统计范围为筛选前的全部会话。
+    #[serde(default)]
+    pub model_usage: Vec<CodexModelUsage>,
+}
+
+/// 同一模型与推理强度组合下的会话数量。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexModelUsage {
+    pub model: Option<String>,
+    pub effort: Option<String>,
+    pub count: usize,
+}
+
+/// Codex 监控快照的筛选条件，按模型/推理强度精确匹配（忽略大小写），为空表示不限。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSessionFilter {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub effort: Option<String>,
+}
+
+/// 打开 Codex 会话工作目录的结果；匹配到已知项目时包含项目 ID 与命中的项目/worktree 路径。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSessionOpenResult {
+    pub session_id: String,
+    pub cwd: String,
+    pub project_id: Option<String>,
+    pub project_path: Option<String>,
+}
+
+/// 实时跟随 Codex 会话时推送的一行记录摘要（`codex-session-line` 事件）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSessionLine {
+    pub session_id: String,
+    pub timestamp: i64,
+    pub entry_type: String,
+    #[serde(default)]
+    pub item_type: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub preview: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -605,3 +1219,142 @@ pub struct TerminalCodexPaneOverlay {
     pub effort: Option<String>,
     pub updated_at: i64,
 }
+
+/// 终端 pane 的命令状态：前台命令与最近一条命令的退出码。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalPaneCommandStatus {
+    pub session_id: String,
+    /// shell 的前台子进程名；shell 空闲时为空。
+    #[serde(default)]
+    pub current_command: Option<String>,
+    /// 最近一条命令的退出码，仅在 shell 输出 OSC 133 命令结束标记时可得。
+    #[serde(default)]
+    pub last_exit_code: Option<i32>,
+    #[serde(default)]
+    pub last_exit_at: Option<i64>,
+    /// 是否处于备用屏幕（vim/less/htop 等全屏程序运行中）。
+    #[serde(default)]
+    pub in_alt_screen: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectPruneResult {
+    /// 路径已不存在而被移除的项目。
+    pub removed: Vec<Project>,
+    /// id 重复而被移除的项目（保留首个）。
+    pub duplicates: Vec<Project>,
+    /// 位于回收站中的项目（缓存条目保留，可恢复）。
+    pub recycled: Vec<Project>,
+}
+
+/// 项目列表排序字段，取值与项目字段名一致。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSortKey {
+    Name,
+    #[default]
+    Mtime,
+    GitLastCommit,
+    Size,
+    Created,
+}
+
+/// 项目搜索命中的字段。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectMatchField {
+    Name,
+    Path,
+    Tag,
+}
+
+/// 模糊匹配命中的区间（按字符计的下标，左闭右开），用于高亮。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 项目模糊搜索结果：按得分从高到低排列，只保留得分最高的命中字段。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSearchResult {
+    pub project_id: String,
+    pub score: i64,
+    #[serde(default)]
+    pub field: Option<ProjectMatchField>,
+    /// 命中字段为标签时对应的标签名。
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub spans: Vec<MatchSpan>,
+}
+
+/// 项目分页结果：当前页项目、过滤后的总数，以及搜索时当前页各项目的命中信息。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectPage {
+    pub projects: Vec<Project>,
+    pub total: usize,
+    #[serde(default)]
+    pub matches: Vec<ProjectSearchResult>,
+}
+
+/// 批量打标操作：为项目添加或移除标签。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TagAssignAction {
+    Add,
+    Remove,
+}
+
+/// 标签批量操作结果：最新的标签列表与项目缓存，以及引用被更新的项目数。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUpdateResult {
+    pub tags: Vec<TagData>,
+    pub projects: Vec<Project>,
+    pub affected_projects: usize,
+}
+
+/// 回收站操作结果：操作后的回收站列表、扫描目录与项目缓存，以及被彻底移除的路径和移到废纸篓失败的信息。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecycleBinResult {
+    pub recycle_bin: Vec<String>,
+    #[serde(default)]
+    pub directories: Vec<String>,
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub purged: Vec<String>,
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FullscreenAuxiliaryResult {
+    /// 当前窗口是否支持全屏辅助模式（非 macOS 或类切换失败时为 false）。
+    pub supported: bool,
+    pub enabled: bool,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppWindowInfo {
+    pub label: String,
+    pub visible: bool,
+    pub focused: bool,
+    pub minimized: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBackupResult {
+    pub path: String,
+    pub files: Vec<String>,
+}
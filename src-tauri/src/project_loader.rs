@@ -1,10 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 
-use crate::models::Project;
-use crate::time_utils::{now_swift, system_time_to_swift, system_time_to_unix_seconds};
+use crate::git_ops;
+use crate::models::{
+    GitRepoStats, MatchSpan, Project, ProjectMatchField, ProjectPage, ProjectPruneResult,
+    ProjectSearchResult, ProjectSortKey, TagAssignAction, TagData,
+};
+use crate::time_utils::{
+    now_swift, swift_date_to_unix_millis, system_time_to_swift, system_time_to_unix_seconds,
+    unix_millis_to_swift_date,
+};
+
+// 可复用占用大小的 checksum 版本前缀；旧缓存（`{mtime}_{len}` 格式）不带前缀，会触发重新统计。
+const SIZE_CHECKSUM_VERSION: &str = "v2";
 
 /// 根据目录列表扫描可用项目路径。
 pub fn discover_projects(directories: &[String]) -> Vec<String> {
@@ -31,6 +40,543 @@ pub fn build_projects(paths: &[String], existing: &[Project]) -> Vec<Project> {
         .collect()
 }
 
+/// 清理项目缓存：移除路径已不存在的项目（并移出回收站），按 id 去重（保留首个）。
+///
+/// 回收站中的项目缓存条目保留（恢复时沿用标签、脚本等配置），单独列在 `recycled` 中。
+pub fn prune_missing_projects(
+    projects: Vec<Project>,
+    recycle_bin: &mut Vec<String>,
+) -> (Vec<Project>, ProjectPruneResult) {
+    recycle_bin.retain(|path| Path::new(path).exists());
+
+    let mut seen_ids = HashSet::new();
+    let mut kept = Vec::with_capacity(projects.len());
+    let mut removed = Vec::new();
+    let mut duplicates = Vec::new();
+    let mut recycled = Vec::new();
+
+    for project in projects {
+        if !Path::new(&project.path).exists() {
+            removed.push(project);
+            continue;
+        }
+        if !seen_ids.insert(project.id.clone()) {
+            duplicates.push(project);
+            continue;
+        }
+        if recycle_bin.contains(&project.path) {
+            recycled.push(project.clone());
+        }
+        kept.push(project);
+    }
+
+    (
+        kept,
+        ProjectPruneResult {
+            removed,
+            duplicates,
+            recycled,
+        },
+    )
+}
+
+/// 将项目移入回收站：记录其路径，返回该路径。
+///
+/// 项目缓存条目保留（仅在列表中隐藏），恢复时可沿用标签、脚本等配置。
+pub fn recycle_project(
+    recycle_bin: &mut Vec<String>,
+    projects: &[Project],
+    project_id: &str,
+) -> Result<String, String> {
+    let path = projects
+        .iter()
+        .find(|project| project.id == project_id)
+        .map(|project| project.path.clone())
+        .ok_or_else(|| "项目不存在".to_string())?;
+    if !recycle_bin.contains(&path) {
+        recycle_bin.push(path.clone());
+    }
+    Ok(path)
+}
+
+/// 从回收站恢复路径：移出回收站列表，项目缓存中缺失时重新构建条目，返回恢复后的项目。
+///
+/// 路径不在扫描目录范围内（既不是目录本身也不是其直接子目录）时加入目录列表，避免下次全量扫描后再次消失。
+pub fn restore_recycled_path(
+    recycle_bin: &mut Vec<String>,
+    directories: &mut Vec<String>,
+    projects: &mut Vec<Project>,
+    path: &str,
+) -> Result<Project, String> {
+    if !recycle_bin.iter().any(|item| item == path) {
+        return Err("回收站中没有该项目".to_string());
+    }
+    let project = match projects.iter().find(|project| project.path == path) {
+        Some(project) => project.clone(),
+        None => {
+            let project = build_projects(&[path.to_string()], projects)
+                .into_iter()
+                .next()
+                .ok_or_else(|| "项目目录不存在，无法恢复".to_string())?;
+            projects.push(project.clone());
+            project
+        }
+    };
+    recycle_bin.retain(|item| item != path);
+    let covered = directories.iter().any(|directory| {
+        Path::new(directory) == Path::new(path)
+            || Path::new(path).parent() == Some(Path::new(directory))
+    });
+    if !covered {
+        directories.push(path.to_string());
+    }
+    Ok(project)
+}
+
+/// 从回收站彻底移除路径，并删除对应的项目缓存；只处理确实在回收站中的路径，返回被移除的路径。
+pub fn purge_recycled_paths(
+    recycle_bin: &mut Vec<String>,
+    projects: &mut Vec<Project>,
+    paths: &[String],
+) -> Vec<String> {
+    let purged: Vec<String> = recycle_bin
+        .iter()
+        .filter(|item| paths.contains(item))
+        .cloned()
+        .collect();
+    recycle_bin.retain(|item| !purged.contains(item));
+    projects.retain(|project| !purged.contains(&project.path));
+    purged
+}
+
+/// 重命名标签并同步更新所有引用该标签的项目，返回受影响的项目数。
+pub fn rename_tag(
+    tags: &mut [TagData],
+    projects: &mut [Project],
+    old_name: &str,
+    new_name: &str,
+) -> Result<usize, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("标签名称不能为空".to_string());
+    }
+    if old_name == new_name {
+        return Ok(0);
+    }
+    if tags.iter().any(|tag| tag.name == new_name) {
+        return Err(format!("标签已存在: {new_name}"));
+    }
+    let tag = tags
+        .iter_mut()
+        .find(|tag| tag.name == old_name)
+        .ok_or_else(|| format!("标签不存在: {old_name}"))?;
+    tag.name = new_name.to_string();
+    Ok(replace_project_tag(projects, old_name, Some(new_name)))
+}
+
+/// 将标签 from 合并到 into：删除 from 并把项目中的引用替换为 into（去重），返回受影响的项目数。
+pub fn merge_tags(
+    tags: &mut Vec<TagData>,
+    projects: &mut [Project],
+    from: &str,
+    into: &str,
+) -> Result<usize, String> {
+    if from == into {
+        return Err("不能将标签合并到自身".to_string());
+    }
+    if !tags.iter().any(|tag| tag.name == into) {
+        return Err(format!("标签不存在: {into}"));
+    }
+    if !tags.iter().any(|tag| tag.name == from) {
+        return Err(format!("标签不存在: {from}"));
+    }
+    tags.retain(|tag| tag.name != from);
+    Ok(replace_project_tag(projects, from, Some(into)))
+}
+
+/// 删除标签；remove_from_projects 为 true 时同时移除项目中的引用，返回受影响的项目数。
+pub fn delete_tag(
+    tags: &mut Vec<TagData>,
+    projects: &mut [Project],
+    name: &str,
+    remove_from_projects: bool,
+) -> Result<usize, String> {
+    let before = tags.len();
+    tags.retain(|tag| tag.name != name);
+    if tags.len() == before {
+        return Err(format!("标签不存在: {name}"));
+    }
+    if !remove_from_projects {
+        return Ok(0);
+    }
+    Ok(replace_project_tag(projects, name, None))
+}
+
+/// 为一组项目批量添加或移除标签（标签须已存在），返回实际发生变化的项目。
+pub fn assign_tag(
+    tags: &[TagData],
+    projects: &mut [Project],
+    project_ids: &[String],
+    tag_name: &str,
+    action: TagAssignAction,
+) -> Result<Vec<Project>, String> {
+    if !tags.iter().any(|tag| tag.name == tag_name) {
+        return Err(format!("标签不存在: {tag_name}"));
+    }
+
+    let mut updated = Vec::new();
+    for project in projects
+        .iter_mut()
+        .filter(|project| project_ids.contains(&project.id))
+    {
+        let has_tag = project.tags.iter().any(|tag| tag == tag_name);
+        match action {
+            TagAssignAction::Add if !has_tag => project.tags.push(tag_name.to_string()),
+            TagAssignAction::Remove if has_tag => project.tags.retain(|tag| tag != tag_name),
+            _ => continue,
+        }
+        updated.push(project.clone());
+    }
+    Ok(updated)
+}
+
+// 将项目中的标签 from 替换为 replacement（None 表示移除），保持原有顺序并去重，返回受影响的项目数。
+fn replace_project_tag(projects: &mut [Project], from: &str, replacement: Option<&str>) -> usize {
+    let mut affected = 0;
+    for project in projects.iter_mut() {
+        if !project.tags.iter().any(|tag| tag == from) {
+            continue;
+        }
+        let mut next: Vec<String> = Vec::with_capacity(project.tags.len());
+        for tag in &project.tags {
+            let tag = if tag == from {
+                match replacement {
+                    Some(replacement) => replacement,
+                    None => continue,
+                }
+            } else {
+                tag.as_str()
+            };
+            if !next.iter().any(|existing| existing == tag) {
+                next.push(tag.to_string());
+            }
+        }
+        project.tags = next;
+        affected += 1;
+    }
+    affected
+}
+
+/// 按指定字段排序项目；主键相同时按名称（忽略大小写）与路径升序，保证顺序稳定。
+pub fn sort_projects(projects: &mut [Project], sort_by: ProjectSortKey, descending: bool) {
+    projects.sort_by(|left, right| {
+        let ordering = match sort_by {
+            ProjectSortKey::Name => compare_project_names(left, right),
+            ProjectSortKey::Mtime => left.mtime.total_cmp(&right.mtime),
+            ProjectSortKey::GitLastCommit => left.git_last_commit.total_cmp(&right.git_last_commit),
+            ProjectSortKey::Size => left.size.cmp(&right.size),
+            ProjectSortKey::Created => left.created.total_cmp(&right.created),
+        };
+        let ordering = if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        ordering.then_with(|| compare_project_names(left, right))
+    });
+}
+
+fn compare_project_names(left: &Project, right: &Project) -> std::cmp::Ordering {
+    left.name
+        .to_lowercase()
+        .cmp(&right.name.to_lowercase())
+        .then_with(|| left.path.cmp(&right.path))
+}
+
+/// 过滤、排序后按 offset/limit 截取项目分页。
+///
+/// query 非空时先做模糊过滤；未指定排序字段时搜索结果按相关度排列，否则默认按修改时间倒序。
+pub fn paginate_projects(
+    projects: Vec<Project>,
+    query: &str,
+    sort_by: Option<ProjectSortKey>,
+    descending: bool,
+    offset: usize,
+    limit: usize,
+) -> ProjectPage {
+    let query = query.trim();
+    let (mut projects, mut matches) = if query.is_empty() {
+        (projects, Vec::new())
+    } else {
+        let matches = search_projects(&projects, query, &[]);
+        let mut by_id: HashMap<String, Project> = projects
+            .into_iter()
+            .map(|project| (project.id.clone(), project))
+            .collect();
+        let ranked = matches
+            .iter()
+            .filter_map(|result| by_id.remove(&result.project_id))
+            .collect();
+        (ranked, matches)
+    };
+
+    if let Some(sort_by) = sort_by {
+        sort_projects(&mut projects, sort_by, descending);
+    } else if query.is_empty() {
+        sort_projects(&mut projects, ProjectSortKey::default(), true);
+    }
+
+    let total = projects.len();
+    let projects: Vec<Project> = projects.into_iter().skip(offset).take(limit).collect();
+    if !matches.is_empty() {
+        let page_ids: HashSet<&str> = projects.iter().map(|project| project.id.as_str()).collect();
+        matches.retain(|result| page_ids.contains(result.project_id.as_str()));
+    }
+    ProjectPage {
+        projects,
+        total,
+        matches,
+    }
+}
+
+// 各字段命中时的额外加分：名称优先，其次标签，路径最低。
+const NAME_MATCH_BONUS: i64 = 30;
+const TAG_MATCH_BONUS: i64 = 15;
+
+/// 按名称、路径与标签模糊搜索项目，返回按得分排序的结果及命中区间。
+///
+/// tags_filter 非空时项目须包含其中全部标签；query 为空时按原顺序返回全部通过筛选的项目。
+pub fn search_projects(
+    projects: &[Project],
+    query: &str,
+    tags_filter: &[String],
+) -> Vec<ProjectSearchResult> {
+    let query = query.trim();
+    let mut results: Vec<ProjectSearchResult> = projects
+        .iter()
+        .filter(|project| {
+            tags_filter
+                .iter()
+                .all(|tag| project.tags.iter().any(|item| item == tag))
+        })
+        .filter_map(|project| {
+            if query.is_empty() {
+                return Some(ProjectSearchResult {
+                    project_id: project.id.clone(),
+                    score: 0,
+                    field: None,
+                    tag: None,
+                    spans: Vec::new(),
+                });
+            }
+            best_project_match(project, query)
+        })
+        .collect();
+    results.sort_by_key(|result| std::cmp::Reverse(result.score));
+    results
+}
+
+// 在名称、路径、标签中取得分最高的命中。
+fn best_project_match(project: &Project, query: &str) -> Option<ProjectSearchResult> {
+    let mut best: Option<ProjectSearchResult> = None;
+    let mut consider = |field: ProjectMatchField, text: &str, tag: Option<&str>, bonus: i64| {
+        let Some((score, spans)) = fuzzy_match(query, text) else {
+            return;
+        };
+        let score = score + bonus;
+        if best.as_ref().is_none_or(|current| score > current.score) {
+            best = Some(ProjectSearchResult {
+                project_id: project.id.clone(),
+                score,
+                field: Some(field),
+                tag: tag.map(str::to_string),
+                spans,
+            });
+        }
+    };
+
+    consider(
+        ProjectMatchField::Name,
+        &project.name,
+        None,
+        NAME_MATCH_BONUS,
+    );
+    for tag in &project.tags {
+        consider(ProjectMatchField::Tag, tag, Some(tag), TAG_MATCH_BONUS);
+    }
+    consider(ProjectMatchField::Path, &project.path, None, 0);
+    best
+}
+
+// 忽略大小写的子序列匹配：连续命中与词首命中加分，跳过的字符扣分；完整子串命中直接给高分。
+fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<MatchSpan>)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let text_chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text_chars
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    if query.is_empty() || query.len() > lower.len() {
+        return None;
+    }
+
+    if let Some(start) = lower
+        .windows(query.len())
+        .position(|window| window == query.as_slice())
+    {
+        let mut score = query.len() as i64 * 10;
+        if is_word_start(&text_chars, start) {
+            score += 20;
+        }
+        if query.len() == lower.len() {
+            score += 30;
+        }
+        return Some((
+            score,
+            vec![MatchSpan {
+                start,
+                end: start + query.len(),
+            }],
+        ));
+    }
+
+    let mut score = 0_i64;
+    let mut spans: Vec<MatchSpan> = Vec::new();
+    let mut query_index = 0;
+    let mut last_match: Option<usize> = None;
+    for (index, ch) in lower.iter().enumerate() {
+        if query_index == query.len() {
+            break;
+        }
+        if *ch != query[query_index] {
+            continue;
+        }
+        score += 1;
+        if is_word_start(&text_chars, index) {
+            score += 8;
+        }
+        match last_match {
+            Some(last) if last + 1 == index => {
+                score += 5;
+                if let Some(span) = spans.last_mut() {
+                    span.end = index + 1;
+                }
+            }
+            Some(last) => {
+                score -= (index - last - 1).min(5) as i64;
+                spans.push(MatchSpan {
+                    start: index,
+                    end: index + 1,
+                });
+            }
+            None => spans.push(MatchSpan {
+                start: index,
+                end: index + 1,
+            }),
+        }
+        last_match = Some(index);
+        query_index += 1;
+    }
+
+    if query_index < query.len() {
+        return None;
+    }
+    Some((score, spans))
+}
+
+// 字符是否位于词首：开头、分隔符之后或小写到大写的驼峰边界。
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let prev = chars[index - 1];
+    matches!(prev, '/' | '\\' | '-' | '_' | '.' | ' ')
+        || (prev.is_lowercase() && chars[index].is_uppercase())
+}
+
+/// 仅刷新单个项目的 Git 统计（提交数、最后提交时间）并更新检查时间，返回更新后的项目。
+pub fn refresh_project_git_stats(
+    projects: &mut [Project],
+    project_id: &str,
+) -> Result<Project, String> {
+    let project = projects
+        .iter_mut()
+        .find(|project| project.id == project_id)
+        .ok_or_else(|| "项目不存在".to_string())?;
+    let stats = if git_ops::is_git_repo(&project.path) {
+        git_ops::repo_stats(&project.path)?
+    } else {
+        GitRepoStats::default()
+    };
+    project.git_commits = stats.commit_count;
+    project.git_last_commit = if stats.last_commit_ts > 0 {
+        unix_millis_to_swift_date(stats.last_commit_ts)
+    } else {
+        0.0
+    };
+    project.checked = now_swift();
+    Ok(project.clone())
+}
+
+/// 计算项目占用大小（字节）：可跳过 .git，并可按 .gitignore 排除忽略文件（如 node_modules）。
+pub fn compute_project_size(path: &str, exclude_git: bool, respect_gitignore: bool) -> u64 {
+    let root = Path::new(path);
+    if respect_gitignore && is_git_repo(root) {
+        let listed = run_git_command(
+            path,
+            &[
+                "ls-files",
+                "-z",
+                "--cached",
+                "--others",
+                "--exclude-standard",
+            ],
+        );
+        if let Some(listed) = listed {
+            let tracked_size: u64 = listed
+                .split('\0')
+                .filter(|relative| !relative.is_empty())
+                .filter_map(|relative| fs::symlink_metadata(root.join(relative)).ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum();
+            let git_size = if exclude_git {
+                0
+            } else {
+                dir_size(&root.join(".git"), false)
+            };
+            return tracked_size + git_size;
+        }
+    }
+    dir_size(root, exclude_git)
+}
+
+// 递归统计目录大小，不跟随软链。
+fn dir_size(path: &Path, exclude_git: bool) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    let mut total = 0;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if exclude_git && entry.file_name() == ".git" {
+                continue;
+            }
+            total += dir_size(&entry.path(), exclude_git);
+        } else if let Ok(metadata) = entry.metadata() {
+            total += metadata.len();
+        }
+    }
+    total
+}
+
 // 扫描指定目录：收录根目录（若为 Git 仓库）、其直接子目录，以及更深层的 Git 仓库。
 fn scan_directory_with_git(path: &str) -> Vec<String> {
     let mut results = Vec::new();
@@ -128,10 +674,40 @@ fn should_skip_recursive_dir(name: &std::ffi::OsStr) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{build_projects, scan_directory_with_git};
+    use super::{
+        assign_tag, build_projects, compute_project_size, delete_tag, fuzzy_match, merge_tags,
+        paginate_projects, prune_missing_projects, purge_recycled_paths, recycle_project,
+        rename_tag, restore_recycled_path, scan_directory_with_git, search_projects, sort_projects,
+    };
+    use crate::models::{
+        ColorData, MatchSpan, Project, ProjectMatchField, ProjectSortKey, TagAssignAction, TagData,
+    };
+    use std::collections::HashMap;
     use std::fs;
     use std::path::PathBuf;
 
+    // 构造仅存在于内存中的项目模型，供不依赖磁盘的用例使用。
+    fn test_project(name: &str, path: &str) -> Project {
+        Project {
+            id: format!("id-{name}"),
+            name: name.to_string(),
+            path: path.to_string(),
+            tags: Vec::new(),
+            scripts: Vec::new(),
+            worktrees: Vec::new(),
+            mtime: 0.0,
+            size: 0,
+            checksum: String::new(),
+            git_commits: 0,
+            git_last_commit: 0.0,
+            git_daily: None,
+            created: 0.0,
+            checked: 0.0,
+            terminal_shell: None,
+            terminal_env: HashMap::new(),
+        }
+    }
+
     #[test]
     fn scan_directory_with_git_handles_root_and_nested_repos() {
         let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
@@ -201,6 +777,347 @@ mod tests {
 
         let _ = fs::remove_dir_all(&root);
     }
+
+    #[test]
+    fn compute_project_size_excludes_git_and_ignored_files() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("node_modules")).expect("create node_modules");
+        fs::write(root.join("main.rs"), "0123456789").expect("write source");
+        fs::write(root.join("node_modules").join("dep.js"), "x".repeat(1000))
+            .expect("write dependency");
+        fs::write(root.join(".gitignore"), "node_modules/\n").expect("write gitignore");
+
+        let root_str = root.to_string_lossy().to_string();
+        assert_eq!(compute_project_size(&root_str, true, true), 1024);
+
+        let init = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status()
+            .expect("git init");
+        assert!(init.success());
+
+        assert_eq!(compute_project_size(&root_str, true, true), 24);
+        assert_eq!(compute_project_size(&root_str, true, false), 1024);
+        assert!(compute_project_size(&root_str, false, false) > 1024);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn build_projects_reuses_cached_size_until_git_content_changes() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("src").join("nested")).expect("create root");
+        let nested = root.join("src").join("nested").join("main.rs");
+        fs::write(&nested, "0123456789").expect("write source");
+        let init = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status()
+            .expect("git init");
+        assert!(init.success());
+
+        let paths = vec![root.to_string_lossy().to_string()];
+        let mut projects = build_projects(&paths, &[]);
+        assert_eq!(projects[0].size, 10);
+        let checksum = projects[0].checksum.clone();
+        assert!(checksum.starts_with("v2:"));
+
+        projects[0].size = 42;
+        let rebuilt = build_projects(&paths, &projects);
+        assert_eq!(rebuilt[0].size, 42);
+        assert_eq!(rebuilt[0].checksum, checksum);
+
+        // 旧版本缓存的 checksum 不带版本前缀，即使内容未变也要重新统计。
+        let mut legacy = projects.clone();
+        legacy[0].checksum = "725760000.5_4096".to_string();
+        legacy[0].size = 4096;
+        assert_eq!(build_projects(&paths, &legacy)[0].size, 10);
+
+        // 嵌套目录内的文件变大不会改变根目录 mtime，但会改变内容指纹。
+        fs::write(&nested, "0123456789abcdef").expect("grow source");
+        let rebuilt = build_projects(&paths, &projects);
+        assert_eq!(rebuilt[0].size, 16);
+        assert_ne!(rebuilt[0].checksum, checksum);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn build_projects_recomputes_size_for_non_git_directories() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create root");
+        fs::write(root.join("main.rs"), "0123456789").expect("write source");
+
+        let paths = vec![root.to_string_lossy().to_string()];
+        let mut projects = build_projects(&paths, &[]);
+        assert_eq!(projects[0].size, 10);
+
+        projects[0].size = 42;
+        assert_eq!(build_projects(&paths, &projects)[0].size, 10);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn prune_missing_projects_drops_missing_paths_and_duplicates() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let alpha = root.join("alpha");
+        let beta = root.join("beta");
+        fs::create_dir_all(&alpha).expect("create alpha dir");
+        fs::create_dir_all(&beta).expect("create beta dir");
+
+        let paths = vec![
+            alpha.to_string_lossy().to_string(),
+            beta.to_string_lossy().to_string(),
+        ];
+        let mut projects = build_projects(&paths, &[]);
+        assert_eq!(projects.len(), 2);
+        projects[1].id = projects[0].id.clone();
+        let mut missing = projects[0].clone();
+        missing.id = "missing".to_string();
+        missing.path = root.join("gone").to_string_lossy().to_string();
+        projects.push(missing);
+
+        let gone_path = root.join("gone").to_string_lossy().to_string();
+        let mut recycle_bin = vec![paths[0].clone(), gone_path];
+        let (kept, result) = prune_missing_projects(projects, &mut recycle_bin);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, paths[0]);
+        assert_eq!(result.removed.len(), 1);
+        assert_eq!(result.removed[0].id, "missing");
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(result.duplicates[0].path, paths[1]);
+        assert_eq!(result.recycled.len(), 1);
+        assert_eq!(result.recycled[0].path, paths[0]);
+        assert_eq!(recycle_bin, vec![paths[0].clone()]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn recycle_bin_restore_rebuilds_missing_project_and_purge_drops_cache() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let alpha = root.join("alpha");
+        fs::create_dir_all(&alpha).expect("create alpha dir");
+        let alpha_path = alpha.to_string_lossy().to_string();
+        let beta_path = root.join("beta").to_string_lossy().to_string();
+
+        let mut projects = vec![test_project("beta", &beta_path)];
+        let mut recycle_bin = vec![alpha_path.clone()];
+        assert_eq!(
+            recycle_project(&mut recycle_bin, &projects, "id-beta"),
+            Ok(beta_path.clone())
+        );
+        assert!(recycle_project(&mut recycle_bin, &projects, "id-beta").is_ok());
+        assert_eq!(recycle_bin, vec![alpha_path.clone(), beta_path.clone()]);
+        assert!(recycle_project(&mut recycle_bin, &projects, "missing").is_err());
+
+        // alpha 不在项目缓存中，恢复时从磁盘重新构建。
+        let mut directories = Vec::new();
+        let restored = restore_recycled_path(
+            &mut recycle_bin,
+            &mut directories,
+            &mut projects,
+            &alpha_path,
+        )
+        .expect("restore alpha");
+        assert_eq!(restored.path, alpha_path);
+        assert_eq!(projects.len(), 2);
+        assert_eq!(recycle_bin, vec![beta_path.clone()]);
+        assert_eq!(directories, vec![alpha_path.clone()]);
+        assert!(
+            restore_recycled_path(
+                &mut recycle_bin,
+                &mut directories,
+                &mut projects,
+                &alpha_path
+            )
+            .is_err()
+        );
+
+        let purged = purge_recycled_paths(
+            &mut recycle_bin,
+            &mut projects,
+            &[alpha_path.clone(), beta_path.clone()],
+        );
+        assert_eq!(purged, vec![beta_path]);
+        assert!(recycle_bin.is_empty());
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].path, alpha_path);
+
+        // 已在扫描目录下的路径恢复时不重复加入目录列表。
+        let gamma_path = root.join("gamma").to_string_lossy().to_string();
+        projects.push(test_project("gamma", &gamma_path));
+        recycle_bin.push(gamma_path.clone());
+        let mut directories = vec![root.to_string_lossy().to_string()];
+        restore_recycled_path(
+            &mut recycle_bin,
+            &mut directories,
+            &mut projects,
+            &gamma_path,
+        )
+        .expect("restore gamma");
+        assert_eq!(directories.len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn tag_rename_merge_and_delete_update_project_references() {
+        let tag = |name: &str| TagData {
+            name: name.to_string(),
+            color: ColorData {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            },
+            hidden: false,
+        };
+        let mut projects = vec![
+            test_project("alpha", "/work/alpha"),
+            test_project("beta", "/work/beta"),
+        ];
+        projects[0].tags = vec!["web".to_string(), "rust".to_string()];
+        projects[1].tags = vec!["frontend".to_string()];
+        let mut tags = vec![tag("web"), tag("rust"), tag("frontend")];
+
+        let ids = vec![projects[0].id.clone(), projects[1].id.clone()];
+        assert!(assign_tag(&tags, &mut projects, &ids, "missing", TagAssignAction::Add).is_err());
+        let updated = assign_tag(&tags, &mut projects, &ids, "rust", TagAssignAction::Add)
+            .expect("assign rust");
+        assert_eq!(updated.len(), 1);
+        assert_eq!(projects[1].tags, vec!["frontend", "rust"]);
+        let updated = assign_tag(
+            &tags,
+            &mut projects,
+            &ids[1..],
+            "rust",
+            TagAssignAction::Remove,
+        )
+        .expect("remove rust");
+        assert_eq!(updated.len(), 1);
+        assert_eq!(projects[1].tags, vec!["frontend"]);
+
+        assert!(rename_tag(&mut tags, &mut projects, "web", "rust").is_err());
+        assert_eq!(rename_tag(&mut tags, &mut projects, "web", " site "), Ok(1));
+        assert_eq!(projects[0].tags, vec!["site", "rust"]);
+
+        assert_eq!(
+            merge_tags(&mut tags, &mut projects, "frontend", "site"),
+            Ok(1)
+        );
+        assert_eq!(projects[1].tags, vec!["site"]);
+        assert!(tags.iter().all(|tag| tag.name != "frontend"));
+
+        assert_eq!(merge_tags(&mut tags, &mut projects, "rust", "site"), Ok(1));
+        assert_eq!(projects[0].tags, vec!["site"]);
+
+        assert_eq!(delete_tag(&mut tags, &mut projects, "site", true), Ok(2));
+        assert!(projects.iter().all(|project| project.tags.is_empty()));
+        assert!(tags.is_empty());
+        assert!(delete_tag(&mut tags, &mut projects, "site", false).is_err());
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_substrings_and_reports_spans() {
+        let (substring_score, spans) = fuzzy_match("hav", "DevHaven").expect("substring");
+        assert_eq!(spans, vec![MatchSpan { start: 3, end: 6 }]);
+
+        let (subsequence_score, spans) = fuzzy_match("dvh", "DevHaven").expect("subsequence");
+        assert_eq!(
+            spans,
+            vec![
+                MatchSpan { start: 0, end: 1 },
+                MatchSpan { start: 2, end: 4 }
+            ]
+        );
+        assert!(substring_score > subsequence_score);
+        assert!(fuzzy_match("xyz", "DevHaven").is_none());
+    }
+
+    #[test]
+    fn search_projects_ranks_name_matches_and_applies_tag_filter() {
+        let mut projects = vec![
+            test_project("misc", "/work/misc"),
+            test_project("haven-notes", "/work/tools/haven-notes"),
+            test_project("devhaven", "/work/devhaven"),
+        ];
+        projects[0].tags = vec!["haven".to_string()];
+        projects[2].tags = vec!["work".to_string()];
+
+        let results = search_projects(&projects, "devhaven", &[]);
+        assert_eq!(results[0].project_id, projects[2].id);
+        assert_eq!(results[0].field, Some(ProjectMatchField::Name));
+
+        // 完整命中标签（含标签加分）高于名称中间的子串命中。
+        let results = search_projects(&projects, "haven", &[]);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].project_id, projects[0].id);
+        assert_eq!(results[0].field, Some(ProjectMatchField::Tag));
+        assert_eq!(results[0].tag.as_deref(), Some("haven"));
+        assert_eq!(results[1].project_id, projects[1].id);
+        assert_eq!(results[2].project_id, projects[2].id);
+        assert_eq!(results[2].field, Some(ProjectMatchField::Name));
+
+        let results = search_projects(&projects, "", &["work".to_string()]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].project_id, projects[2].id);
+    }
+
+    #[test]
+    fn sort_projects_orders_by_key_with_name_tiebreak() {
+        let mut projects: Vec<Project> = ["beta", "Alpha", "gamma"]
+            .iter()
+            .map(|name| test_project(name, &format!("/work/{name}")))
+            .collect();
+        for project in projects.iter_mut() {
+            (project.mtime, project.size) = match project.name.as_str() {
+                "beta" => (300.0, 10),
+                "Alpha" => (100.0, 10),
+                _ => (200.0, 5),
+            };
+        }
+        let names = |projects: &[Project]| -> Vec<String> {
+            projects
+                .iter()
+                .map(|project| project.name.clone())
+                .collect()
+        };
+
+        sort_projects(&mut projects, ProjectSortKey::Mtime, true);
+        assert_eq!(names(&projects), vec!["beta", "gamma", "Alpha"]);
+
+        sort_projects(&mut projects, ProjectSortKey::Name, false);
+        assert_eq!(names(&projects), vec!["Alpha", "beta", "gamma"]);
+
+        sort_projects(&mut projects, ProjectSortKey::Size, true);
+        assert_eq!(names(&projects), vec!["Alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn paginate_projects_filters_sorts_then_slices() {
+        let mut projects: Vec<Project> = ["app-web", "app-api", "docs", "app-cli"]
+            .iter()
+            .map(|name| test_project(name, &format!("/work/{name}")))
+            .collect();
+        for (index, project) in projects.iter_mut().enumerate() {
+            project.mtime = index as f64;
+        }
+
+        let page = paginate_projects(projects.clone(), "", None, true, 1, 2);
+        assert_eq!(page.total, 4);
+        let names: Vec<&str> = page.projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "app-api"]);
+        assert!(page.matches.is_empty());
+
+        let page = paginate_projects(projects, "app", Some(ProjectSortKey::Name), false, 0, 2);
+        assert_eq!(page.total, 3);
+        let names: Vec<&str> = page.projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["app-api", "app-cli"]);
+        assert_eq!(page.matches.len(), 2);
+    }
 }
 
 // 创建单个项目模型，必要时复用已存在的配置。
@@ -229,8 +1146,17 @@ fn create_project(path: &str, existing_by_path: &HashMap<&str, &Project>) -> Opt
         .modified()
         .map(system_time_to_unix_seconds)
         .unwrap_or(0.0);
-    let size = metadata.len() as i64;
-    let checksum = format!("{}_{}", unix_mtime, size);
+    // Git 项目内容指纹未变时沿用缓存的占用大小，避免每次扫描都遍历整个项目树；
+    // 非 Git 目录无法低成本判断内容变化，每次重新统计。
+    let fingerprint = project_content_fingerprint(path);
+    let checksum = match fingerprint.as_deref() {
+        Some(fingerprint) => format!("{SIZE_CHECKSUM_VERSION}:{fingerprint}"),
+        None => format!("{}_{}", unix_mtime, metadata.len()),
+    };
+    let size = match existing_by_path.get(path) {
+        Some(existing) if fingerprint.is_some() && existing.checksum == checksum => existing.size,
+        _ => compute_project_size(path, true, true) as i64,
+    };
 
     let git_info = load_git_info(path);
 
@@ -250,6 +1176,8 @@ fn create_project(path: &str, existing_by_path: &HashMap<&str, &Project>) -> Opt
             git_daily: existing.git_daily.clone(),
             created: existing.created,
             checked: now_swift(),
+            terminal_shell: existing.terminal_shell.clone(),
+            terminal_env: existing.terminal_env.clone(),
         });
     }
 
@@ -268,6 +1196,8 @@ fn create_project(path: &str, existing_by_path: &HashMap<&str, &Project>) -> Opt
         git_daily: None,
         created: now_swift(),
         checked: now_swift(),
+        terminal_shell: None,
+        terminal_env: HashMap::new(),
     })
 }
 
@@ -300,6 +1230,47 @@ fn load_git_info(path: &str) -> GitInfo {
     }
 }
 
+// 计算 Git 项目的内容指纹：HEAD + 工作区改动列表（含未跟踪文件）及各改动文件的 mtime/大小。
+// 提交、编辑（含嵌套目录内的文件）、新增或删除未忽略文件都会改变指纹。
+fn project_content_fingerprint(path: &str) -> Option<String> {
+    let root = Path::new(path);
+    if !is_git_repo(root) {
+        return None;
+    }
+    let head =
+        run_git_command(path, &["rev-parse", "--verify", "--quiet", "HEAD"]).unwrap_or_default();
+    let status = run_git_command(
+        path,
+        &["status", "--porcelain", "-z", "--untracked-files=all"],
+    )?;
+
+    // FNV-1a：结果会持久化到项目缓存，需要跨版本稳定。
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes.iter().chain([&0]) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(head.trim().as_bytes());
+    for entry in status.split('\0').filter(|entry| !entry.is_empty()) {
+        feed(entry.as_bytes());
+        let Some(metadata) = entry
+            .get(3..)
+            .and_then(|relative| fs::symlink_metadata(root.join(relative)).ok())
+        else {
+            continue;
+        };
+        let modified = metadata
+            .modified()
+            .map(|time| swift_date_to_unix_millis(system_time_to_swift(time)))
+            .unwrap_or(0);
+        feed(&modified.to_le_bytes());
+        feed(&metadata.len().to_le_bytes());
+    }
+    Some(format!("{hash:016x}"))
+}
+
 fn is_git_repo(path: &Path) -> bool {
     path.join(".git").exists()
 }
@@ -346,7 +1317,7 @@ fn resolve_gitdir_from_file(git_file_path: &Path) -> Option<PathBuf> {
 
 // 执行 Git 命令并返回输出内容。
 fn run_git_command(path: &str, args: &[&str]) -> Option<String> {
-    let output = Command::new("/usr/bin/git")
+    let output = git_ops::git_command()
         .args(args)
         .current_dir(path)
         .output()
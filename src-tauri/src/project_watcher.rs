@@ -0,0 +1,345 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{
+    Arc, Mutex, Weak,
+    mpsc::{self, Receiver},
+};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+
+use crate::git_ops;
+use crate::models::ProjectChangedPayload;
+
+pub const PROJECT_CHANGED_EVENT: &str = "project-changed";
+
+const WATCH_DEBOUNCE_MS: u64 = 350;
+const IGNORED_DIRS: &[&str] = &["node_modules", "target", "dist", "build"];
+// macOS（FSEvents）与 Windows 的递归监听只占用一个句柄；Linux inotify 的递归监听会为
+// 每个子目录（包括依赖/构建目录）各注册一个 watch，需要自行遍历并跳过忽略目录。
+const NATIVE_RECURSIVE: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// 项目目录监听：按项目路径开启/关闭，文件变更（去抖后）广播 `project-changed` 事件。
+///
+/// 同一路径按引用计数共享 watcher；计数归零时释放，监听线程随通道断开自动退出。
+#[derive(Clone, Default)]
+pub struct ProjectWatcherState {
+    watchers: Arc<Mutex<HashMap<String, WatchEntry>>>,
+}
+
+struct WatchEntry {
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+    ref_count: usize,
+}
+
+impl ProjectWatcherState {
+    pub fn watch(&self, app: &AppHandle, path: &str) -> Result<(), String> {
+        let key = normalize_watch_key(path);
+        if key.is_empty() {
+            return Err("项目路径不能为空".to_string());
+        }
+        if !Path::new(path).is_dir() {
+            return Err("项目目录不存在".to_string());
+        }
+
+        let mut watchers = self
+            .watchers
+            .lock()
+            .map_err(|_| "项目监听状态锁定失败".to_string())?;
+        if let Some(entry) = watchers.get_mut(&key) {
+            entry.ref_count += 1;
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = RecommendedWatcher::new(tx, notify::Config::default())
+            .map_err(|error| format!("创建项目监听失败: {error}"))?;
+        register_watches(&mut watcher, Path::new(path), Path::new(path))
+            .map_err(|error| format!("监听项目目录失败: {error}"))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        // 监听线程只持有弱引用，watcher 释放后通道断开，线程随之退出。
+        let watch_handle = Arc::downgrade(&watcher);
+        let watch_app = app.clone();
+        let watch_path = path.to_string();
+        thread::spawn(move || watch_loop(rx, watch_handle, watch_app, watch_path));
+
+        watchers.insert(
+            key,
+            WatchEntry {
+                _watcher: watcher,
+                ref_count: 1,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn unwatch(&self, path: &str) -> Result<bool, String> {
+        let key = normalize_watch_key(path);
+        let mut watchers = self
+            .watchers
+            .lock()
+            .map_err(|_| "项目监听状态锁定失败".to_string())?;
+        let Some(entry) = watchers.get_mut(&key) else {
+            return Ok(false);
+        };
+        entry.ref_count -= 1;
+        if entry.ref_count == 0 {
+            watchers.remove(&key);
+        }
+        Ok(true)
+    }
+}
+
+fn watch_loop(
+    rx: Receiver<Result<notify::Event, notify::Error>>,
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+    app: AppHandle,
+    path: String,
+) {
+    let debounce = Duration::from_millis(WATCH_DEBOUNCE_MS);
+    let mut pending = false;
+    let mut last_emit = Instant::now()
+        .checked_sub(debounce)
+        .unwrap_or_else(Instant::now);
+
+    loop {
+        match rx.recv_timeout(debounce) {
+            Ok(Ok(event)) => {
+                if !NATIVE_RECURSIVE && matches!(event.kind, EventKind::Create(_)) {
+                    watch_created_dirs(&watcher, &event, Path::new(&path));
+                }
+                if should_emit_for_event(&event, Path::new(&path)) {
+                    pending = true;
+                    if last_emit.elapsed() >= debounce {
+                        pending = false;
+                        last_emit = Instant::now();
+                        emit_project_changed(&app, &path);
+                    }
+                }
+            }
+            Ok(Err(error)) => {
+                log::warn!("项目监听错误 path={}: {}", path, error);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if pending {
+                    pending = false;
+                    last_emit = Instant::now();
+                    emit_project_changed(&app, &path);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+// 非递归后端下新建的目录不会自动被监听，需要补注册。
+fn watch_created_dirs(
+    watcher: &Weak<Mutex<RecommendedWatcher>>,
+    event: &notify::Event,
+    root: &Path,
+) {
+    let Some(watcher) = watcher.upgrade() else {
+        return;
+    };
+    let Ok(mut watcher) = watcher.lock() else {
+        return;
+    };
+    for created in event.paths.iter().filter(|path| path.is_dir()) {
+        if let Err(error) = register_watches(&mut watcher, root, created) {
+            log::warn!("监听新建目录失败 path={}: {}", created.display(), error);
+        }
+    }
+}
+
+fn register_watches(
+    watcher: &mut RecommendedWatcher,
+    root: &Path,
+    dir: &Path,
+) -> notify::Result<()> {
+    if NATIVE_RECURSIVE {
+        return watcher.watch(dir, RecursiveMode::Recursive);
+    }
+    // 仅 dir 本身监听失败视为错误；个别子目录（如无权限）失败只记录日志。
+    for watch_dir in collect_watch_dirs(root, dir) {
+        match watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {}
+            Err(error) if watch_dir == dir => return Err(error),
+            Err(error) => log::warn!("监听子目录失败 path={}: {}", watch_dir.display(), error),
+        }
+    }
+    Ok(())
+}
+
+// 收集 dir 及其下需要监听的目录（不跟随符号链接），跳过忽略目录与无关的 .git 子目录。
+fn collect_watch_dirs(root: &Path, dir: &Path) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        if !should_watch_dir(current.strip_prefix(root).unwrap_or(&current)) {
+            continue;
+        }
+        if let Ok(entries) = fs::read_dir(&current) {
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                    stack.push(entry.path());
+                }
+            }
+        }
+        result.push(current);
+    }
+    result
+}
+
+// 与 is_relevant_path 保持一致：.git 内只需监听自身（index/HEAD）与 refs 子树。
+fn should_watch_dir(relative: &Path) -> bool {
+    let names = normal_components(relative);
+    if names.iter().any(|name| IGNORED_DIRS.contains(name)) {
+        return false;
+    }
+    match names.iter().position(|name| *name == ".git") {
+        Some(index) => matches!(names.get(index + 1).copied(), None | Some("refs")),
+        None => true,
+    }
+}
+
+fn emit_project_changed(app: &AppHandle, path: &str) {
+    git_ops::invalidate_status_cache(path);
+    let payload = ProjectChangedPayload {
+        path: path.to_string(),
+    };
+    if let Err(error) = app.emit(PROJECT_CHANGED_EVENT, payload) {
+        log::warn!("发送 project-changed 失败: {}", error);
+    }
+}
+
+fn should_emit_for_event(event: &notify::Event, root: &Path) -> bool {
+    let matches_kind = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+    if !matches_kind {
+        return false;
+    }
+    event
+        .paths
+        .iter()
+        .any(|path| is_relevant_path(path.strip_prefix(root).unwrap_or(path)))
+}
+
+// 基于项目内相对路径判断：.git 内仅关注 index/HEAD/refs（暂存、提交、切换分支），
+// 忽略对象与锁文件；依赖/构建目录一律忽略。
+fn is_relevant_path(relative: &Path) -> bool {
+    let names = normal_components(relative);
+
+    if names.iter().any(|name| IGNORED_DIRS.contains(name)) {
+        return false;
+    }
+
+    match names.iter().position(|name| *name == ".git") {
+        Some(index) => {
+            let is_lock = relative.extension().is_some_and(|ext| ext == "lock");
+            !is_lock
+                && matches!(
+                    names.get(index + 1).copied(),
+                    Some("index" | "HEAD" | "refs")
+                )
+        }
+        None => true,
+    }
+}
+
+fn normal_components(path: &Path) -> Vec<&str> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn normalize_watch_key(path: &str) -> String {
+    let normalized = path.trim().replace('\\', "/");
+    normalized.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_watch_dirs, is_relevant_path, should_watch_dir};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn is_relevant_path_only_keeps_git_index_head_and_refs() {
+        assert!(is_relevant_path(Path::new("src/main.rs")));
+        assert!(is_relevant_path(Path::new(".git/index")));
+        assert!(is_relevant_path(Path::new(".git/HEAD")));
+        assert!(is_relevant_path(Path::new(".git/refs/heads/main")));
+        assert!(!is_relevant_path(Path::new(".git/index.lock")));
+        assert!(!is_relevant_path(Path::new(".git/refs/heads/main.lock")));
+        assert!(!is_relevant_path(Path::new(".git/objects/ab/cdef")));
+        assert!(!is_relevant_path(Path::new(".git/logs/HEAD")));
+        assert!(!is_relevant_path(Path::new(".git/ORIG_HEAD")));
+    }
+
+    #[test]
+    fn is_relevant_path_ignores_dependency_and_build_dirs() {
+        assert!(!is_relevant_path(Path::new("node_modules/react/index.js")));
+        assert!(!is_relevant_path(Path::new("src-tauri/target/debug/app")));
+        assert!(!is_relevant_path(Path::new("dist/index.html")));
+        assert!(!is_relevant_path(Path::new("build")));
+        assert!(is_relevant_path(Path::new("src/builder.rs")));
+    }
+
+    #[test]
+    fn should_watch_dir_skips_ignored_and_git_internal_dirs() {
+        assert!(should_watch_dir(Path::new("")));
+        assert!(should_watch_dir(Path::new("src")));
+        assert!(should_watch_dir(Path::new(".git")));
+        assert!(should_watch_dir(Path::new(".git/refs/heads")));
+        assert!(!should_watch_dir(Path::new(".git/objects")));
+        assert!(!should_watch_dir(Path::new("node_modules")));
+        assert!(!should_watch_dir(Path::new("app/target")));
+    }
+
+    #[test]
+    fn collect_watch_dirs_does_not_descend_into_ignored_dirs() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        for dir in [
+            "src/nested",
+            "node_modules/pkg/lib",
+            "target/debug",
+            ".git/refs/heads",
+            ".git/objects/ab",
+        ] {
+            fs::create_dir_all(root.join(dir)).expect("create dir");
+        }
+
+        let mut dirs: Vec<String> = collect_watch_dirs(&root, &root)
+            .iter()
+            .map(|dir| {
+                dir.strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        dirs.sort();
+        assert_eq!(
+            dirs,
+            vec![
+                "",
+                ".git",
+                ".git/refs",
+                ".git/refs/heads",
+                "src",
+                "src/nested"
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,516 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use crate::models::{
+    Project, ProjectScript, ProjectScriptAction, ProjectScriptRunResult, RunningScriptStatus,
+};
+use crate::storage;
+use crate::terminal;
+use crate::worktree_setup;
+
+pub const PROJECT_SCRIPT_OUTPUT_EVENT: &str = "project-script-output";
+pub const PROJECT_SCRIPT_EXIT_EVENT: &str = "project-script-exit";
+
+type ScriptKey = (String, String);
+type ScriptMap<M = Box<dyn MasterPty + Send>> = HashMap<ScriptKey, RunningScript<M>>;
+
+/// 项目脚本运行状态：按 (project_id, script_id) 记录正在运行的脚本进程。
+///
+/// 脚本在独立 PTY 中以 login shell 执行，输出通过 `project-script-output` 事件推送，
+/// 退出时广播 `project-script-exit`。
+#[derive(Clone, Default)]
+pub struct ProjectScriptState {
+    scripts: Arc<Mutex<ScriptMap>>,
+}
+
+// master 泛型化仅为测试可替换 PTY 句柄，运行时始终为 `Box<dyn MasterPty + Send>`。
+struct RunningScript<M = Box<dyn MasterPty + Send>> {
+    run_id: String,
+    pid: Option<u32>,
+    started_at: i64,
+    master: M,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+impl<M> RunningScript<M> {
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    // 结束仍在运行的进程，返回是否确实结束了进程。
+    fn terminate(mut self) -> bool {
+        if !self.is_alive() {
+            return false;
+        }
+        // 先关闭 PTY 让前台进程组收到 SIGHUP，再结束 shell 本身。
+        drop(self.master);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        true
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProjectScriptOutputPayload {
+    project_id: String,
+    script_id: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProjectScriptExitPayload {
+    project_id: String,
+    script_id: String,
+    code: Option<u32>,
+}
+
+impl ProjectScriptState {
+    pub fn run(
+        &self,
+        app: &AppHandle,
+        project_path: &str,
+        script_id: &str,
+        action: ProjectScriptAction,
+    ) -> Result<ProjectScriptRunResult, String> {
+        let project = storage::load_projects(app)?
+            .into_iter()
+            .find(|project| project.path == project_path)
+            .ok_or_else(|| "项目不存在".to_string())?;
+        let script = project
+            .scripts
+            .iter()
+            .find(|script| script.id == script_id)
+            .cloned()
+            .ok_or_else(|| "脚本不存在".to_string())?;
+
+        match action {
+            ProjectScriptAction::Start => self.start(app, &project, &script),
+            ProjectScriptAction::Stop => self.stop(&project, &script),
+        }
+    }
+
+    /// 列出已登记的脚本进程；已退出的进程在本次查询中回收并以 alive=false 返回一次。
+    pub fn list_running(&self) -> Result<Vec<RunningScriptStatus>, String> {
+        let mut scripts = self
+            .scripts
+            .lock()
+            .map_err(|_| "脚本运行状态锁定失败".to_string())?;
+        Ok(collect_statuses(&mut scripts))
+    }
+
+    /// 结束所有已登记的脚本进程（应用退出时调用），返回结束的数量；不执行 stop 命令。
+    pub fn stop_all(&self) -> usize {
+        let drained: Vec<RunningScript> = match self.scripts.lock() {
+            Ok(mut scripts) => scripts.drain().map(|(_, running)| running).collect(),
+            Err(_) => return 0,
+        };
+        drained
+            .into_iter()
+            .map(RunningScript::terminate)
+            .filter(|terminated| *terminated)
+            .count()
+    }
+
+    fn start(
+        &self,
+        app: &AppHandle,
+        project: &Project,
+        script: &ProjectScript,
+    ) -> Result<ProjectScriptRunResult, String> {
+        let command = script.start.trim();
+        if command.is_empty() {
+            return Err("启动命令为空".to_string());
+        }
+
+        let key = (project.id.clone(), script.id.clone());
+        let mut scripts = self
+            .scripts
+            .lock()
+            .map_err(|_| "脚本运行状态锁定失败".to_string())?;
+        ensure_not_running(&mut scripts, &key)?;
+
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 120,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|err| format!("创建脚本终端失败: {err}"))?;
+
+        let (shell, args) = worktree_setup::resolve_shell();
+        let mut cmd = CommandBuilder::new(shell);
+        cmd.args(args);
+        cmd.arg(command);
+        cmd.cwd(&project.path);
+        terminal::ensure_terminal_env(&mut cmd);
+        terminal::apply_session_env(&mut cmd, Some(project), None);
+
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|err| format!("启动脚本失败: {err}"))?;
+        drop(pair.slave);
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|err| format!("读取脚本输出失败: {err}"))?;
+
+        let run_id = Uuid::new_v4().to_string();
+        let pid = child.process_id();
+        scripts.insert(
+            key.clone(),
+            RunningScript {
+                run_id: run_id.clone(),
+                pid,
+                started_at: now_millis(),
+                master: pair.master,
+                child,
+            },
+        );
+        drop(scripts);
+
+        let app_handle = app.clone();
+        let scripts_map = self.scripts.clone();
+        thread::spawn(move || forward_script_output(app_handle, scripts_map, key, run_id, reader));
+
+        Ok(ProjectScriptRunResult {
+            project_id: project.id.clone(),
+            script_id: script.id.clone(),
+            pid,
+            running: true,
+        })
+    }
+
+    fn stop(
+        &self,
+        project: &Project,
+        script: &ProjectScript,
+    ) -> Result<ProjectScriptRunResult, String> {
+        let key = (project.id.clone(), script.id.clone());
+        let running = self
+            .scripts
+            .lock()
+            .map_err(|_| "脚本运行状态锁定失败".to_string())?
+            .remove(&key);
+
+        let stop_result = match script.stop.as_deref().map(str::trim) {
+            Some(stop) if !stop.is_empty() => run_stop_command(&project.path, stop),
+            _ => Ok(()),
+        };
+
+        if let Some(running) = running {
+            running.terminate();
+        }
+
+        stop_result.map(|_| ProjectScriptRunResult {
+            project_id: project.id.clone(),
+            script_id: script.id.clone(),
+            pid: None,
+            running: false,
+        })
+    }
+}
+
+// 同步执行脚本的停止命令（如 `docker compose down`），失败时返回输出摘要。
+fn run_stop_command(project_path: &str, command: &str) -> Result<(), String> {
+    let (shell, args) = worktree_setup::resolve_shell();
+    let output = Command::new(shell)
+        .args(args)
+        .arg(command)
+        .current_dir(project_path)
+        .output()
+        .map_err(|err| format!("执行停止命令失败: {err}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let detail = if stderr.is_empty() { stdout } else { stderr };
+    Err(format!("停止命令执行失败: {detail}"))
+}
+
+// 转发脚本输出直到 PTY 关闭；脚本自然退出时回收进程并带上退出码。
+fn forward_script_output(
+    app: AppHandle,
+    scripts: Arc<Mutex<ScriptMap>>,
+    key: ScriptKey,
+    run_id: String,
+    mut reader: Box<dyn Read + Send>,
+) {
+    let (project_id, script_id) = key.clone();
+    let emit_output = |data: String| {
+        let payload = ProjectScriptOutputPayload {
+            project_id: project_id.clone(),
+            script_id: script_id.clone(),
+            data,
+        };
+        if let Err(error) = app.emit(PROJECT_SCRIPT_OUTPUT_EVENT, payload) {
+            log::warn!("发送 project-script-output 失败: {}", error);
+        }
+    };
+
+    let mut buffer = [0u8; 8192];
+    let mut pending_utf8: Vec<u8> = Vec::new();
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(size) => {
+                pending_utf8.extend_from_slice(&buffer[..size]);
+                let data = terminal::drain_utf8_stream(&mut pending_utf8);
+                if !data.is_empty() {
+                    emit_output(data);
+                }
+            }
+        }
+    }
+    if !pending_utf8.is_empty() {
+        emit_output(String::from_utf8_lossy(&pending_utf8).to_string());
+    }
+
+    let finished = scripts
+        .lock()
+        .ok()
+        .and_then(|mut scripts| take_current_run(&mut scripts, &key, &run_id));
+    let code =
+        finished.and_then(|mut running| running.child.wait().ok().map(|status| status.exit_code()));
+
+    let payload = ProjectScriptExitPayload {
+        project_id,
+        script_id,
+        code,
+    };
+    if let Err(error) = app.emit(PROJECT_SCRIPT_EXIT_EVENT, payload) {
+        log::warn!("发送 project-script-exit 失败: {}", error);
+    }
+}
+
+// 汇总登记的脚本状态（按启动时间倒序），并回收已退出的记录。
+fn collect_statuses<M>(scripts: &mut ScriptMap<M>) -> Vec<RunningScriptStatus> {
+    let mut statuses = Vec::with_capacity(scripts.len());
+    scripts.retain(|(project_id, script_id), running| {
+        let alive = running.is_alive();
+        statuses.push(RunningScriptStatus {
+            project_id: project_id.clone(),
+            script_id: script_id.clone(),
+            pid: running.pid,
+            started_at: running.started_at,
+            alive,
+        });
+        alive
+    });
+    statuses.sort_by_key(|status| std::cmp::Reverse(status.started_at));
+    statuses
+}
+
+// 同一脚本仍在运行时拒绝重复启动；已退出的旧记录直接清理。
+fn ensure_not_running<M>(scripts: &mut ScriptMap<M>, key: &ScriptKey) -> Result<(), String> {
+    if let Some(running) = scripts.get_mut(key) {
+        if running.is_alive() {
+            return Err("脚本已在运行".to_string());
+        }
+        scripts.remove(key);
+    }
+    Ok(())
+}
+
+// 仅回收属于本次运行的记录，避免误删停止后重新启动的同名脚本。
+fn take_current_run<M>(
+    scripts: &mut ScriptMap<M>,
+    key: &ScriptKey,
+    run_id: &str,
+) -> Option<RunningScript<M>> {
+    if scripts
+        .get(key)
+        .is_some_and(|running| running.run_id == run_id)
+    {
+        scripts.remove(key)
+    } else {
+        None
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RunningScript, ScriptKey, ScriptMap, collect_statuses, ensure_not_running, take_current_run,
+    };
+    use portable_pty::{Child, ChildKiller, ExitStatus};
+    use std::io::Result as IoResult;
+    use std::sync::{Arc, Mutex};
+
+    // 不依赖 PTY 的假进程：exit_code 为 None 表示仍在运行。
+    #[derive(Debug, Clone, Default)]
+    struct FakeChild {
+        exit_code: Arc<Mutex<Option<u32>>>,
+        killed: Arc<Mutex<bool>>,
+    }
+
+    impl FakeChild {
+        fn exit(&self, code: u32) {
+            *self.exit_code.lock().unwrap() = Some(code);
+        }
+
+        fn was_killed(&self) -> bool {
+            *self.killed.lock().unwrap()
+        }
+    }
+
+    impl ChildKiller for FakeChild {
+        fn kill(&mut self) -> IoResult<()> {
+            *self.killed.lock().unwrap() = true;
+            self.exit(1);
+            Ok(())
+        }
+
+        fn clone_killer(&self) -> Box<dyn ChildKiller + Send + Sync> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl Child for FakeChild {
+        fn try_wait(&mut self) -> IoResult<Option<ExitStatus>> {
+            Ok(self
+                .exit_code
+                .lock()
+                .unwrap()
+                .map(ExitStatus::with_exit_code))
+        }
+
+        fn wait(&mut self) -> IoResult<ExitStatus> {
+            Ok(ExitStatus::with_exit_code(
+                self.exit_code.lock().unwrap().unwrap_or(0),
+            ))
+        }
+
+        fn process_id(&self) -> Option<u32> {
+            None
+        }
+
+        #[cfg(windows)]
+        fn as_raw_handle(&self) -> Option<std::os::windows::io::RawHandle> {
+            None
+        }
+    }
+
+    fn key(project_id: &str, script_id: &str) -> ScriptKey {
+        (project_id.to_string(), script_id.to_string())
+    }
+
+    fn insert(
+        scripts: &mut ScriptMap<()>,
+        key: ScriptKey,
+        run_id: &str,
+        started_at: i64,
+    ) -> FakeChild {
+        let child = FakeChild::default();
+        scripts.insert(
+            key,
+            RunningScript {
+                run_id: run_id.to_string(),
+                pid: Some(started_at as u32),
+                started_at,
+                master: (),
+                child: Box::new(child.clone()),
+            },
+        );
+        child
+    }
+
+    #[test]
+    fn take_current_run_ignores_stale_run_id() {
+        let mut scripts = ScriptMap::<()>::new();
+        let script = key("p1", "dev");
+        insert(&mut scripts, script.clone(), "run-2", 2);
+
+        // 旧运行的输出线程结束时不应回收重新启动的新记录。
+        assert!(take_current_run(&mut scripts, &script, "run-1").is_none());
+        assert!(scripts.contains_key(&script));
+
+        let taken = take_current_run(&mut scripts, &script, "run-2").expect("current run");
+        assert_eq!(taken.run_id, "run-2");
+        assert!(scripts.is_empty());
+        assert!(take_current_run(&mut scripts, &script, "run-2").is_none());
+    }
+
+    #[test]
+    fn collect_statuses_reaps_exited_scripts_once() {
+        let mut scripts = ScriptMap::<()>::new();
+        insert(&mut scripts, key("p1", "dev"), "run-1", 10);
+        let exited = insert(&mut scripts, key("p1", "build"), "run-2", 20);
+        exited.exit(0);
+
+        let statuses = collect_statuses(&mut scripts);
+        let summary: Vec<(&str, bool)> = statuses
+            .iter()
+            .map(|status| (status.script_id.as_str(), status.alive))
+            .collect();
+        assert_eq!(summary, vec![("build", false), ("dev", true)]);
+
+        let statuses = collect_statuses(&mut scripts);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].script_id, "dev");
+        assert!(statuses[0].alive);
+    }
+
+    #[test]
+    fn ensure_not_running_rejects_live_script_and_clears_exited_one() {
+        let mut scripts = ScriptMap::<()>::new();
+        let script = key("p1", "dev");
+        let child = insert(&mut scripts, script.clone(), "run-1", 1);
+
+        assert_eq!(
+            ensure_not_running(&mut scripts, &script),
+            Err("脚本已在运行".to_string())
+        );
+        assert!(ensure_not_running(&mut scripts, &key("p2", "dev")).is_ok());
+
+        child.exit(0);
+        assert!(ensure_not_running(&mut scripts, &script).is_ok());
+        assert!(scripts.is_empty());
+    }
+
+    #[test]
+    fn stop_terminates_only_the_matching_project_script() {
+        let mut scripts = ScriptMap::<()>::new();
+        let target = insert(&mut scripts, key("p1", "dev"), "run-1", 1);
+        let same_script_other_project = insert(&mut scripts, key("p2", "dev"), "run-2", 2);
+        let other_script_same_project = insert(&mut scripts, key("p1", "test"), "run-3", 3);
+
+        let running = scripts
+            .remove(&key("p1", "dev"))
+            .expect("registered script");
+        assert!(running.terminate());
+
+        assert!(target.was_killed());
+        assert!(!same_script_other_project.was_killed());
+        assert!(!other_script_same_project.was_killed());
+        assert_eq!(scripts.len(), 2);
+
+        // 已退出的进程不会被再次结束。
+        other_script_same_project.exit(0);
+        let running = scripts
+            .remove(&key("p1", "test"))
+            .expect("registered script");
+        assert!(!running.terminate());
+        assert!(!other_script_same_project.was_killed());
+    }
+}
@@ -1,15 +1,29 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tauri::{AppHandle, Manager};
 
 use crate::models::{
-    AppStateFile, HeatmapCacheFile, Project, TerminalWorkspace, TerminalWorkspaceSummary,
-    TerminalWorkspacesFile,
+    AppStateFile, DataBackupResult, HeatmapCacheFile, Project, TerminalWorkspace,
+    TerminalWorkspaceSummary, TerminalWorkspacesFile,
 };
 
+const TERMINAL_WORKSPACES_FILE: &str = "terminal_workspaces.json";
+const TERMINAL_WORKSPACES_BACKUP_FILE: &str = "terminal_workspaces.json.bak";
+// 当前支持的终端工作空间文件版本。
+const TERMINAL_WORKSPACES_VERSION: i32 = 1;
+
+// 备份/恢复涉及的数据文件。
+const DATA_FILES: [&str; 4] = [
+    "app_state.json",
+    "projects.json",
+    "heatmap_cache.json",
+    "terminal_workspaces.json",
+];
+
 // 获取应用数据目录。
 fn app_support_dir(app: &AppHandle) -> Result<PathBuf, String> {
     let home_dir = app
@@ -20,12 +34,12 @@ fn app_support_dir(app: &AppHandle) -> Result<PathBuf, String> {
 }
 
 // 确保目录存在。
-fn ensure_dir(path: &PathBuf) -> Result<(), String> {
+fn ensure_dir(path: &Path) -> Result<(), String> {
     fs::create_dir_all(path).map_err(|err| format!("无法创建目录: {err}"))
 }
 
 // 读取并反序列化 JSON 文件。
-fn read_json<T: DeserializeOwned>(path: &PathBuf) -> Result<T, String> {
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
     let data = fs::read(path).map_err(|err| format!("无法读取文件: {err}"))?;
     serde_json::from_slice(&data).map_err(|err| format!("解析 JSON 失败: {err}"))
 }
@@ -43,6 +57,31 @@ fn write_json_compact<T: Serialize + ?Sized>(path: &PathBuf, value: &T) -> Resul
     fs::write(path, data).map_err(|err| format!("写入文件失败: {err}"))
 }
 
+// 以易读格式原子写入 JSON。
+fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let data =
+        serde_json::to_vec_pretty(value).map_err(|err| format!("序列化 JSON 失败: {err}"))?;
+    write_atomic(path, &data)
+}
+
+// 先写入同目录临时文件再重命名覆盖，避免写入中断留下半截文件。
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    let mut file = fs::File::create(&tmp_path).map_err(|err| format!("写入文件失败: {err}"))?;
+    file.write_all(data)
+        .and_then(|_| file.sync_all())
+        .map_err(|err| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("写入文件失败: {err}")
+        })?;
+    fs::rename(&tmp_path, path).map_err(|err| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("写入文件失败: {err}")
+    })
+}
+
 /// 读取应用状态文件。
 pub fn load_app_state(app: &AppHandle) -> Result<AppStateFile, String> {
     let dir = app_support_dir(app)?;
@@ -100,29 +139,24 @@ pub fn save_heatmap_cache(app: &AppHandle, cache: &HeatmapCacheFile) -> Result<(
     write_json_pretty(&file_path, cache)
 }
 
-/// 读取终端工作空间集合。
+/// 读取终端工作空间集合；文件损坏时改名保留原文件并回退到 `.bak`，均不可用则返回空集合。
 pub fn load_terminal_workspaces(app: &AppHandle) -> Result<TerminalWorkspacesFile, String> {
     let dir = app_support_dir(app)?;
     ensure_dir(&dir)?;
-    let file_path = dir.join("terminal_workspaces.json");
-    if !file_path.exists() {
-        return Ok(TerminalWorkspacesFile::default());
-    }
-    read_json(&file_path)
+    Ok(load_terminal_workspaces_from(&dir))
 }
 
-/// 保存终端工作空间集合。
+/// 保存终端工作空间集合（原子写入，写入前将可解析的旧文件保留为 `.bak`）。
 pub fn save_terminal_workspaces(
     app: &AppHandle,
     workspaces: &TerminalWorkspacesFile,
 ) -> Result<(), String> {
     let dir = app_support_dir(app)?;
     ensure_dir(&dir)?;
-    let file_path = dir.join("terminal_workspaces.json");
-    write_json_pretty(&file_path, workspaces)
+    save_terminal_workspaces_to(&dir, workspaces)
 }
 
-/// 读取指定项目终端工作空间。
+/// 读取指定项目终端工作空间；文件损坏或条目格式无效时返回 `None`。
 pub fn load_terminal_workspace(
     app: &AppHandle,
     project_path: &str,
@@ -131,12 +165,15 @@ pub fn load_terminal_workspace(
     Ok(workspaces.workspaces.get(project_path).cloned())
 }
 
-/// 保存指定项目终端工作空间。
+/// 保存指定项目终端工作空间，工作空间必须是 JSON 对象。
 pub fn save_terminal_workspace(
     app: &AppHandle,
     project_path: &str,
     workspace: TerminalWorkspace,
 ) -> Result<(), String> {
+    if !workspace.is_object() {
+        return Err("终端工作空间格式无效".to_string());
+    }
     let mut workspaces = load_terminal_workspaces(app)?;
     workspaces
         .workspaces
@@ -189,3 +226,326 @@ pub fn list_terminal_workspace_summaries(
 
     Ok(summaries)
 }
+
+// 从数据目录读取终端工作空间；主文件损坏时先改名保留（便于手工恢复），再回退到 `.bak`。
+fn load_terminal_workspaces_from(dir: &Path) -> TerminalWorkspacesFile {
+    let file_path = dir.join(TERMINAL_WORKSPACES_FILE);
+    if !file_path.exists() {
+        return TerminalWorkspacesFile::default();
+    }
+    let error = match read_terminal_workspaces_file(&file_path) {
+        Ok(workspaces) => return workspaces,
+        Err(error) => error,
+    };
+
+    log::warn!("终端工作空间文件损坏，尝试读取备份: {}", error);
+    let corrupt_path = dir.join(format!(
+        "{TERMINAL_WORKSPACES_FILE}.corrupt-{}",
+        chrono::Utc::now().timestamp_millis()
+    ));
+    match fs::rename(&file_path, &corrupt_path) {
+        Ok(()) => log::warn!("损坏的终端工作空间文件已保留为 {}", corrupt_path.display()),
+        Err(error) => log::warn!("保留损坏的终端工作空间文件失败: {}", error),
+    }
+
+    match read_terminal_workspaces_file(&dir.join(TERMINAL_WORKSPACES_BACKUP_FILE)) {
+        Ok(workspaces) => workspaces,
+        Err(backup_error) => {
+            log::warn!("终端工作空间备份不可用，使用空布局: {}", backup_error);
+            TerminalWorkspacesFile::default()
+        }
+    }
+}
+
+// 将终端工作空间写入数据目录；只备份能解析的旧文件，避免损坏内容覆盖掉仍可用的备份。
+fn save_terminal_workspaces_to(
+    dir: &Path,
+    workspaces: &TerminalWorkspacesFile,
+) -> Result<(), String> {
+    let file_path = dir.join(TERMINAL_WORKSPACES_FILE);
+    if read_terminal_workspaces_file(&file_path).is_ok()
+        && let Err(error) = fs::copy(&file_path, dir.join(TERMINAL_WORKSPACES_BACKUP_FILE))
+    {
+        log::warn!("备份终端工作空间失败: {}", error);
+    }
+    write_json_atomic(&file_path, workspaces)
+}
+
+// 读取并校验终端工作空间文件：版本过高视为不可用，非对象的工作空间条目丢弃。
+fn read_terminal_workspaces_file(path: &Path) -> Result<TerminalWorkspacesFile, String> {
+    let mut workspaces: TerminalWorkspacesFile = read_json(path)?;
+    if workspaces.version > TERMINAL_WORKSPACES_VERSION {
+        return Err(format!("不支持的终端工作空间版本: {}", workspaces.version));
+    }
+    workspaces.workspaces.retain(|project_path, workspace| {
+        let valid = workspace.is_object();
+        if !valid {
+            log::warn!("忽略格式无效的终端工作空间: {}", project_path);
+        }
+        valid
+    });
+    Ok(workspaces)
+}
+
+/// 将数据目录中的配置与状态文件打包为带时间戳的 zip 备份。
+pub fn backup_data_dir(app: &AppHandle, dest: &str) -> Result<DataBackupResult, String> {
+    let dir = app_support_dir(app)?;
+    backup_data_files(&dir, dest)
+}
+
+/// 从 zip 备份恢复数据文件，所有内容校验通过后才会写入。
+pub fn restore_data_dir(app: &AppHandle, src: &str) -> Result<DataBackupResult, String> {
+    let dir = app_support_dir(app)?;
+    restore_data_files(&dir, src)
+}
+
+// 将数据目录中的数据文件打包写入 `dest` 目录下带时间戳的 zip。
+fn backup_data_files(dir: &Path, dest: &str) -> Result<DataBackupResult, String> {
+    let dest_dir = PathBuf::from(dest);
+    ensure_dir(&dest_dir)?;
+
+    let file_name = format!(
+        "devhaven-backup-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let zip_path = dest_dir.join(file_name);
+    let file = fs::File::create(&zip_path).map_err(|err| format!("创建备份文件失败: {err}"))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut files = Vec::new();
+    for name in DATA_FILES {
+        let source = dir.join(name);
+        if !source.is_file() {
+            continue;
+        }
+        let data = fs::read(&source).map_err(|err| format!("无法读取文件: {err}"))?;
+        writer
+            .start_file(name, options)
+            .map_err(|err| format!("写入备份失败: {err}"))?;
+        writer
+            .write_all(&data)
+            .map_err(|err| format!("写入备份失败: {err}"))?;
+        files.push(name.to_string());
+    }
+    writer
+        .finish()
+        .map_err(|err| format!("写入备份失败: {err}"))?;
+
+    Ok(DataBackupResult {
+        path: zip_path.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+// 校验 zip 中的全部数据文件后逐个原子写入数据目录。
+fn restore_data_files(dir: &Path, src: &str) -> Result<DataBackupResult, String> {
+    let file = fs::File::open(Path::new(src)).map_err(|err| format!("无法打开备份文件: {err}"))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|err| format!("无法解析备份文件: {err}"))?;
+
+    let mut entries: Vec<(&str, Vec<u8>)> = Vec::new();
+    for name in DATA_FILES {
+        let mut entry = match archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(err) => return Err(format!("读取备份内容失败: {err}")),
+        };
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|err| format!("读取备份内容失败: {err}"))?;
+        validate_data_file(name, &data)?;
+        entries.push((name, data));
+    }
+
+    if entries.is_empty() {
+        return Err("备份文件中没有可恢复的数据".to_string());
+    }
+
+    ensure_dir(dir)?;
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, data) in entries {
+        write_atomic(&dir.join(name), &data)?;
+        files.push(name.to_string());
+    }
+
+    Ok(DataBackupResult {
+        path: dir.to_string_lossy().to_string(),
+        files,
+    })
+}
+
+// 按文件类型校验备份内容可被正常解析。
+fn validate_data_file(name: &str, data: &[u8]) -> Result<(), String> {
+    let result = match name {
+        "app_state.json" => serde_json::from_slice::<AppStateFile>(data).map(|_| ()),
+        "projects.json" => serde_json::from_slice::<Vec<Project>>(data).map(|_| ()),
+        "heatmap_cache.json" => serde_json::from_slice::<HeatmapCacheFile>(data).map(|_| ()),
+        "terminal_workspaces.json" => {
+            serde_json::from_slice::<TerminalWorkspacesFile>(data).map(|_| ())
+        }
+        _ => return Err(format!("未知的备份文件: {name}")),
+    };
+    result.map_err(|err| format!("备份文件 {name} 校验失败: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TERMINAL_WORKSPACES_BACKUP_FILE, TERMINAL_WORKSPACES_FILE, backup_data_files,
+        load_terminal_workspaces_from, restore_data_files, save_terminal_workspaces_to,
+        write_json_atomic,
+    };
+    use crate::models::TerminalWorkspacesFile;
+    use serde_json::json;
+    use std::fs;
+
+    fn workspaces_with(project_path: &str, layout: &str) -> TerminalWorkspacesFile {
+        let mut workspaces = TerminalWorkspacesFile::default();
+        workspaces.workspaces.insert(
+            project_path.to_string(),
+            json!({ "projectId": "p1", "layout": layout }),
+        );
+        workspaces
+    }
+
+    #[test]
+    fn write_json_atomic_replaces_file_without_leaving_temp() {
+        let dir = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("data.json");
+        fs::write(&path, "old").expect("write old file");
+
+        write_json_atomic(&path, &json!({ "value": 1 })).expect("atomic write");
+        let data: serde_json::Value =
+            serde_json::from_slice(&fs::read(&path).expect("read file")).expect("parse file");
+        assert_eq!(data, json!({ "value": 1 }));
+        assert!(!dir.join("data.json.tmp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn terminal_workspaces_round_trip_and_keep_backup() {
+        let dir = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create dir");
+        assert!(load_terminal_workspaces_from(&dir).workspaces.is_empty());
+
+        save_terminal_workspaces_to(&dir, &workspaces_with("/a", "v1")).expect("save v1");
+        save_terminal_workspaces_to(&dir, &workspaces_with("/a", "v2")).expect("save v2");
+
+        let loaded = load_terminal_workspaces_from(&dir);
+        assert_eq!(loaded.workspaces["/a"]["layout"], "v2");
+        let backup: TerminalWorkspacesFile = serde_json::from_slice(
+            &fs::read(dir.join(TERMINAL_WORKSPACES_BACKUP_FILE)).expect("read backup"),
+        )
+        .expect("parse backup");
+        assert_eq!(backup.workspaces["/a"]["layout"], "v1");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn corrupt_terminal_workspaces_fall_back_to_backup_and_are_kept_aside() {
+        let dir = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create dir");
+        save_terminal_workspaces_to(&dir, &workspaces_with("/a", "v1")).expect("save v1");
+        save_terminal_workspaces_to(&dir, &workspaces_with("/a", "v2")).expect("save v2");
+        fs::write(
+            dir.join(TERMINAL_WORKSPACES_FILE),
+            "{\"version\": 1, \"works",
+        )
+        .expect("corrupt");
+
+        let loaded = load_terminal_workspaces_from(&dir);
+        assert_eq!(loaded.workspaces["/a"]["layout"], "v1");
+        assert!(!dir.join(TERMINAL_WORKSPACES_FILE).exists());
+        let kept: Vec<String> = fs::read_dir(&dir)
+            .expect("read dir")
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("terminal_workspaces.json.corrupt-"))
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join(&kept[0])).expect("read corrupt copy"),
+            "{\"version\": 1, \"works"
+        );
+
+        // 主文件与备份都不可用时返回空集合，且不会删除备份。
+        fs::write(dir.join(TERMINAL_WORKSPACES_FILE), "not json").expect("corrupt again");
+        fs::write(dir.join(TERMINAL_WORKSPACES_BACKUP_FILE), "not json").expect("corrupt backup");
+        assert!(load_terminal_workspaces_from(&dir).workspaces.is_empty());
+        assert!(dir.join(TERMINAL_WORKSPACES_BACKUP_FILE).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn terminal_workspaces_schema_rejects_newer_version_and_drops_invalid_entries() {
+        let dir = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("create dir");
+        fs::write(
+            dir.join(TERMINAL_WORKSPACES_FILE),
+            json!({ "version": 1, "workspaces": { "/a": { "layout": "v1" }, "/b": "broken" } })
+                .to_string(),
+        )
+        .expect("write workspaces");
+
+        let loaded = load_terminal_workspaces_from(&dir);
+        assert!(loaded.workspaces.contains_key("/a"));
+        assert!(!loaded.workspaces.contains_key("/b"));
+
+        fs::write(
+            dir.join(TERMINAL_WORKSPACES_FILE),
+            json!({ "version": 99, "workspaces": {} }).to_string(),
+        )
+        .expect("write newer version");
+        assert!(load_terminal_workspaces_from(&dir).workspaces.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backup_then_restore_round_trips_data_files() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let data_dir = root.join("data");
+        let backup_dir = root.join("backup");
+        fs::create_dir_all(&data_dir).expect("create data dir");
+        let projects = json!([]).to_string();
+        let workspaces = serde_json::to_string(&workspaces_with("/a", "v1")).expect("serialize");
+        fs::write(data_dir.join("projects.json"), &projects).expect("write projects");
+        fs::write(data_dir.join(TERMINAL_WORKSPACES_FILE), &workspaces).expect("write workspaces");
+
+        let backup =
+            backup_data_files(&data_dir, &backup_dir.to_string_lossy()).expect("backup data files");
+        assert_eq!(
+            backup.files,
+            vec!["projects.json", TERMINAL_WORKSPACES_FILE]
+        );
+
+        fs::write(data_dir.join("projects.json"), "changed").expect("change projects");
+        fs::remove_file(data_dir.join(TERMINAL_WORKSPACES_FILE)).expect("remove workspaces");
+
+        let restored = restore_data_files(&data_dir, &backup.path).expect("restore data files");
+        assert_eq!(restored.files, backup.files);
+        assert_eq!(
+            fs::read_to_string(data_dir.join("projects.json")).expect("read projects"),
+            projects
+        );
+        assert_eq!(
+            fs::read_to_string(data_dir.join(TERMINAL_WORKSPACES_FILE)).expect("read workspaces"),
+            workspaces
+        );
+        let leftovers = fs::read_dir(&data_dir)
+            .expect("read data dir")
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
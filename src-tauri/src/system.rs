@@ -1,9 +1,71 @@
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use tauri::AppHandle;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
+use crate::models::{DetectedEditor, OpenToolFailureReason, OpenToolSettings, OpenToolValidation};
+
+// 常见编辑器探测项。
+struct EditorCandidate {
+    name: &'static str,
+    binaries: &'static [&'static str],
+    known_paths: &'static [&'static str],
+    default_args: &'static [&'static str],
+}
+
+// 常见编辑器探测表：PATH 中的可执行名优先，其次为已知安装位置。
+const EDITOR_CANDIDATES: &[EditorCandidate] = &[
+    EditorCandidate {
+        name: "Visual Studio Code",
+        binaries: &["code"],
+        known_paths: &["/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code"],
+        default_args: &[],
+    },
+    EditorCandidate {
+        name: "Cursor",
+        binaries: &["cursor"],
+        known_paths: &["/Applications/Cursor.app/Contents/Resources/app/bin/cursor"],
+        default_args: &[],
+    },
+    EditorCandidate {
+        name: "Zed",
+        binaries: &["zed", "zeditor"],
+        known_paths: &["/Applications/Zed.app/Contents/MacOS/cli"],
+        default_args: &[],
+    },
+    EditorCandidate {
+        name: "IntelliJ IDEA",
+        binaries: &["idea"],
+        known_paths: &[
+            "/Applications/IntelliJ IDEA.app/Contents/MacOS/idea",
+            "/Applications/IntelliJ IDEA CE.app/Contents/MacOS/idea",
+        ],
+        default_args: &[],
+    },
+    EditorCandidate {
+        name: "Sublime Text",
+        binaries: &["subl"],
+        known_paths: &["/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl"],
+        default_args: &[],
+    },
+    EditorCandidate {
+        name: "Vim",
+        binaries: &["vim"],
+        known_paths: &[],
+        default_args: &[],
+    },
+];
+
+// GUI 应用启动时 PATH 往往不完整，额外探测的常见 bin 目录。
+const EXTRA_BIN_DIRS: &[&str] = &[
+    "/usr/local/bin",
+    "/opt/homebrew/bin",
+    "/usr/bin",
+    "/snap/bin",
+];
+
 #[derive(Debug, serde::Deserialize)]
 pub struct EditorOpenParams {
     pub path: String,
@@ -13,8 +75,60 @@ pub struct EditorOpenParams {
     pub arguments: Option<Vec<String>>,
 }
 
-/// 在系统文件管理器中定位路径。
-pub fn open_in_finder(path: &str) -> Result<(), String> {
+/// 在系统文件管理器中打开路径。
+/// `reveal` 为 true 时在所在目录中选中目标（macOS `open -R`，Linux 优先 `nautilus --select`）；
+/// 为 false 时直接打开目录（目标为文件时打开其所在目录）。
+pub fn open_in_finder(path: &str, reveal: bool) -> Result<(), String> {
+    let target = Path::new(path);
+    if reveal {
+        return reveal_in_file_manager(path);
+    }
+
+    let dir = if target.is_file() {
+        target.parent().unwrap_or(target)
+    } else {
+        target
+    };
+    open_with_default(&dir.to_string_lossy())
+}
+
+/// 将文件或目录移到系统废纸篓（macOS 通过 Finder，Linux 使用 `gio trash`）。
+pub fn move_to_trash(path: &str) -> Result<(), String> {
+    if !Path::new(path).exists() {
+        return Err(format!("路径不存在: {path}"));
+    }
+
+    if cfg!(target_os = "macos") {
+        let escaped = path.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("tell application \"Finder\" to delete (POSIX file \"{escaped}\")");
+        let output = Command::new("/usr/bin/osascript")
+            .args(["-e", &script])
+            .output()
+            .map_err(|err| format!("无法调用 Finder: {err}"))?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(format!("移到废纸篓失败: {stderr}"));
+    }
+
+    if let Some(gio) = find_in_path("gio") {
+        let output = Command::new(gio)
+            .args(["trash", path])
+            .output()
+            .map_err(|err| format!("无法调用 gio: {err}"))?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(format!("移到废纸篓失败: {stderr}"));
+    }
+
+    Err("当前平台不支持移到废纸篓".to_string())
+}
+
+// 在文件管理器中选中目标。
+fn reveal_in_file_manager(path: &str) -> Result<(), String> {
     if cfg!(target_os = "macos") {
         let status = Command::new("/usr/bin/open")
             .args(["-R", path])
@@ -26,7 +140,20 @@ pub fn open_in_finder(path: &str) -> Result<(), String> {
         return Err("Finder 打开失败".to_string());
     }
 
-    open_with_default(path)
+    if let Some(nautilus) = find_in_path("nautilus") {
+        let status = Command::new(nautilus)
+            .args(["--select", path])
+            .status()
+            .map_err(|err| format!("无法打开文件管理器: {err}"))?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+
+    // 无法选中时退化为打开所在目录。
+    let target = Path::new(path);
+    let dir = target.parent().unwrap_or(target);
+    open_with_default(&dir.to_string_lossy())
 }
 
 /// 使用指定编辑器打开文件或目录。
@@ -54,12 +181,9 @@ pub fn open_in_editor(params: EditorOpenParams) -> Result<(), String> {
     }
 
     if let Some(command_path) = params.command_path {
-        let mut command = Command::new(command_path);
-        if let Some(arguments) = params.arguments {
-            command.args(arguments);
-        }
-        let status = command
-            .arg(params.path)
+        let arguments = params.arguments.unwrap_or_default();
+        let status = Command::new(command_path)
+            .args(expand_tool_arguments(&arguments, &params.path))
             .status()
             .map_err(|err| format!("打开编辑器失败: {err}"))?;
         if status.success() {
@@ -70,6 +194,213 @@ pub fn open_in_editor(params: EditorOpenParams) -> Result<(), String> {
     Err("未能打开编辑器".to_string())
 }
 
+/// 在外部终端中打开目录；未配置终端工具时回退到系统默认终端。
+pub fn open_in_terminal(path: &str, tool: &OpenToolSettings) -> Result<(), String> {
+    let command_path = tool.command_path.trim();
+    if !command_path.is_empty() {
+        Command::new(command_path)
+            .args(expand_tool_arguments(&tool.arguments, path))
+            .current_dir(path)
+            .spawn()
+            .map_err(|err| format!("打开终端失败: {err}"))?;
+        return Ok(());
+    }
+
+    if cfg!(target_os = "macos") {
+        let status = Command::new("/usr/bin/open")
+            .args(["-a", "Terminal", path])
+            .status()
+            .map_err(|err| format!("打开终端失败: {err}"))?;
+        if status.success() {
+            return Ok(());
+        }
+        return Err("终端打开失败".to_string());
+    }
+
+    Command::new("x-terminal-emulator")
+        .current_dir(path)
+        .spawn()
+        .map_err(|err| format!("打开终端失败: {err}"))?;
+    Ok(())
+}
+
+// 展开工具参数中的占位符：`{path}` 为完整路径，`{name}` 为末级名称，`{dir}` 为所在目录。
+// 参数中未出现任何占位符时，沿用旧行为把路径追加到末尾。
+fn expand_tool_arguments(arguments: &[String], path: &str) -> Vec<String> {
+    const PLACEHOLDERS: [&str; 3] = ["{path}", "{name}", "{dir}"];
+    let has_placeholder = arguments.iter().any(|arg| {
+        PLACEHOLDERS
+            .iter()
+            .any(|placeholder| arg.contains(placeholder))
+    });
+    if !has_placeholder {
+        let mut expanded = arguments.to_vec();
+        expanded.push(path.to_string());
+        return expanded;
+    }
+
+    let target = Path::new(path);
+    let name = target
+        .file_name()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = target
+        .parent()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let values = [
+        ("{path}", path),
+        ("{name}", name.as_str()),
+        ("{dir}", dir.as_str()),
+    ];
+    arguments
+        .iter()
+        .map(|arg| replace_placeholders(arg, &values))
+        .collect()
+}
+
+// 单次扫描替换占位符，已替换进来的值（如路径中含 `{name}`）不会被再次展开。
+fn replace_placeholders(arg: &str, values: &[(&str, &str)]) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+    'scan: while !rest.is_empty() {
+        for (placeholder, value) in values {
+            if let Some(after) = rest.strip_prefix(placeholder) {
+                expanded.push_str(value);
+                rest = after;
+                continue 'scan;
+            }
+        }
+        let mut chars = rest.chars();
+        if let Some(ch) = chars.next() {
+            expanded.push(ch);
+        }
+        rest = chars.as_str();
+    }
+    expanded
+}
+
+/// 探测本机已安装的常见编辑器（PATH + 已知安装位置）。
+pub fn detect_editors() -> Vec<DetectedEditor> {
+    EDITOR_CANDIDATES
+        .iter()
+        .filter_map(|candidate| {
+            let command_path = candidate
+                .binaries
+                .iter()
+                .find_map(|binary| find_in_path(binary))
+                .or_else(|| {
+                    candidate
+                        .known_paths
+                        .iter()
+                        .map(PathBuf::from)
+                        .find(|path| is_executable(path))
+                })?;
+            Some(DetectedEditor {
+                name: candidate.name.to_string(),
+                command_path: command_path.to_string_lossy().to_string(),
+                default_args: candidate
+                    .default_args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// 校验外部工具配置：命令路径需存在且可执行（裸命令名按 PATH 解析）。
+pub fn validate_open_tool(settings: &OpenToolSettings) -> OpenToolValidation {
+    let command_path = settings.command_path.trim();
+    if command_path.is_empty() {
+        return open_tool_failure(OpenToolFailureReason::Empty, "命令路径不能为空");
+    }
+
+    let candidate = Path::new(command_path);
+    let resolved = if candidate.components().count() == 1 && !candidate.is_absolute() {
+        match find_in_path(command_path) {
+            Some(path) => path,
+            None => {
+                return open_tool_failure(
+                    OpenToolFailureReason::NotFound,
+                    &format!("在 PATH 中未找到命令: {command_path}"),
+                );
+            }
+        }
+    } else {
+        candidate.to_path_buf()
+    };
+
+    let metadata = match std::fs::metadata(&resolved) {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            return open_tool_failure(
+                OpenToolFailureReason::NotFound,
+                &format!("命令路径不存在: {command_path}"),
+            );
+        }
+    };
+    if !metadata.is_file() {
+        return open_tool_failure(
+            OpenToolFailureReason::NotAFile,
+            &format!("命令路径不是文件: {command_path}"),
+        );
+    }
+    if !is_executable(&resolved) {
+        return open_tool_failure(
+            OpenToolFailureReason::NotExecutable,
+            &format!("命令不可执行: {command_path}"),
+        );
+    }
+
+    OpenToolValidation {
+        ok: true,
+        resolved_path: Some(resolved.to_string_lossy().to_string()),
+        reason: None,
+        message: None,
+    }
+}
+
+// 构造工具校验失败结果。
+fn open_tool_failure(reason: OpenToolFailureReason, message: &str) -> OpenToolValidation {
+    OpenToolValidation {
+        ok: false,
+        resolved_path: None,
+        reason: Some(reason),
+        message: Some(message.to_string()),
+    }
+}
+
+// 在 PATH 与常见 bin 目录中查找可执行文件。
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|value| std::env::split_paths(&value).collect())
+        .unwrap_or_default();
+    dirs.extend(EXTRA_BIN_DIRS.iter().map(PathBuf::from));
+    dirs.into_iter()
+        .map(|dir| dir.join(binary))
+        .find(|candidate| is_executable(candidate))
+}
+
+// 判断路径是否为可执行文件。
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
 /// 复制文本到系统剪贴板（跨平台）。
 pub fn copy_to_clipboard(app: &AppHandle, content: &str) -> Result<(), String> {
     if let Err(err) = app.clipboard().write_text(content.to_string()) {
@@ -86,6 +417,11 @@ pub fn copy_to_clipboard(app: &AppHandle, content: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// 读取系统剪贴板文本；剪贴板为空或为非文本内容时返回 None。
+pub fn read_from_clipboard(app: &AppHandle) -> Option<String> {
+    app.clipboard().read_text().ok()
+}
+
 #[cfg(target_os = "macos")]
 fn copy_with_pbcopy(content: &str) -> Result<(), std::io::Error> {
     let mut child = Command::new("/usr/bin/pbcopy")
@@ -107,7 +443,12 @@ fn copy_with_pbcopy(content: &str) -> Result<(), std::io::Error> {
 
 // 使用系统默认方式打开路径。
 fn open_with_default(path: &str) -> Result<(), String> {
-    let status = Command::new("/usr/bin/open")
+    let opener = if cfg!(target_os = "macos") {
+        "/usr/bin/open"
+    } else {
+        "xdg-open"
+    };
+    let status = Command::new(opener)
         .arg(path)
         .status()
         .map_err(|err| format!("无法打开路径: {err}"))?;
@@ -117,3 +458,54 @@ fn open_with_default(path: &str) -> Result<(), String> {
         Err("打开路径失败".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::expand_tool_arguments;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn expand_tool_arguments_appends_path_without_placeholders() {
+        assert_eq!(
+            expand_tool_arguments(&args(&["--new-window"]), "/work/app"),
+            args(&["--new-window", "/work/app"])
+        );
+        assert_eq!(
+            expand_tool_arguments(&[], "/work/app"),
+            args(&["/work/app"])
+        );
+    }
+
+    #[test]
+    fn expand_tool_arguments_replaces_each_placeholder() {
+        let path = "/work/app";
+        assert_eq!(
+            expand_tool_arguments(&args(&["{path}"]), path),
+            args(&[path])
+        );
+        assert_eq!(
+            expand_tool_arguments(&args(&["--title={name}"]), path),
+            args(&["--title=app"])
+        );
+        assert_eq!(
+            expand_tool_arguments(&args(&["--cwd", "{dir}"]), path),
+            args(&["--cwd", "/work"])
+        );
+        assert_eq!(
+            expand_tool_arguments(&args(&["{dir}/{name}:{path}"]), path),
+            args(&["/work/app:/work/app"])
+        );
+    }
+
+    #[test]
+    fn expand_tool_arguments_does_not_reexpand_substituted_values() {
+        let path = "/work/{name}/{dir}";
+        assert_eq!(
+            expand_tool_arguments(&args(&["{path}", "{name}"]), path),
+            args(&[path, "{dir}"])
+        );
+    }
+}
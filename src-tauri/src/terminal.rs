@@ -1,20 +1,27 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::{self, File};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use portable_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
 use serde::Serialize;
 use serde_json::Value;
 use sysinfo::{Pid, System};
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
-use crate::models::TerminalCodexPaneOverlay;
+use crate::codex_monitor;
+use crate::file_tail::read_tail_non_empty_lines_resilient;
+use crate::models::{
+    OpenToolSettings, Project, TerminalCodexPaneOverlay, TerminalPaneCommandStatus,
+};
+use crate::storage;
+use crate::system;
 
 const TERMINAL_OUTPUT_EVENT: &str = "terminal-output";
 const TERMINAL_EXIT_EVENT: &str = "terminal-exit";
@@ -22,13 +29,21 @@ const MAX_ROLLOUT_TAIL_LINES: usize = 800;
 const MAX_ROLLOUT_TAIL_BYTES: u64 = 192 * 1024;
 const MAX_ROLLOUT_TAIL_BYTES_CAP: u64 = 2 * 1024 * 1024;
 const MAX_ROLLOUT_JSON_LINE_BYTES: usize = 2 * 1024 * 1024;
+const OSC_COMMAND_FINISHED_PREFIX: &[u8] = b"\x1b]133;D";
+const MAX_OSC_CARRY_BYTES: usize = 64;
+const BRACKETED_PASTE_ENABLE: &[u8] = b"\x1b[?2004h";
+const BRACKETED_PASTE_DISABLE: &[u8] = b"\x1b[?2004l";
+const ALT_SCREEN_ENABLE: &[u8] = b"\x1b[?1049h";
+const ALT_SCREEN_DISABLE: &[u8] = b"\x1b[?1049l";
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
 
 /// 将 PTY 的字节流按 UTF-8 逐步解码。
 ///
 /// 关键点：PTY 读到的字节可能会把一个 UTF-8 字符拆到两次 read() 里。
 /// 如果每次 read() 都直接 `String::from_utf8_lossy(&chunk)`，就会把拆开的字符解成 `�`，
 /// 在中文/emoji 等多字节字符场景看起来像“乱码”。
-fn drain_utf8_stream(pending: &mut Vec<u8>) -> String {
+pub(crate) fn drain_utf8_stream(pending: &mut Vec<u8>) -> String {
     if pending.is_empty() {
         return String::new();
     }
@@ -69,6 +84,184 @@ fn drain_utf8_stream(pending: &mut Vec<u8>) -> String {
     out
 }
 
+// 从 PTY 输出中识别 shell integration 的命令结束标记（OSC 133;D[;exit_code]），
+// 标记可能被拆到两次 read() 中，未闭合的部分暂存到下一次。
+#[derive(Default)]
+struct CommandExitScanner {
+    carry: Vec<u8>,
+}
+
+impl CommandExitScanner {
+    // 返回本段输出中最后一个带退出码的命令结束标记。
+    fn feed(&mut self, chunk: &[u8]) -> Option<i32> {
+        let mut data = std::mem::take(&mut self.carry);
+        data.extend_from_slice(chunk);
+
+        let mut last_code = None;
+        let mut cursor = 0;
+        while let Some(offset) = find_bytes(&data[cursor..], OSC_COMMAND_FINISHED_PREFIX) {
+            let start = cursor + offset;
+            let params_start = start + OSC_COMMAND_FINISHED_PREFIX.len();
+            let Some((params_len, terminator_len)) = find_osc_terminator(&data[params_start..])
+            else {
+                if data.len() - start <= MAX_OSC_CARRY_BYTES {
+                    self.carry = data[start..].to_vec();
+                }
+                return last_code;
+            };
+            if let Some(code) =
+                parse_command_exit_code(&data[params_start..params_start + params_len])
+            {
+                last_code = Some(code);
+            }
+            cursor = params_start + params_len + terminator_len;
+        }
+
+        // 保留末尾可能是前缀一部分的字节。
+        let keep = (OSC_COMMAND_FINISHED_PREFIX.len() - 1).min(data.len() - cursor);
+        self.carry = data[data.len() - keep..].to_vec();
+        last_code
+    }
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// OSC 以 BEL 或 ST（ESC \）结束，返回参数长度与结束符长度。
+fn find_osc_terminator(data: &[u8]) -> Option<(usize, usize)> {
+    for (index, byte) in data.iter().enumerate() {
+        match byte {
+            0x07 => return Some((index, 1)),
+            0x1b if data.get(index + 1) == Some(&b'\\') => return Some((index, 2)),
+            0x1b => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_command_exit_code(params: &[u8]) -> Option<i32> {
+    let params = std::str::from_utf8(params).ok()?;
+    let code = params.strip_prefix(';')?.split(';').next()?;
+    code.trim().parse().ok()
+}
+
+// 跟踪前台程序对某个 DEC 私有模式（如 bracketed paste、备用屏幕）的开关，以最后一次切换为准。
+struct DecModeTracker {
+    enable: &'static [u8],
+    disable: &'static [u8],
+    tail: Vec<u8>,
+}
+
+impl DecModeTracker {
+    fn new(enable: &'static [u8], disable: &'static [u8]) -> Self {
+        Self {
+            enable,
+            disable,
+            tail: Vec::new(),
+        }
+    }
+
+    fn bracketed_paste() -> Self {
+        Self::new(BRACKETED_PASTE_ENABLE, BRACKETED_PASTE_DISABLE)
+    }
+
+    fn alt_screen() -> Self {
+        Self::new(ALT_SCREEN_ENABLE, ALT_SCREEN_DISABLE)
+    }
+
+    fn feed(&mut self, chunk: &[u8]) -> Option<bool> {
+        let mut data = std::mem::take(&mut self.tail);
+        data.extend_from_slice(chunk);
+
+        let enabled_at = rfind_bytes(&data, self.enable);
+        let disabled_at = rfind_bytes(&data, self.disable);
+        let keep = (self.enable.len().max(self.disable.len()) - 1).min(data.len());
+        self.tail = data[data.len() - keep..].to_vec();
+
+        match (enabled_at, disabled_at) {
+            (Some(enabled), Some(disabled)) => Some(enabled > disabled),
+            (Some(_), None) => Some(true),
+            (None, Some(_)) => Some(false),
+            (None, None) => None,
+        }
+    }
+}
+
+fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+// 用 bracketed paste 标记包裹粘贴内容；去掉内容中的结束标记，避免提前退出粘贴模式。
+fn wrap_bracketed_paste(data: &str) -> String {
+    format!(
+        "{BRACKETED_PASTE_START}{}{BRACKETED_PASTE_END}",
+        data.replace(BRACKETED_PASTE_END, "")
+    )
+}
+
+// 单个 pane 的输出限速：按 1 秒窗口统计字节数，超出阈值的输出被丢弃，
+// 下一个窗口开始（或会话结束）时以一行提示汇总被省略的字节数。
+struct OutputRateLimiter {
+    max_bytes_per_sec: u64,
+    window_start: Instant,
+    window_bytes: u64,
+    dropped_bytes: u64,
+}
+
+impl OutputRateLimiter {
+    fn new(max_bytes_per_sec: u64) -> Self {
+        Self {
+            max_bytes_per_sec,
+            window_start: Instant::now(),
+            window_bytes: 0,
+            dropped_bytes: 0,
+        }
+    }
+
+    // 返回 (本次可转发的前缀字节数, 需要先输出的省略提示)：超出剩余额度的部分被丢弃，
+    // 前缀截断在 UTF-8 字符边界上；`now` 由调用方传入，便于测试窗口切换。
+    fn admit(&mut self, data: &[u8], now: Instant) -> (usize, Option<String>) {
+        if self.max_bytes_per_sec == 0 {
+            return (data.len(), None);
+        }
+
+        let mut notice = None;
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            notice = self.take_notice();
+            self.window_start = now;
+            self.window_bytes = 0;
+        }
+
+        let budget = self.max_bytes_per_sec.saturating_sub(self.window_bytes);
+        let mut admitted =
+            usize::try_from(budget).map_or(data.len(), |budget| budget.min(data.len()));
+        if admitted < data.len() {
+            // 不在多字节字符中间截断：回退到下一个字符的起始字节。
+            while admitted > 0 && data[admitted] & 0xC0 == 0x80 {
+                admitted -= 1;
+            }
+        }
+        self.window_bytes += admitted as u64;
+        self.dropped_bytes += (data.len() - admitted) as u64;
+        (admitted, notice)
+    }
+
+    fn take_notice(&mut self) -> Option<String> {
+        if self.dropped_bytes == 0 {
+            return None;
+        }
+        let notice = format!("\r\n[输出过快，已省略 {} 字节]\r\n", self.dropped_bytes);
+        self.dropped_bytes = 0;
+        Some(notice)
+    }
+}
+
 #[derive(Default)]
 pub struct TerminalState {
     pub sessions: Arc<Mutex<HashMap<String, Arc<PtySession>>>>,
@@ -82,6 +275,8 @@ pub struct TerminalSessionMeta {
     pub session_id: String,
     pub pty_id: String,
     pub shell_pid: Option<u32>,
+    pub project_path: String,
+    pub project_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -95,6 +290,12 @@ pub struct PtySession {
     pub master: Mutex<Box<dyn MasterPty + Send>>,
     pub writer: Mutex<Box<dyn Write + Send>>,
     pub child: Mutex<Box<dyn Child + Send>>,
+    /// 最近一条命令的退出码及记录时间（毫秒）。
+    pub last_command_exit: Mutex<Option<(i32, i64)>>,
+    /// 前台程序是否开启了 bracketed paste 模式。
+    pub bracketed_paste: AtomicBool,
+    /// 前台程序是否处于备用屏幕（vim/less/htop 等全屏程序）。
+    pub alt_screen: AtomicBool,
 }
 
 #[derive(Debug, Serialize)]
@@ -105,6 +306,17 @@ pub struct TerminalCreateResult {
     pub shell: String,
 }
 
+/// 终端会话归属的项目信息，用于前端将会话与项目对应。
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSessionMetadata {
+    pub session_id: String,
+    pub pty_id: String,
+    pub project_path: String,
+    pub project_id: Option<String>,
+    pub shell_pid: Option<u32>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct TerminalOutputPayload {
@@ -123,6 +335,48 @@ fn default_shell() -> String {
     std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
 }
 
+// 按路径查找会话所属项目，用于读取项目级终端配置。
+fn find_session_project(app: &AppHandle, project_path: &str) -> Option<Project> {
+    storage::load_projects(app)
+        .ok()?
+        .into_iter()
+        .find(|project| project.path == project_path)
+}
+
+// 解析会话 shell：优先使用显式指定，其次使用项目配置；无法找到可执行文件时回退默认 shell。
+fn resolve_session_shell(project: Option<&Project>, requested: Option<String>) -> String {
+    let configured = requested
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| {
+            project?
+                .terminal_shell
+                .as_ref()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        });
+
+    let Some(configured) = configured else {
+        return default_shell();
+    };
+
+    let validation = system::validate_open_tool(&OpenToolSettings {
+        command_path: configured.clone(),
+        arguments: Vec::new(),
+    });
+    match validation.resolved_path {
+        Some(resolved) if validation.ok => resolved,
+        _ => {
+            log::warn!(
+                "项目 shell 不可用，回退默认 shell: shell={} reason={}",
+                configured,
+                validation.message.unwrap_or_default()
+            );
+            default_shell()
+        }
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn resolve_login_username() -> Option<String> {
     if let Some(user) = std::env::var("USER")
@@ -162,7 +416,27 @@ fn build_terminal_command(shell: &str) -> CommandBuilder {
     CommandBuilder::new(shell.to_string())
 }
 
-fn ensure_terminal_env(cmd: &mut CommandBuilder) {
+// 在基础环境之上叠加项目环境变量，显式传入的变量优先；空变量名或含 `=` 的变量名忽略。
+pub(crate) fn apply_session_env(
+    cmd: &mut CommandBuilder,
+    project: Option<&Project>,
+    env: Option<HashMap<String, String>>,
+) {
+    let project_env = project.map(|project| project.terminal_env.clone());
+    for (key, value) in project_env
+        .into_iter()
+        .flatten()
+        .chain(env.into_iter().flatten())
+    {
+        let key = key.trim();
+        if key.is_empty() || key.contains('=') {
+            continue;
+        }
+        cmd.env(key, value);
+    }
+}
+
+pub(crate) fn ensure_terminal_env(cmd: &mut CommandBuilder) {
     // GUI 启动的 macOS App 往往缺少 TERM/PATH 等环境变量，导致交互式 shell 初始化时报错。
     if cmd.get_env("TERM").is_none() {
         cmd.env("TERM", "xterm-256color");
@@ -301,78 +575,13 @@ fn parse_timestamp(value: &Value) -> Option<i64> {
         .map(|dt| dt.timestamp_millis())
 }
 
-fn read_tail_lines(path: &Path, max_lines: usize, max_bytes: u64) -> Result<Vec<String>, String> {
-    let mut file = File::open(path).map_err(|err| format!("读取会话文件失败: {err}"))?;
-    let size = file
-        .metadata()
-        .map_err(|err| format!("读取文件元信息失败: {err}"))?
-        .len();
-    let start = if size > max_bytes {
-        size - max_bytes
-    } else {
-        0
-    };
-    let read_start = if start > 0 {
-        start.saturating_sub(1)
-    } else {
-        0
-    };
-
-    file.seek(SeekFrom::Start(read_start))
-        .map_err(|err| format!("定位会话文件失败: {err}"))?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)
-        .map_err(|err| format!("读取会话文件失败: {err}"))?;
-
-    let text = String::from_utf8_lossy(&buffer);
-    let mut lines: Vec<&str> = text.split('\n').collect();
-    if read_start > 0 && !lines.is_empty() {
-        lines.remove(0);
-    }
-
-    let mut trimmed: Vec<String> = lines
-        .into_iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| line.to_string())
-        .collect();
-
-    if trimmed.len() > max_lines {
-        trimmed = trimmed.split_off(trimmed.len() - max_lines);
-    }
-
-    Ok(trimmed)
-}
-
-fn read_tail_lines_resilient(
-    path: &Path,
-    max_lines: usize,
-    initial_bytes: u64,
-    max_bytes_cap: u64,
-) -> Result<Vec<String>, String> {
-    let size = fs::metadata(path)
-        .map_err(|err| format!("读取文件元信息失败: {err}"))?
-        .len();
-    if size == 0 {
-        return Ok(Vec::new());
-    }
-
-    let mut bytes = initial_bytes.min(size).max(1024);
-    loop {
-        let lines = read_tail_lines(path, max_lines, bytes)?;
-        if !lines.is_empty() || bytes >= size || bytes >= max_bytes_cap {
-            return Ok(lines);
-        }
-        bytes = bytes.saturating_mul(2).min(size).min(max_bytes_cap);
-    }
-}
-
 fn parse_rollout_context(path: &Path) -> RolloutContextInfo {
     let mut info = RolloutContextInfo {
         updated_at: file_modified_millis(path).unwrap_or(0),
         ..RolloutContextInfo::default()
     };
 
-    let lines = match read_tail_lines_resilient(
+    let lines = match read_tail_non_empty_lines_resilient(
         path,
         MAX_ROLLOUT_TAIL_LINES,
         MAX_ROLLOUT_TAIL_BYTES,
@@ -589,12 +798,7 @@ pub fn terminal_get_codex_pane_overlay(
         return Ok(Vec::new());
     }
 
-    let codex_sessions_root = app
-        .path()
-        .home_dir()
-        .map_err(|err| format!("无法获取用户目录: {err}"))?
-        .join(".codex")
-        .join("sessions");
+    let codex_sessions_root = codex_monitor::resolve_sessions_dir(&app)?;
     if !codex_sessions_root.exists() {
         return Ok(Vec::new());
     }
@@ -648,7 +852,68 @@ pub fn terminal_get_codex_pane_overlay(
     Ok(overlays)
 }
 
+// 选取 shell 最近启动的直接子进程作为前台命令。
+fn resolve_foreground_command(
+    shell_pid: u32,
+    system: &System,
+    children_index: &HashMap<u32, Vec<u32>>,
+) -> Option<String> {
+    children_index
+        .get(&shell_pid)?
+        .iter()
+        .filter_map(|pid| system.process(Pid::from_u32(*pid)))
+        .max_by_key(|process| process.start_time())
+        .map(|process| process.name().to_string())
+}
+
 #[tauri::command]
+pub fn terminal_get_pane_command_status(
+    state: State<TerminalState>,
+    window_label: String,
+    session_ids: Vec<String>,
+) -> Result<Vec<TerminalPaneCommandStatus>, String> {
+    if session_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let target_sessions = collect_target_terminal_sessions(&state, &window_label, &session_ids)?;
+    if target_sessions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut system = System::new();
+    system.refresh_processes();
+    let children_index = build_process_children_index(&system);
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "终端会话锁定失败".to_string())?;
+
+    let mut statuses = Vec::new();
+    for terminal_session in target_sessions {
+        let current_command = terminal_session
+            .shell_pid
+            .and_then(|shell_pid| resolve_foreground_command(shell_pid, &system, &children_index));
+        let session = sessions.get(&terminal_session.pty_id);
+        let last_exit = session
+            .and_then(|session| session.last_command_exit.lock().ok().and_then(|exit| *exit));
+        let in_alt_screen =
+            session.is_some_and(|session| session.alt_screen.load(Ordering::Relaxed));
+
+        statuses.push(TerminalPaneCommandStatus {
+            session_id: terminal_session.session_id,
+            current_command,
+            last_exit_code: last_exit.map(|(code, _)| code),
+            last_exit_at: last_exit.map(|(_, at)| at),
+            in_alt_screen,
+        });
+    }
+
+    Ok(statuses)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn terminal_create_session(
     app: AppHandle,
     state: State<TerminalState>,
@@ -657,8 +922,12 @@ pub fn terminal_create_session(
     rows: u16,
     window_label: String,
     session_id: Option<String>,
+    project_id: Option<String>,
+    shell: Option<String>,
+    env: Option<HashMap<String, String>>,
 ) -> Result<TerminalCreateResult, String> {
-    let shell = default_shell();
+    let project = find_session_project(&app, &project_path);
+    let shell = resolve_session_shell(project.as_ref(), shell);
     let pty_system = native_pty_system();
     let pair = pty_system
         .openpty(PtySize {
@@ -670,8 +939,9 @@ pub fn terminal_create_session(
         .map_err(|err| format!("创建终端失败: {err}"))?;
 
     let mut cmd = build_terminal_command(&shell);
-    cmd.cwd(project_path);
+    cmd.cwd(&project_path);
     ensure_terminal_env(&mut cmd);
+    apply_session_env(&mut cmd, project.as_ref(), env);
 
     let child = pair
         .slave
@@ -695,6 +965,9 @@ pub fn terminal_create_session(
         master: Mutex::new(master),
         writer: Mutex::new(writer),
         child: Mutex::new(child),
+        last_command_exit: Mutex::new(None),
+        bracketed_paste: AtomicBool::new(false),
+        alt_screen: AtomicBool::new(false),
     });
 
     {
@@ -712,6 +985,8 @@ pub fn terminal_create_session(
             session_id: session_id.clone(),
             pty_id: pty_id.clone(),
             shell_pid,
+            project_path: project_path.clone(),
+            project_id,
         },
     ) {
         if let Ok(mut sessions) = state.sessions.lock() {
@@ -731,26 +1006,62 @@ pub fn terminal_create_session(
     let session_id_for_output = session_id.clone();
     let window_label_for_output = window_label.clone();
     let pty_id_for_output = pty_id.clone();
+    let session_for_output = session.clone();
+
+    let max_output_bytes_per_sec = storage::load_app_state(&app)
+        .map(|state| state.settings.terminal_max_output_bytes_per_sec)
+        .unwrap_or(0);
 
     thread::spawn(move || {
         let mut reader = reader;
         let mut buffer = [0u8; 8192];
         let mut pending_utf8: Vec<u8> = Vec::new();
+        let mut rate_limiter = OutputRateLimiter::new(max_output_bytes_per_sec);
+        let mut exit_scanner = CommandExitScanner::default();
+        let mut paste_tracker = DecModeTracker::bracketed_paste();
+        let mut alt_screen_tracker = DecModeTracker::alt_screen();
+        let emit_output = |data: String| {
+            let _ = app_handle.emit_to(
+                &window_label_for_output,
+                TERMINAL_OUTPUT_EVENT,
+                TerminalOutputPayload {
+                    session_id: session_id_for_output.clone(),
+                    data,
+                },
+            );
+        };
         loop {
             match reader.read(&mut buffer) {
                 Ok(0) => break,
                 Ok(size) => {
-                    pending_utf8.extend_from_slice(&buffer[..size]);
+                    // 限速丢弃的输出同样需要识别，避免漏掉命令结束标记。
+                    if let Some(code) = exit_scanner.feed(&buffer[..size])
+                        && let Ok(mut last_exit) = session_for_output.last_command_exit.lock()
+                    {
+                        *last_exit = Some((code, now_millis()));
+                    }
+                    if let Some(enabled) = paste_tracker.feed(&buffer[..size]) {
+                        session_for_output
+                            .bracketed_paste
+                            .store(enabled, Ordering::Relaxed);
+                    }
+                    if let Some(active) = alt_screen_tracker.feed(&buffer[..size]) {
+                        session_for_output
+                            .alt_screen
+                            .store(active, Ordering::Relaxed);
+                    }
+                    let (admitted, notice) = rate_limiter.admit(&buffer[..size], Instant::now());
+                    if let Some(notice) = notice {
+                        emit_output(notice);
+                    }
+                    pending_utf8.extend_from_slice(&buffer[..admitted]);
                     let data = drain_utf8_stream(&mut pending_utf8);
                     if !data.is_empty() {
-                        let _ = app_handle.emit_to(
-                            &window_label_for_output,
-                            TERMINAL_OUTPUT_EVENT,
-                            TerminalOutputPayload {
-                                session_id: session_id_for_output.clone(),
-                                data,
-                            },
-                        );
+                        emit_output(data);
+                    }
+                    if admitted < size {
+                        // 有数据被丢弃时清空未完成的 UTF-8 残片，避免与后续数据拼出错误字符。
+                        pending_utf8.clear();
                     }
                 }
                 Err(_) => break,
@@ -761,16 +1072,12 @@ pub fn terminal_create_session(
         if !pending_utf8.is_empty() {
             let data = String::from_utf8_lossy(&pending_utf8).to_string();
             if !data.is_empty() {
-                let _ = app_handle.emit_to(
-                    &window_label_for_output,
-                    TERMINAL_OUTPUT_EVENT,
-                    TerminalOutputPayload {
-                        session_id: session_id_for_output.clone(),
-                        data,
-                    },
-                );
+                emit_output(data);
             }
         }
+        if let Some(notice) = rate_limiter.take_notice() {
+            emit_output(notice);
+        }
 
         let _ = app_handle.emit_to(
             &window_label_for_output,
@@ -798,18 +1105,46 @@ pub fn terminal_create_session(
 }
 
 #[tauri::command]
-pub fn terminal_write(
+pub fn terminal_get_session_metadata(
     state: State<TerminalState>,
-    pty_id: String,
-    data: String,
+    window_label: String,
+    session_id: String,
+) -> Result<Option<TerminalSessionMetadata>, String> {
+    let key = build_terminal_session_key(&window_label, &session_id);
+    let session_meta_by_key = state
+        .session_meta_by_key
+        .lock()
+        .map_err(|_| "终端会话元信息锁定失败".to_string())?;
+    Ok(session_meta_by_key
+        .get(&key)
+        .map(|meta| TerminalSessionMetadata {
+            session_id: meta.session_id.clone(),
+            pty_id: meta.pty_id.clone(),
+            project_path: meta.project_path.clone(),
+            project_id: meta.project_id.clone(),
+            shell_pid: meta.shell_pid,
+        }))
+}
+
+// 向指定 PTY 写入数据；bracketed 为 true 且前台程序开启了 bracketed paste 时包裹粘贴标记。
+fn write_to_session(
+    state: &TerminalState,
+    pty_id: &str,
+    data: &str,
+    bracketed: bool,
 ) -> Result<(), String> {
     let sessions = state
         .sessions
         .lock()
         .map_err(|_| "终端会话锁定失败".to_string())?;
     let session = sessions
-        .get(&pty_id)
+        .get(pty_id)
         .ok_or_else(|| "终端会话不存在".to_string())?;
+    let data = if bracketed && session.bracketed_paste.load(Ordering::Relaxed) {
+        wrap_bracketed_paste(data)
+    } else {
+        data.to_string()
+    };
     let mut writer = session
         .writer
         .lock()
@@ -823,6 +1158,39 @@ pub fn terminal_write(
     Ok(())
 }
 
+#[tauri::command]
+pub fn terminal_write(
+    state: State<TerminalState>,
+    pty_id: String,
+    data: String,
+    bracketed: Option<bool>,
+) -> Result<(), String> {
+    write_to_session(&state, &pty_id, &data, bracketed.unwrap_or(false))
+}
+
+/// 在终端中执行一行命令：写入命令文本后发送回车。
+#[tauri::command]
+pub fn terminal_run_command(
+    state: State<TerminalState>,
+    pty_id: String,
+    command: String,
+) -> Result<(), String> {
+    let line = build_command_line(&command)?;
+    write_to_session(&state, &pty_id, &line, false)
+}
+
+// 去掉末尾换行后追加回车（\r 对应终端里的 Enter 键）；多行命令直接拒绝。
+fn build_command_line(command: &str) -> Result<String, String> {
+    let command = command.trim_end_matches(['\r', '\n']);
+    if command.trim().is_empty() {
+        return Err("命令不能为空".to_string());
+    }
+    if command.contains(['\r', '\n']) {
+        return Err("命令只能包含一行".to_string());
+    }
+    Ok(format!("{command}\r"))
+}
+
 #[tauri::command]
 pub fn terminal_resize(
     state: State<TerminalState>,
@@ -877,3 +1245,223 @@ pub fn terminal_kill(state: State<TerminalState>, pty_id: String) -> Result<(),
     );
     Ok(())
 }
+
+/// 结束所有终端会话的 shell 进程（应用退出时调用），返回结束的数量。
+pub fn kill_all_sessions(state: &TerminalState) -> usize {
+    let sessions: Vec<Arc<PtySession>> = match state.sessions.lock() {
+        Ok(mut sessions) => sessions.drain().map(|(_, session)| session).collect(),
+        Err(_) => return 0,
+    };
+    for session in &sessions {
+        if let Ok(mut child) = session.child.lock() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+    if let Ok(mut meta) = state.session_meta_by_key.lock() {
+        meta.clear();
+    }
+    if let Ok(mut keys) = state.pty_to_session_key.lock() {
+        keys.clear();
+    }
+    sessions.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CommandExitScanner, DecModeTracker, OutputRateLimiter, apply_session_env,
+        build_command_line, drain_utf8_stream, wrap_bracketed_paste,
+    };
+    use crate::models::Project;
+    use portable_pty::CommandBuilder;
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::time::Duration;
+
+    #[test]
+    fn apply_session_env_skips_invalid_keys_and_prefers_explicit_env() {
+        let project = Project {
+            id: "p1".to_string(),
+            name: "repo".to_string(),
+            path: "/repo".to_string(),
+            tags: Vec::new(),
+            scripts: Vec::new(),
+            worktrees: Vec::new(),
+            mtime: 0.0,
+            size: 0,
+            checksum: String::new(),
+            git_commits: 0,
+            git_last_commit: 0.0,
+            git_daily: None,
+            created: 0.0,
+            checked: 0.0,
+            terminal_shell: None,
+            terminal_env: HashMap::from([
+                ("APP_ENV".to_string(), "dev".to_string()),
+                (" PORT ".to_string(), "3000".to_string()),
+                ("".to_string(), "empty".to_string()),
+                ("BAD=KEY".to_string(), "x".to_string()),
+            ]),
+        };
+        let mut cmd = CommandBuilder::new("sh");
+        apply_session_env(
+            &mut cmd,
+            Some(&project),
+            Some(HashMap::from([("APP_ENV".to_string(), "test".to_string())])),
+        );
+
+        assert_eq!(cmd.get_env("APP_ENV"), Some(OsStr::new("test")));
+        assert_eq!(cmd.get_env("PORT"), Some(OsStr::new("3000")));
+        assert_eq!(cmd.get_env("BAD=KEY"), None);
+        assert_eq!(cmd.get_env("BAD"), None);
+        assert!(cmd.iter_extra_env_as_str().all(|(key, _)| !key.is_empty()));
+    }
+
+    #[test]
+    fn drain_utf8_stream_reassembles_character_split_across_reads() {
+        let bytes = "终端".as_bytes();
+        let mut pending = bytes[..2].to_vec();
+        assert_eq!(drain_utf8_stream(&mut pending), "");
+        assert_eq!(pending.len(), 2);
+
+        pending.extend_from_slice(&bytes[2..4]);
+        assert_eq!(drain_utf8_stream(&mut pending), "终");
+        assert_eq!(pending, bytes[3..4].to_vec());
+
+        pending.extend_from_slice(&bytes[4..]);
+        assert_eq!(drain_utf8_stream(&mut pending), "端");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn drain_utf8_stream_replaces_invalid_bytes_without_stalling() {
+        let mut pending = vec![b'a', 0xFF, b'b'];
+        assert_eq!(drain_utf8_stream(&mut pending), "a\u{FFFD}b");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn command_exit_scanner_reads_osc_133_exit_codes() {
+        let mut scanner = CommandExitScanner::default();
+        assert_eq!(
+            scanner.feed(b"make\r\n\x1b]133;D;2\x07\x1b]133;A\x07$ "),
+            Some(2)
+        );
+        assert_eq!(scanner.feed(b"\x1b]133;D;0\x1b\\"), Some(0));
+        assert_eq!(scanner.feed(b"\x1b]133;D\x07"), None);
+        assert_eq!(scanner.feed(b"plain output"), None);
+    }
+
+    #[test]
+    fn command_exit_scanner_handles_marker_split_across_reads() {
+        let mut scanner = CommandExitScanner::default();
+        assert_eq!(scanner.feed(b"done\x1b]13"), None);
+        assert_eq!(scanner.feed(b"3;D;1"), None);
+        assert_eq!(scanner.feed(b"27;aid=1\x07"), Some(127));
+        assert_eq!(scanner.feed(b"\x07"), None);
+    }
+
+    #[test]
+    fn build_command_line_appends_enter_and_rejects_multiline() {
+        assert_eq!(
+            build_command_line("npm run dev\n").unwrap(),
+            "npm run dev\r"
+        );
+        assert_eq!(build_command_line("echo 'a b'").unwrap(), "echo 'a b'\r");
+        assert!(build_command_line("  \n").is_err());
+        assert!(build_command_line("echo a\necho b").is_err());
+    }
+
+    #[test]
+    fn dec_mode_tracker_follows_last_mode_switch() {
+        let mut tracker = DecModeTracker::bracketed_paste();
+        assert_eq!(tracker.feed(b"$ \x1b[?2004h"), Some(true));
+        assert_eq!(tracker.feed(b"ls\r\n\x1b[?2004l\r"), Some(false));
+        assert_eq!(tracker.feed(b"output"), None);
+        assert_eq!(tracker.feed(b"\x1b[?20"), None);
+        assert_eq!(tracker.feed(b"04h$ "), Some(true));
+    }
+
+    #[test]
+    fn wrap_bracketed_paste_strips_embedded_end_marker() {
+        assert_eq!(
+            wrap_bracketed_paste("echo a\n\x1b[201~rm -rf x\n"),
+            "\x1b[200~echo a\nrm -rf x\n\x1b[201~"
+        );
+    }
+
+    #[test]
+    fn dec_mode_tracker_detects_alt_screen_of_full_screen_apps() {
+        let mut tracker = DecModeTracker::alt_screen();
+        assert_eq!(tracker.feed(b"\x1b[?2004h$ vim\r\n"), None);
+        assert_eq!(tracker.feed(b"\x1b[?1049h\x1b[22;0;0t"), Some(true));
+        assert_eq!(tracker.feed(b"\x1b[?1049l\x1b[?2004h$ "), Some(false));
+    }
+
+    #[test]
+    fn output_rate_limiter_is_disabled_by_zero_limit() {
+        let mut limiter = OutputRateLimiter::new(0);
+        let now = limiter.window_start;
+        assert_eq!(limiter.admit(&[b'x'; 8192], now), (8192, None));
+        assert_eq!(limiter.take_notice(), None);
+    }
+
+    #[test]
+    fn output_rate_limiter_drops_over_limit_and_reports_on_rollover() {
+        let mut limiter = OutputRateLimiter::new(10);
+        let start = limiter.window_start;
+
+        assert_eq!(limiter.admit(b"abcdef", start), (6, None));
+        assert_eq!(limiter.admit(b"ghij", start), (4, None));
+        assert_eq!(limiter.admit(b"k", start), (0, None));
+        assert_eq!(
+            limiter.admit(b"lmnop", start + Duration::from_millis(999)),
+            (0, None)
+        );
+
+        let (admitted, notice) = limiter.admit(b"qrstuvwx", start + Duration::from_secs(1));
+        assert_eq!(admitted, 8);
+        assert_eq!(notice.as_deref(), Some("\r\n[输出过快，已省略 6 字节]\r\n"));
+        assert_eq!(limiter.take_notice(), None);
+
+        // 新窗口从切换时刻开始计算，已用 8 字节，只剩 2 字节额度。
+        assert_eq!(
+            limiter.admit(b"yz!", start + Duration::from_millis(1500)),
+            (2, None)
+        );
+        assert_eq!(
+            limiter.take_notice().as_deref(),
+            Some("\r\n[输出过快，已省略 1 字节]\r\n")
+        );
+    }
+
+    #[test]
+    fn output_rate_limiter_admits_budget_of_chunks_larger_than_limit() {
+        // 单次读取（最多 8192 字节）超过限额时仍转发剩余额度，而不是整块丢弃。
+        let mut limiter = OutputRateLimiter::new(4096);
+        let start = limiter.window_start;
+        assert_eq!(limiter.admit(&[b'x'; 8192], start), (4096, None));
+        assert_eq!(limiter.admit(&[b'x'; 8192], start), (0, None));
+
+        let (admitted, notice) = limiter.admit(&[b'x'; 8192], start + Duration::from_secs(1));
+        assert_eq!(admitted, 4096);
+        assert_eq!(
+            notice.as_deref(),
+            Some("\r\n[输出过快，已省略 12288 字节]\r\n")
+        );
+    }
+
+    #[test]
+    fn output_rate_limiter_trims_admitted_prefix_to_utf8_boundary() {
+        let mut limiter = OutputRateLimiter::new(4);
+        let start = limiter.window_start;
+        let data = "ab终端".as_bytes();
+        let (admitted, _) = limiter.admit(data, start);
+        assert_eq!(admitted, 2);
+        assert_eq!(std::str::from_utf8(&data[..admitted]).unwrap(), "ab");
+
+        let mut limiter = OutputRateLimiter::new(4);
+        assert_eq!(limiter.admit("a终端".as_bytes(), start).0, 4);
+    }
+}
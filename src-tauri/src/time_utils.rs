@@ -6,7 +6,7 @@ const APPLE_REFERENCE_EPOCH: i64 = 978_307_200;
 
 /// 获取当前时间的 Swift 时间戳。
 pub fn now_swift() -> SwiftDate {
-    unix_to_swift(system_time_to_unix(SystemTime::now()))
+    system_time_to_swift(SystemTime::now())
 }
 
 /// 将系统时间转换为 Swift 时间戳。
@@ -19,6 +19,16 @@ pub fn unix_to_swift(unix_seconds: f64) -> SwiftDate {
     unix_seconds - APPLE_REFERENCE_EPOCH as f64
 }
 
+/// 将 Swift 时间戳（以 2001-01-01 为起点的秒数）转换为 Unix 毫秒。
+pub fn swift_date_to_unix_millis(swift_date: SwiftDate) -> i64 {
+    ((swift_date + APPLE_REFERENCE_EPOCH as f64) * 1000.0).round() as i64
+}
+
+/// 将 Unix 毫秒转换为 Swift 时间戳（以 2001-01-01 为起点的秒数）。
+pub fn unix_millis_to_swift_date(unix_millis: i64) -> SwiftDate {
+    unix_to_swift(unix_millis as f64 / 1000.0)
+}
+
 /// 将系统时间转换为 Unix 秒数。
 pub fn system_time_to_unix_seconds(time: SystemTime) -> f64 {
     system_time_to_unix(time)
@@ -31,3 +41,32 @@ fn system_time_to_unix(time: SystemTime) -> f64 {
         Err(_) => 0.0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swift_date_should_round_trip_through_unix_millis() {
+        // 2001-01-01T00:00:00Z 是 Swift 时间戳的零点。
+        assert_eq!(swift_date_to_unix_millis(0.0), 978_307_200_000);
+        assert_eq!(unix_millis_to_swift_date(978_307_200_000), 0.0);
+
+        // 2024-01-01T00:00:00Z
+        let unix_millis = 1_704_067_200_000;
+        let swift_date = unix_millis_to_swift_date(unix_millis);
+        assert_eq!(swift_date, 725_760_000.0);
+        assert_eq!(swift_date_to_unix_millis(swift_date), unix_millis);
+        assert_eq!(swift_date_to_unix_millis(725_760_000.5), unix_millis + 500);
+    }
+
+    #[test]
+    fn system_time_should_convert_to_swift_date() {
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_704_067_200);
+        assert_eq!(system_time_to_swift(time), 725_760_000.0);
+
+        // 不截断到毫秒，保留文件系统时间戳的原始精度。
+        let time = time + std::time::Duration::from_micros(1_500);
+        assert!((system_time_to_swift(time) - 725_760_000.001_5).abs() < 1e-6);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -8,10 +9,13 @@ use uuid::Uuid;
 
 use crate::git_ops;
 use crate::models::{
-    BranchListItem, WorktreeInitCancelResult, WorktreeInitJobStatus, WorktreeInitProgressPayload,
+    BranchListItem, GitWorktreeListItem, Project, ProjectWorktree, SwiftDate,
+    WorktreeInitCancelResult, WorktreeInitJobStatus, WorktreeInitProgressPayload,
     WorktreeInitRetryRequest, WorktreeInitStartRequest, WorktreeInitStartResult,
-    WorktreeInitStatusQuery, WorktreeInitStep,
+    WorktreeInitStatusQuery, WorktreeInitStep, WorktreeInitVisualStatus,
 };
+use crate::storage;
+use crate::time_utils::unix_millis_to_swift_date;
 use crate::worktree_setup;
 
 pub const WORKTREE_INIT_PROGRESS_EVENT: &str = "worktree-init-progress";
@@ -37,6 +41,9 @@ struct WorktreeInitJob {
     branch: String,
     base_branch: Option<String>,
     create_branch: bool,
+    register_as_project: bool,
+    inherit_config: bool,
+    post_create_script: Option<String>,
     step: WorktreeInitStep,
     message: String,
     error: Option<String>,
@@ -46,7 +53,10 @@ struct WorktreeInitJob {
 }
 
 enum JobRunOutcome {
-    Ready(Option<String>),
+    Ready {
+        warning: Option<String>,
+        inherit_failed: usize,
+    },
     Failed(String),
     Cancelled(String),
 }
@@ -71,6 +81,28 @@ impl WorktreeInitState {
             return Err("分支名不能为空".to_string());
         }
 
+        if let Some(existing) = find_existing_worktree(&project_path, &branch) {
+            log::info!(
+                "分支已在 worktree 检出，直接复用 branch={} path={}",
+                branch,
+                existing.path
+            );
+            return Ok(WorktreeInitStartResult {
+                job_id: String::new(),
+                project_id: request.project_id,
+                project_path,
+                worktree_path: existing.path,
+                branch,
+                base_branch: None,
+                step: WorktreeInitStep::Ready,
+                message: "该分支已有 worktree，已直接复用".to_string(),
+                dry_run: request.dry_run,
+                start_point: None,
+                branch_exists: true,
+                reused: true,
+            });
+        }
+
         let base_branch = if request.create_branch {
             Some(resolve_request_base_branch(
                 &project_path,
@@ -99,6 +131,9 @@ impl WorktreeInitState {
             branch: branch.clone(),
             base_branch: base_branch.clone(),
             create_branch: request.create_branch,
+            register_as_project: request.register_as_project,
+            inherit_config: request.inherit_config,
+            post_create_script: request.post_create_script.clone(),
             step: WorktreeInitStep::Pending,
             message: String::new(),
             error: None,
@@ -107,6 +142,10 @@ impl WorktreeInitState {
             cancel_requested: false,
         };
 
+        if request.dry_run {
+            return build_dry_run_result(&job);
+        }
+
         let (queue_message, should_start) = {
             let mut runtime = self
                 .inner
@@ -146,6 +185,10 @@ impl WorktreeInitState {
             base_branch,
             step: WorktreeInitStep::Pending,
             message: queue_message,
+            dry_run: false,
+            start_point: None,
+            branch_exists: !request.create_branch,
+            reused: false,
         })
     }
 
@@ -188,6 +231,7 @@ impl WorktreeInitState {
                     step: job.step.clone(),
                     message: "已收到取消请求，等待当前步骤结束".to_string(),
                     error: None,
+                    worktree: None,
                 }
             };
             if let Err(error) = app.emit(WORKTREE_INIT_PROGRESS_EVENT, payload) {
@@ -228,6 +272,10 @@ impl WorktreeInitState {
             create_branch: job.create_branch,
             base_branch: job.base_branch.clone(),
             target_path: Some(job.worktree_path.clone()),
+            register_as_project: job.register_as_project,
+            inherit_config: job.inherit_config,
+            post_create_script: job.post_create_script.clone(),
+            dry_run: false,
         };
 
         drop(runtime);
@@ -298,7 +346,10 @@ impl WorktreeInitState {
         };
 
         match self.run_job_flow(&app, &job_id, &job_snapshot) {
-            JobRunOutcome::Ready(warning) => self.finish_ready(&app, &job_id, warning),
+            JobRunOutcome::Ready {
+                warning,
+                inherit_failed,
+            } => self.finish_ready(&app, &job_id, warning, inherit_failed),
             JobRunOutcome::Failed(error) => self.finish_failed(&app, &job_id, error),
             JobRunOutcome::Cancelled(message) => self.finish_cancelled(&app, &job_id, message),
         }
@@ -365,7 +416,7 @@ impl WorktreeInitState {
             start_point.as_deref(),
         ) {
             Ok(result) => result.path,
-            Err(error) => return JobRunOutcome::Failed(error),
+            Err(error) => return JobRunOutcome::Failed(error.message),
         };
 
         if self.is_cancel_requested(job_id) {
@@ -379,11 +430,64 @@ impl WorktreeInitState {
             "执行中：准备工作区环境...",
         );
 
-        let setup_warning = worktree_setup::prepare_worktree_environment(
+        let inherit_files = if job_snapshot.inherit_config {
+            storage::load_app_state(app)
+                .map(|state| state.settings.worktree_inherit_files)
+                .unwrap_or_else(|error| {
+                    log::warn!("读取 worktree 继承文件设置失败: {}", error);
+                    Vec::new()
+                })
+        } else {
+            Vec::new()
+        };
+        let environment = worktree_setup::prepare_worktree_environment(
             &job_snapshot.project_path,
             &created_path,
             &job_snapshot.branch,
+            &inherit_files,
         );
+        let inherit_failed = environment.inherit.failed.len();
+        if inherit_failed > 0 {
+            log::warn!(
+                "worktree 继承文件复制失败，job_id={} path={}: {}",
+                job_id,
+                created_path,
+                environment.inherit.failed.join(", ")
+            );
+        }
+
+        let script = job_snapshot
+            .post_create_script
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        let script_warning = script.and_then(|script| {
+            worktree_setup::run_post_create_script(
+                &job_snapshot.project_path,
+                &created_path,
+                &job_snapshot.branch,
+                script,
+                |line| {
+                    self.emit_running_step(
+                        app,
+                        job_id,
+                        WorktreeInitStep::PreparingEnvironment,
+                        &format!("执行中：{line}"),
+                    )
+                },
+                || self.is_cancel_requested(job_id),
+            )
+            .err()
+        });
+
+        if self.is_cancel_requested(job_id) {
+            return self.rollback_created_worktree(job_snapshot, &created_path);
+        }
+
+        let setup_warning = match (environment.warning, script_warning) {
+            (Some(setup), Some(script)) => Some(format!("{setup}\n{script}")),
+            (setup, script) => setup.or(script),
+        };
 
         if let Some(warning) = setup_warning.as_ref() {
             log::warn!(
@@ -405,7 +509,10 @@ impl WorktreeInitState {
             log::warn!("同步 worktree 列表失败: {}", error);
         }
 
-        JobRunOutcome::Ready(setup_warning)
+        JobRunOutcome::Ready {
+            warning: setup_warning,
+            inherit_failed,
+        }
     }
 
     fn emit_running_step(
@@ -448,17 +555,36 @@ impl WorktreeInitState {
             .unwrap_or(false)
     }
 
-    fn finish_ready(&self, app: &AppHandle, job_id: &str, warning: Option<String>) {
-        self.emit_progress(
+    fn finish_ready(
+        &self,
+        app: &AppHandle,
+        job_id: &str,
+        warning: Option<String>,
+        inherit_failed: usize,
+    ) {
+        let message = if inherit_failed > 0 {
+            format!("创建完成（{inherit_failed} 个继承文件复制失败）")
+        } else if warning.is_some() {
+            "创建完成（环境初始化存在告警）".to_string()
+        } else {
+            "创建完成".to_string()
+        };
+        let worktree = self.snapshot_job(job_id).and_then(|job| {
+            if !job.register_as_project {
+                return None;
+            }
+            register_project_worktree(app, &job, &message, warning.as_deref())
+                .map_err(|error| log::warn!("登记 worktree 到项目缓存失败: {}", error))
+                .ok()
+                .flatten()
+        });
+        self.emit_progress_with_worktree(
             app,
             job_id,
             WorktreeInitStep::Ready,
-            if warning.is_some() {
-                "创建完成（环境初始化存在告警）".to_string()
-            } else {
-                "创建完成".to_string()
-            },
+            message,
             warning,
+            worktree,
         );
         self.finalize_job(app, job_id);
     }
@@ -486,6 +612,18 @@ impl WorktreeInitState {
         step: WorktreeInitStep,
         message: String,
         error: Option<String>,
+    ) {
+        self.emit_progress_with_worktree(app, job_id, step, message, error, None);
+    }
+
+    fn emit_progress_with_worktree(
+        &self,
+        app: &AppHandle,
+        job_id: &str,
+        step: WorktreeInitStep,
+        message: String,
+        error: Option<String>,
+        worktree: Option<ProjectWorktree>,
     ) {
         let payload = {
             let Ok(mut runtime) = self.inner.lock() else {
@@ -510,6 +648,7 @@ impl WorktreeInitState {
                 step,
                 message,
                 error,
+                worktree,
             }
         };
 
@@ -561,6 +700,89 @@ impl WorktreeInitState {
     }
 }
 
+// 将创建完成的 worktree 写入所属项目的缓存条目，返回写入后的条目；项目不存在时返回 None。
+fn register_project_worktree(
+    app: &AppHandle,
+    job: &WorktreeInitJob,
+    message: &str,
+    warning: Option<&str>,
+) -> Result<Option<ProjectWorktree>, String> {
+    let mut projects = storage::load_projects(app)?;
+    let now = unix_millis_to_swift_date(now_millis());
+    let worktree = build_ready_worktree(job, message, warning, now);
+
+    let Some(stored) = upsert_project_worktree(&mut projects, &job.project_id, worktree) else {
+        return Ok(None);
+    };
+    storage::save_projects(app, &projects)?;
+    Ok(Some(stored))
+}
+
+// 根据任务构造创建完成的 worktree 条目，inherit_config 沿用调用方的选择。
+fn build_ready_worktree(
+    job: &WorktreeInitJob,
+    message: &str,
+    warning: Option<&str>,
+    now: SwiftDate,
+) -> ProjectWorktree {
+    ProjectWorktree {
+        id: format!("worktree:{}", job.worktree_path),
+        name: resolve_worktree_name(&job.worktree_path),
+        path: job.worktree_path.clone(),
+        branch: job.branch.clone(),
+        base_branch: job.base_branch.clone(),
+        inherit_config: job.inherit_config,
+        created: now,
+        status: Some(WorktreeInitVisualStatus::Ready),
+        init_step: Some(WorktreeInitStep::Ready),
+        init_message: Some(message.to_string()),
+        init_error: warning.map(|value| value.to_string()),
+        init_job_id: Some(job.job_id.clone()),
+        updated_at: Some(now),
+    }
+}
+
+// 按路径合并 worktree 条目，保留已有的 id/name/created/inherit_config。
+fn upsert_project_worktree(
+    projects: &mut [Project],
+    project_id: &str,
+    worktree: ProjectWorktree,
+) -> Option<ProjectWorktree> {
+    let project = projects
+        .iter_mut()
+        .find(|project| project.id == project_id)?;
+    let key = normalize_path_for_compare(&worktree.path);
+    match project
+        .worktrees
+        .iter_mut()
+        .find(|item| normalize_path_for_compare(&item.path) == key)
+    {
+        Some(existing) => {
+            let merged = ProjectWorktree {
+                id: existing.id.clone(),
+                name: existing.name.clone(),
+                created: existing.created,
+                inherit_config: existing.inherit_config,
+                base_branch: existing.base_branch.clone().or(worktree.base_branch),
+                ..worktree
+            };
+            *existing = merged.clone();
+            Some(merged)
+        }
+        None => {
+            project.worktrees.push(worktree.clone());
+            Some(worktree)
+        }
+    }
+}
+
+fn resolve_worktree_name(path: &str) -> String {
+    Path::new(path.trim_end_matches(['/', '\\']))
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
 fn validate_branch(project_path: &str, branch: &str, create_branch: bool) -> Result<(), String> {
     let branches = git_ops::list_branches(project_path);
 
@@ -587,7 +809,42 @@ fn resolve_create_start_point(job: &WorktreeInitJob) -> Result<Option<String>, S
         return Err("基线分支不可用：未提供基线分支".to_string());
     };
 
-    git_ops::resolve_create_branch_start_point(&job.project_path, base_branch).map(Some)
+    git_ops::resolve_create_branch_start_point(&job.project_path, base_branch)
+        .map(Some)
+        .map_err(String::from)
+}
+
+// 查找已检出目标分支的 worktree（不含主仓库本身），用于幂等复用。
+fn find_existing_worktree(project_path: &str, branch: &str) -> Option<GitWorktreeListItem> {
+    git_ops::list_worktrees(project_path)
+        .map_err(|error| log::warn!("查询已有 worktree 失败: {}", error))
+        .ok()?
+        .into_iter()
+        .find(|item| item.branch == branch)
+}
+
+// 按真实流程执行校验与起点解析，但不创建 worktree，返回供确认的计划。
+fn build_dry_run_result(job: &WorktreeInitJob) -> Result<WorktreeInitStartResult, String> {
+    let branch_exists = git_ops::list_branches(&job.project_path)
+        .iter()
+        .any(|item| item.name == job.branch);
+    validate_branch(&job.project_path, &job.branch, job.create_branch)?;
+    let start_point = resolve_create_start_point(job)?;
+
+    Ok(WorktreeInitStartResult {
+        job_id: String::new(),
+        project_id: job.project_id.clone(),
+        project_path: job.project_path.clone(),
+        worktree_path: job.worktree_path.clone(),
+        branch: job.branch.clone(),
+        base_branch: job.base_branch.clone(),
+        step: WorktreeInitStep::Pending,
+        message: format!("预览：将在 {} 创建 worktree", job.worktree_path),
+        dry_run: true,
+        start_point,
+        branch_exists,
+        reused: false,
+    })
 }
 
 fn resolve_request_base_branch(
@@ -687,9 +944,12 @@ fn now_millis() -> i64 {
 #[cfg(test)]
 mod tests {
     use super::{
-        build_queue_message, choose_default_base_branch, dequeue_project_job, enqueue_project_job,
+        WorktreeInitJob, build_queue_message, build_ready_worktree, choose_default_base_branch,
+        dequeue_project_job, enqueue_project_job, upsert_project_worktree,
+    };
+    use crate::models::{
+        BranchListItem, Project, ProjectWorktree, WorktreeInitStep, WorktreeInitVisualStatus,
     };
-    use crate::models::BranchListItem;
     use std::collections::{HashMap, VecDeque};
 
     #[test]
@@ -765,9 +1025,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_ready_worktree_should_keep_inherit_config_choice() {
+        let job = |inherit_config: bool| WorktreeInitJob {
+            job_id: "job-1".to_string(),
+            project_id: "p1".to_string(),
+            project_path: "/repo".to_string(),
+            project_key: "/repo".to_string(),
+            worktree_path: "/repo-wt/feature-x".to_string(),
+            branch: "feature/x".to_string(),
+            base_branch: Some("main".to_string()),
+            create_branch: true,
+            register_as_project: true,
+            inherit_config,
+            post_create_script: None,
+            step: WorktreeInitStep::Ready,
+            message: String::new(),
+            error: None,
+            updated_at: 0,
+            is_running: false,
+            cancel_requested: false,
+        };
+
+        let inherited = build_ready_worktree(&job(true), "创建完成", None, 1.0);
+        assert!(inherited.inherit_config);
+        assert_eq!(inherited.path, "/repo-wt/feature-x");
+        assert_eq!(inherited.init_job_id.as_deref(), Some("job-1"));
+
+        let standalone = build_ready_worktree(&job(false), "创建完成", Some("告警"), 1.0);
+        assert!(!standalone.inherit_config);
+        assert_eq!(standalone.init_error.as_deref(), Some("告警"));
+    }
+
     #[test]
     fn queue_message_should_reflect_waiting_count() {
         assert_eq!(build_queue_message(0), "排队中（即将开始）");
         assert_eq!(build_queue_message(2), "排队中（前方还有 2 个任务）");
     }
+
+    #[test]
+    fn upsert_project_worktree_should_merge_by_path_and_keep_identity() {
+        let worktree = |path: &str, created: f64| ProjectWorktree {
+            id: format!("worktree:{path}"),
+            name: "new".to_string(),
+            path: path.to_string(),
+            branch: "feature/x".to_string(),
+            base_branch: None,
+            inherit_config: true,
+            created,
+            status: Some(WorktreeInitVisualStatus::Ready),
+            init_step: Some(WorktreeInitStep::Ready),
+            init_message: None,
+            init_error: None,
+            init_job_id: Some("job-1".to_string()),
+            updated_at: Some(created),
+        };
+        let mut existing = worktree("/repo-wt/", 1.0);
+        existing.id = "custom-id".to_string();
+        existing.name = "custom".to_string();
+        existing.status = Some(WorktreeInitVisualStatus::Creating);
+
+        let mut projects = vec![Project {
+            id: "p1".to_string(),
+            name: "repo".to_string(),
+            path: "/repo".to_string(),
+            tags: Vec::new(),
+            scripts: Vec::new(),
+            worktrees: vec![existing],
+            mtime: 0.0,
+            size: 0,
+            checksum: String::new(),
+            git_commits: 0,
+            git_last_commit: 0.0,
+            git_daily: None,
+            created: 0.0,
+            checked: 0.0,
+            terminal_shell: None,
+            terminal_env: HashMap::new(),
+        }];
+
+        let merged = upsert_project_worktree(&mut projects, "p1", worktree("/repo-wt", 5.0))
+            .expect("project exists");
+        assert_eq!(merged.id, "custom-id");
+        assert_eq!(merged.name, "custom");
+        assert_eq!(merged.created, 1.0);
+        assert!(matches!(
+            merged.status,
+            Some(WorktreeInitVisualStatus::Ready)
+        ));
+        assert_eq!(projects[0].worktrees.len(), 1);
+
+        upsert_project_worktree(&mut projects, "p1", worktree("/repo-wt2", 5.0));
+        assert_eq!(projects[0].worktrees.len(), 2);
+        assert!(upsert_project_worktree(&mut projects, "missing", worktree("/x", 5.0)).is_none());
+    }
 }
@@ -1,11 +1,17 @@
+use std::collections::VecDeque;
 use std::fs;
-use std::path::Path;
-use std::process::{Command, Output};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
 
 use serde::Deserialize;
 
 const PROJECT_SETUP_DIR: &str = ".devhaven";
 const SETUP_CONFIG_FILE: &str = "config.json";
+const POST_CREATE_TAIL_LINES: usize = 40;
 
 #[derive(Debug, Deserialize)]
 struct SetupConfig {
@@ -13,33 +19,171 @@ struct SetupConfig {
     setup: Vec<String>,
 }
 
+/// 继承文件复制结果（路径均为相对主仓库的 `/` 分隔路径）。
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InheritCopyReport {
+    pub copied: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl InheritCopyReport {
+    // 仅在存在跳过或失败的文件时生成告警文本（含已复制列表）。
+    fn warning(&self) -> Option<String> {
+        if self.skipped.is_empty() && self.failed.is_empty() {
+            return None;
+        }
+        let mut lines = Vec::new();
+        if !self.failed.is_empty() {
+            lines.push(format!("继承文件复制失败：{}", self.failed.join(", ")));
+        }
+        if !self.skipped.is_empty() {
+            lines.push(format!("已存在，跳过：{}", self.skipped.join(", ")));
+        }
+        if !self.copied.is_empty() {
+            lines.push(format!("已复制继承文件：{}", self.copied.join(", ")));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+/// 环境准备结果：warning 汇总所有告警，inherit 保留继承文件的逐项结果供调用方判断失败。
+#[derive(Debug, Default)]
+pub struct WorktreeEnvironmentReport {
+    pub warning: Option<String>,
+    pub inherit: InheritCopyReport,
+}
+
 /// 为新建 worktree 准备环境。
 ///
 /// 行为对齐 DevHaven：
 /// 1) 尝试复制主仓库 .devhaven 到 worktree（仅当 worktree 不存在该目录时）；
-/// 2) 读取 .devhaven/config.json 的 setup 命令并依次执行；
-/// 3) 任一步失败只返回告警，不阻塞 worktree 创建完成。
+/// 2) 按 inherit_files（支持 `*`/`?` 通配）从主仓库复制文件，已存在的文件跳过；
+/// 3) 读取 .devhaven/config.json 的 setup 命令并依次执行；
+/// 4) 任一步失败只返回告警，不阻塞 worktree 创建完成；继承文件失败项单独返回。
 pub fn prepare_worktree_environment(
     main_repo_path: &str,
     worktree_path: &str,
     workspace_name: &str,
-) -> Option<String> {
+    inherit_files: &[String],
+) -> WorktreeEnvironmentReport {
     let mut warnings: Vec<String> = Vec::new();
 
     if let Err(error) = copy_setup_directory(main_repo_path, worktree_path) {
         warnings.push(format!("复制 .devhaven 目录失败：{}", error));
     }
 
+    let inherit = copy_inherit_files(main_repo_path, worktree_path, inherit_files);
+    if let Some(report) = inherit.warning() {
+        warnings.push(report);
+    }
+
     if let Err(error) = run_setup_commands_if_needed(main_repo_path, worktree_path, workspace_name)
     {
         warnings.push(error);
     }
 
-    if warnings.is_empty() {
-        None
-    } else {
-        Some(warnings.join("\n"))
+    WorktreeEnvironmentReport {
+        warning: if warnings.is_empty() {
+            None
+        } else {
+            Some(warnings.join("\n"))
+        },
+        inherit,
+    }
+}
+
+/// 执行创建后脚本，逐行回调 stdout/stderr 输出。
+///
+/// is_cancelled 返回 true 时终止脚本；非零退出或被取消时返回告警文本，由调用方决定是否阻塞。
+pub fn run_post_create_script(
+    main_repo_path: &str,
+    worktree_path: &str,
+    workspace_name: &str,
+    script: &str,
+    mut on_line: impl FnMut(&str),
+    is_cancelled: impl Fn() -> bool,
+) -> Result<(), String> {
+    let mut child = build_shell_command(main_repo_path, worktree_path, workspace_name, script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("执行创建后脚本失败（{}）：{}", script, error))?;
+
+    let (sender, receiver) = mpsc::channel::<String>();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_line_reader(stdout, sender.clone()));
     }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_line_reader(stderr, sender.clone()));
+    }
+    drop(sender);
+
+    let mut tail: VecDeque<String> = VecDeque::new();
+    loop {
+        if is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("创建后脚本已取消".to_string());
+        }
+        match receiver.recv_timeout(Duration::from_millis(200)) {
+            Ok(line) => {
+                on_line(&line);
+                if tail.len() == POST_CREATE_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child
+        .wait()
+        .map_err(|error| format!("等待创建后脚本结束失败：{}", error))?;
+    if status.success() {
+        return Ok(());
+    }
+
+    let status_text = status
+        .code()
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let output_text = if tail.is_empty() {
+        "命令无输出".to_string()
+    } else {
+        tail.into_iter().collect::<Vec<_>>().join("\n")
+    };
+    Err(format!(
+        "创建后脚本执行失败：\n$ {}\n退出码：{}\n{}",
+        script, status_text, output_text
+    ))
+}
+
+fn spawn_line_reader(
+    source: impl Read + Send + 'static,
+    sender: mpsc::Sender<String>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(source).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    })
 }
 
 fn run_setup_commands_if_needed(
@@ -80,6 +224,109 @@ fn copy_setup_directory(main_repo_path: &str, worktree_path: &str) -> Result<(),
     })
 }
 
+// 复制继承文件，返回逐项的复制、跳过与失败列表；成功复制的文件记录日志。
+fn copy_inherit_files(
+    main_repo_path: &str,
+    worktree_path: &str,
+    patterns: &[String],
+) -> InheritCopyReport {
+    let source_root = Path::new(main_repo_path);
+    let target_root = Path::new(worktree_path);
+    let mut report = InheritCopyReport::default();
+
+    for pattern in patterns {
+        let pattern = pattern.trim().trim_start_matches("./");
+        if pattern.is_empty() {
+            continue;
+        }
+        if Path::new(pattern).is_absolute() || pattern.split('/').any(|segment| segment == "..") {
+            report
+                .failed
+                .push(format!("{pattern}（仅支持仓库内相对路径）"));
+            continue;
+        }
+
+        for relative in expand_inherit_pattern(source_root, pattern) {
+            let display = relative.to_string_lossy().replace('\\', "/");
+            let target = target_root.join(&relative);
+            if target.exists() {
+                report.skipped.push(display);
+                continue;
+            }
+            let result = target
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::copy(source_root.join(&relative), &target));
+            match result {
+                Ok(_) => report.copied.push(display),
+                Err(error) => report.failed.push(format!("{display}（{error}）")),
+            }
+        }
+    }
+
+    if !report.copied.is_empty() {
+        log::info!("worktree 已继承文件: {}", report.copied.join(", "));
+    }
+    report
+}
+
+// 按路径段展开通配模式，返回匹配到的文件（相对主仓库路径）。
+fn expand_inherit_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut candidates = vec![PathBuf::new()];
+
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        let mut next = Vec::new();
+        for candidate in candidates {
+            if !segment.contains(['*', '?']) {
+                next.push(candidate.join(segment));
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(root.join(&candidate)) else {
+                continue;
+            };
+            let mut names: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| matches_wildcard(segment, name))
+                .collect();
+            names.sort();
+            next.extend(names.into_iter().map(|name| candidate.join(name)));
+        }
+        candidates = next;
+    }
+
+    candidates
+        .into_iter()
+        .filter(|relative| !relative.as_os_str().is_empty() && root.join(relative).is_file())
+        .collect()
+}
+
+// 单段通配匹配：`*` 匹配任意字符，`?` 匹配单个字符。
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
 fn copy_dir_recursive(source: &Path, target: &Path) -> Result<(), std::io::Error> {
     fs::create_dir_all(target)?;
 
@@ -180,6 +427,17 @@ fn run_shell_command(
     workspace_name: &str,
     command: &str,
 ) -> Result<Output, String> {
+    build_shell_command(main_repo_path, worktree_path, workspace_name, command)
+        .output()
+        .map_err(|error| format!("执行 setup 命令失败（{}）：{}", command, error))
+}
+
+fn build_shell_command(
+    main_repo_path: &str,
+    worktree_path: &str,
+    workspace_name: &str,
+    command: &str,
+) -> Command {
     let (shell, mut args) = resolve_shell();
     args.push(command.to_string());
 
@@ -189,13 +447,10 @@ fn run_shell_command(
 
     process.env("DEVHAVEN_WORKSPACE_NAME", workspace_name);
     process.env("DEVHAVEN_ROOT_PATH", main_repo_path);
-
     process
-        .output()
-        .map_err(|error| format!("执行 setup 命令失败（{}）：{}", command, error))
 }
 
-fn resolve_shell() -> (String, Vec<String>) {
+pub(crate) fn resolve_shell() -> (String, Vec<String>) {
     #[cfg(target_os = "windows")]
     {
         let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
@@ -228,3 +483,75 @@ fn summarize_command_output(output: &Output) -> String {
         format!("{}\n...(输出已截断)", truncated)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_inherit_files, matches_wildcard};
+    use std::fs;
+
+    #[test]
+    fn matches_wildcard_supports_star_and_question_mark() {
+        assert!(matches_wildcard("*", ".env"));
+        assert!(matches_wildcard(".env*", ".env"));
+        assert!(matches_wildcard(".env*", ".env.local"));
+        assert!(matches_wildcard("*.local.json", "settings.local.json"));
+        assert!(matches_wildcard("config.?", "config.a"));
+        assert!(matches_wildcard("a*b*c", "a-x-b-y-c"));
+
+        assert!(!matches_wildcard(".env*", "env"));
+        assert!(!matches_wildcard("*.local.json", "settings.json"));
+        assert!(!matches_wildcard("config.?", "config.ab"));
+        assert!(!matches_wildcard("config.?", "config."));
+        assert!(!matches_wildcard("a*b*c", "a-x-b-y"));
+    }
+
+    #[test]
+    fn copy_inherit_files_reports_copied_skipped_and_failed() {
+        let root = std::env::temp_dir().join(format!("devhaven_test_{}", uuid::Uuid::new_v4()));
+        let main_repo = root.join("main");
+        let worktree = root.join("worktree");
+        fs::create_dir_all(main_repo.join("config")).expect("create main repo");
+        fs::create_dir_all(&worktree).expect("create worktree");
+        fs::write(main_repo.join(".env"), "A=1").expect("write .env");
+        fs::write(main_repo.join(".env.local"), "B=2").expect("write .env.local");
+        fs::write(main_repo.join("config/app.local.json"), "{}").expect("write local json");
+        fs::write(worktree.join(".env"), "A=0").expect("write existing .env");
+
+        let patterns = vec![
+            ".env*".to_string(),
+            "./config/*.local.json".to_string(),
+            "../outside".to_string(),
+        ];
+        let report = copy_inherit_files(
+            main_repo.to_str().unwrap(),
+            worktree.to_str().unwrap(),
+            &patterns,
+        );
+
+        assert_eq!(report.copied, vec![".env.local", "config/app.local.json"]);
+        assert_eq!(report.skipped, vec![".env"]);
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.failed[0].starts_with("../outside"));
+        assert_eq!(
+            fs::read_to_string(worktree.join(".env")).unwrap(),
+            "A=0",
+            "已存在的文件不应被覆盖"
+        );
+
+        // 目标路径的父级被同名文件占用，复制必然失败。
+        fs::write(worktree.join("blocked"), "file").expect("write blocker");
+        fs::create_dir_all(main_repo.join("blocked")).expect("create blocked dir");
+        fs::write(main_repo.join("blocked/inner.txt"), "x").expect("write inner");
+        let report = copy_inherit_files(
+            main_repo.to_str().unwrap(),
+            worktree.to_str().unwrap(),
+            &["blocked/*.txt".to_string()],
+        );
+        assert!(report.copied.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.failed[0].starts_with("blocked/inner.txt"));
+        assert!(report.warning().unwrap().contains("继承文件复制失败"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
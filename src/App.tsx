@@ -274,6 +274,7 @@ function AppLayout() {
     toggleTagHidden,
     setTagColor,
     addTagToProject,
+    assignTagToProjects,
     removeTagFromProject,
     addProjectScript,
     updateProjectScript,
@@ -286,6 +287,7 @@ function AppLayout() {
     updateSettings,
     moveProjectToRecycleBin,
     restoreProjectFromRecycleBin,
+    purgeProjectsFromRecycleBin,
   } = useDevHavenContext();
 
   const [searchText, setSearchText] = useState("");
@@ -584,10 +586,10 @@ function AppLayout() {
 
   const handleAssignTagToProjects = useCallback(
     async (tag: string, projectIds: string[]) => {
-      await Promise.all(projectIds.map((projectId) => addTagToProject(projectId, tag)));
+      await assignTagToProjects(projectIds, tag, "add");
       setSelectedTags(new Set());
     },
-    [addTagToProject],
+    [assignTagToProjects],
   );
 
   const handleOpenTagEditor = useCallback((tag?: TagData) => {
@@ -608,7 +610,7 @@ function AppLayout() {
   const handleMoveProjectToRecycleBin = useCallback(
     async (project: Project) => {
       try {
-        await moveProjectToRecycleBin(project.path);
+        await moveProjectToRecycleBin(project.id);
         showToast("已移入回收站");
         setSelectedProjects((prev) => {
           if (!prev.has(project.id)) {
@@ -648,6 +650,32 @@ function AppLayout() {
     [restoreProjectFromRecycleBin, showToast],
   );
 
+  const handlePurgeProjectsFromRecycleBin = useCallback(
+    async (paths: string[]) => {
+      if (paths.length === 0) {
+        return;
+      }
+      const label = paths.length === 1 ? paths[0] : `${paths.length} 个项目`;
+      if (!window.confirm(`确定从回收站彻底移除？\n\n${label}`)) {
+        return;
+      }
+      const moveToTrash = window.confirm("是否同时将项目目录移到系统废纸篓？\n\n取消则仅从 DevHaven 中移除记录。");
+      try {
+        const result = await purgeProjectsFromRecycleBin(paths, moveToTrash);
+        if (result.errors.length > 0) {
+          console.error("移到废纸篓失败。", result.errors);
+          showToast(`${result.errors.length} 个目录移到废纸篓失败`, "error");
+          return;
+        }
+        showToast("已从回收站移除");
+      } catch (error) {
+        console.error("回收站移除失败。", error);
+        showToast("回收站移除失败，请稍后重试", "error");
+      }
+    },
+    [purgeProjectsFromRecycleBin, showToast],
+  );
+
   useEffect(() => {
     return () => {
       if (toastTimerRef.current) {
@@ -973,6 +1001,23 @@ function AppLayout() {
               baseBranch: payload.baseBranch,
             });
 
+            if (created.reused) {
+              // 分支已有 worktree 时后端直接复用，不会产生进度事件。
+              worktreeInitAutoOpenPendingByProjectBranchRef.current.delete(key);
+              const reusedWorktree = buildReadyWorktree(
+                created.worktreePath,
+                created.branch,
+                jsDateToSwiftDate(new Date()),
+              );
+              await addProjectWorktree(sourceProject.id, reusedWorktree);
+              setWorktreeDialogProjectId(null);
+              showToast(created.message || "该分支已有 worktree，已直接复用");
+              if (payload.autoOpen) {
+                openTerminalWorkspace(buildWorktreeVirtualProject(sourceProject, reusedWorktree));
+              }
+              return { mode: "open-existing" };
+            }
+
             // 任务入队后立即关闭弹窗；后续成功/失败提示由 worktree-init-progress 事件驱动。
             setWorktreeDialogProjectId(null);
 
@@ -1995,6 +2040,7 @@ function AppLayout() {
           items={recycleBinItems}
           onClose={() => setShowRecycleBin(false)}
           onRestore={handleRestoreProjectFromRecycleBin}
+          onPurge={(paths) => void handlePurgeProjectsFromRecycleBin(paths)}
         />
       ) : null}
 
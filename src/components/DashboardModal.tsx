@@ -35,6 +35,11 @@ export default function DashboardModal({ projects, tags, heatmapStore, onClose,
     [projects, range.days],
   );
 
+  const projectNames = useMemo(
+    () => Object.fromEntries(projects.map((project) => [project.id, project.name])),
+    [projects],
+  );
+
   const gitProjects = useMemo(() => projects.filter((project) => project.git_commits > 0).length, [projects]);
 
   const lastUpdatedLabel = useMemo(() => {
@@ -116,6 +121,7 @@ export default function DashboardModal({ projects, tags, heatmapStore, onClose,
               config={HEATMAP_CONFIG.dashboard}
               title="开发热力图"
               subtitle={`${range.label} · 日均 ${stats.averageCommitsPerDay.toFixed(1)} 次提交`}
+              projectNames={projectNames}
               className="heatmap-dashboard"
             />
           ) : (
@@ -10,6 +10,8 @@ export type HeatmapProps = {
   subtitle?: string;
   selectedDateKey?: string | null;
   onSelectDate?: (item: HeatmapData | null) => void;
+  /** 项目 ID 到名称的映射，提供时提示中列出当天提交最多的项目。 */
+  projectNames?: Record<string, string>;
   className?: string;
 };
 
@@ -21,6 +23,7 @@ export default function Heatmap({
   subtitle,
   selectedDateKey,
   onSelectDate,
+  projectNames,
   className,
 }: HeatmapProps) {
   const containerRef = useRef<HTMLDivElement | null>(null);
@@ -135,7 +138,7 @@ export default function Heatmap({
                   }
                   const dateKey = formatDateKey(day.date);
                   const isSelected = selectedDateKey === dateKey;
-                  const label = formatTooltip(day);
+                  const label = formatTooltip(day, projectNames);
                   return (
                     <button
                       key={`cell-${dateKey}`}
@@ -270,7 +273,9 @@ function buildMonthLabels(weeks: WeekCell[][]) {
   });
 }
 
-function formatTooltip(item: HeatmapData) {
+const TOOLTIP_PROJECT_LIMIT = 3;
+
+function formatTooltip(item: HeatmapData, projectNames?: Record<string, string>) {
   const dateLabel = item.date.toLocaleDateString("zh-CN", {
     year: "numeric",
     month: "2-digit",
@@ -278,5 +283,22 @@ function formatTooltip(item: HeatmapData) {
   });
   const commitLabel = item.commitCount === 0 ? "无提交" : `${item.commitCount} 次提交`;
   const projectLabel = item.projectIds.length > 0 ? ` · ${item.projectIds.length} 个项目` : "";
-  return `${dateLabel}：${commitLabel}${projectLabel}`;
+  const breakdown = projectNames ? formatProjectBreakdown(item.projectCounts, projectNames) : "";
+  return `${dateLabel}：${commitLabel}${projectLabel}${breakdown}`;
+}
+
+function formatProjectBreakdown(projectCounts: Record<string, number>, projectNames: Record<string, string>) {
+  const entries = Object.entries(projectCounts)
+    .filter(([, count]) => count > 0)
+    .sort((a, b) => b[1] - a[1]);
+  if (entries.length === 0) {
+    return "";
+  }
+  const parts = entries
+    .slice(0, TOOLTIP_PROJECT_LIMIT)
+    .map(([projectId, count]) => `${projectNames[projectId] ?? "未知项目"} ${count}`);
+  if (entries.length > TOOLTIP_PROJECT_LIMIT) {
+    parts.push(`等 ${entries.length} 个`);
+  }
+  return `（${parts.join("、")}）`;
 }
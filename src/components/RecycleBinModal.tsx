@@ -11,10 +11,11 @@ export type RecycleBinModalProps = {
   items: RecycleBinItem[];
   onClose: () => void;
   onRestore: (path: string) => void;
+  onPurge?: (paths: string[]) => void;
 };
 
-/** 回收站弹窗，展示隐藏项目并支持恢复。 */
-export default function RecycleBinModal({ items, onClose, onRestore }: RecycleBinModalProps) {
+/** 回收站弹窗，展示隐藏项目并支持恢复与彻底移除。 */
+export default function RecycleBinModal({ items, onClose, onRestore, onPurge }: RecycleBinModalProps) {
   return (
     <div className="modal-overlay" role="dialog" aria-modal>
       <div className="modal-panel w-[min(820px,90vw)] max-h-[85vh] overflow-hidden">
@@ -24,6 +25,11 @@ export default function RecycleBinModal({ items, onClose, onRestore }: RecycleBi
             <div className="text-fs-caption text-secondary-text">共 {items.length} 个项目</div>
           </div>
           <div className="inline-flex items-center gap-2">
+            {onPurge && items.length > 0 ? (
+              <button className="btn btn-outline" onClick={() => onPurge(items.map((item) => item.path))}>
+                清空回收站
+              </button>
+            ) : null}
             <button className="icon-btn" onClick={onClose} aria-label="关闭">
               <IconX size={14} />
             </button>
@@ -34,7 +40,7 @@ export default function RecycleBinModal({ items, onClose, onRestore }: RecycleBi
           <div className="px-3 py-6 text-center text-fs-caption text-secondary-text">回收站为空</div>
         ) : (
           <div className="flex flex-col overflow-hidden rounded-[10px] border border-border bg-card-bg">
-            <div className="grid grid-cols-[minmax(140px,1.2fr)_minmax(220px,2.4fr)_90px_90px] items-center gap-3 border-t-0 border-border bg-secondary-background px-3 py-2.5 text-[13px] font-semibold text-secondary-text">
+            <div className="grid grid-cols-[minmax(140px,1.2fr)_minmax(220px,2.4fr)_90px_150px] items-center gap-3 border-t-0 border-border bg-secondary-background px-3 py-2.5 text-[13px] font-semibold text-secondary-text">
               <span>名称</span>
               <span>路径</span>
               <span>状态</span>
@@ -44,7 +50,7 @@ export default function RecycleBinModal({ items, onClose, onRestore }: RecycleBi
               const displayPath = formatPathWithTilde(item.path);
               return (
                 <div
-                  className="grid grid-cols-[minmax(140px,1.2fr)_minmax(220px,2.4fr)_90px_90px] items-center gap-3 border-t border-border px-3 py-2.5 text-[13px]"
+                  className="grid grid-cols-[minmax(140px,1.2fr)_minmax(220px,2.4fr)_90px_150px] items-center gap-3 border-t border-border px-3 py-2.5 text-[13px]"
                   key={item.path}
                 >
                   <span className="truncate" title={item.name}>
@@ -56,9 +62,16 @@ export default function RecycleBinModal({ items, onClose, onRestore }: RecycleBi
                   <span className={item.missing ? "text-error" : "text-secondary-text"}>
                     {item.missing ? "已丢失" : "可恢复"}
                   </span>
-                  <button className="btn btn-outline" onClick={() => onRestore(item.path)}>
-                    恢复
-                  </button>
+                  <span className="inline-flex gap-2">
+                    <button className="btn btn-outline" onClick={() => onRestore(item.path)}>
+                      恢复
+                    </button>
+                    {onPurge ? (
+                      <button className="btn btn-outline" onClick={() => onPurge([item.path])}>
+                        移除
+                      </button>
+                    ) : null}
+                  </span>
                 </div>
               );
             })}
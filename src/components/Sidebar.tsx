@@ -68,6 +68,11 @@ export default function Sidebar({
   codexSessionsError,
   onOpenCodexSession,
 }: SidebarProps) {
+  const projectNames = useMemo(
+    () => Object.fromEntries(projects.map((project) => [project.id, project.name])),
+    [projects],
+  );
+
   const directoryCounts = useMemo(() => {
     const counts = new Map<string, number>();
     for (const directory of appState.directories) {
@@ -197,6 +202,7 @@ export default function Sidebar({
               config={HEATMAP_CONFIG.sidebar}
               selectedDateKey={heatmapSelectedDateKey}
               onSelectDate={onSelectHeatmapDate}
+              projectNames={projectNames}
               className="heatmap-sidebar"
             />
           ) : (
@@ -3,6 +3,7 @@ import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import type { BranchListItem } from "../../models/branch";
 import type { GitChangedFile, GitRepoStatus } from "../../models/gitManagement";
 import { listBranches } from "../../services/git";
+import { listenProjectChanged, unwatchProject, watchProject } from "../../services/projectWatcher";
 import {
   gitCheckoutBranch,
   gitCommit,
@@ -10,6 +11,7 @@ import {
   gitGetStatus,
   gitStageFiles,
   gitUnstageFiles,
+  listenBranchCheckedOut,
 } from "../../services/gitManagement";
 import { IconRefresh, IconX } from "../Icons";
 
@@ -53,6 +55,9 @@ function formatGitError(err: unknown): string {
   if (typeof err === "string") {
     return err;
   }
+  if (err && typeof err === "object" && typeof (err as { message?: unknown }).message === "string") {
+    return (err as { message: string }).message;
+  }
   try {
     return JSON.stringify(err);
   } catch {
@@ -91,7 +96,7 @@ export default function TerminalGitPanel({
   const effectiveBranch = status?.branch ?? "";
   const canCommit = Boolean(status && status.staged.length > 0 && commitMessage.trim().length > 0);
 
-  const refresh = useCallback(async (options?: { includeBranches?: boolean }) => {
+  const refresh = useCallback(async (options?: { includeBranches?: boolean; force?: boolean }) => {
     if (!projectPath) {
       return;
     }
@@ -105,7 +110,7 @@ export default function TerminalGitPanel({
     setLoading(true);
     setError(null);
     try {
-      const nextStatus = await gitGetStatus(projectPath);
+      const nextStatus = await gitGetStatus(projectPath, undefined, options?.force);
       const nextBranches = options?.includeBranches ? await listBranches(projectPath) : null;
       if (refreshIdRef.current !== refreshId) {
         return;
@@ -166,12 +171,45 @@ export default function TerminalGitPanel({
     void refresh({ includeBranches: true });
   }, [refresh]);
 
+  const refreshRef = useRef(refresh);
+  refreshRef.current = refresh;
+
   useEffect(() => {
+    if (!projectPath) {
+      return;
+    }
+    // 文件变更由后端监听推送；保留低频轮询兜底（如监听失败或外部 git 操作未触发事件）。
+    let disposed = false;
+    const unlisteners: Array<() => void> = [];
+    const track = (fn: () => void) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisteners.push(fn);
+      }
+    };
+    void watchProject(projectPath).catch((err) => console.warn("监听项目目录失败。", err));
+    void listenProjectChanged((event) => {
+      if (event.payload.path === projectPath) {
+        void refreshRef.current();
+      }
+    }).then(track);
+    // 切换分支后分支列表与状态都会变化，收到事件立即整体刷新。
+    void listenBranchCheckedOut((event) => {
+      if (event.payload.path === projectPath) {
+        void refreshRef.current({ includeBranches: true });
+      }
+    }).then(track);
     const timer = window.setInterval(() => {
-      void refresh();
-    }, 2500);
-    return () => window.clearInterval(timer);
-  }, [refresh]);
+      void refreshRef.current();
+    }, 15000);
+    return () => {
+      disposed = true;
+      unlisteners.forEach((fn) => fn());
+      window.clearInterval(timer);
+      void unwatchProject(projectPath).catch(() => undefined);
+    };
+  }, [projectPath]);
 
   const runAction = useCallback(
     async (action: () => Promise<void>) => {
@@ -182,7 +220,7 @@ export default function TerminalGitPanel({
       setError(null);
       try {
         await action();
-        await refresh();
+        await refresh({ force: true });
       } catch (err) {
         setError(formatGitError(err));
       } finally {
@@ -304,7 +342,7 @@ export default function TerminalGitPanel({
             aria-label="刷新 Git 状态"
             title="刷新"
             disabled={loading || actionBusy}
-            onClick={() => void refresh({ includeBranches: true })}
+            onClick={() => void refresh({ includeBranches: true, force: true })}
           >
             <IconRefresh size={14} />
           </button>
@@ -43,6 +43,38 @@ export type CodexMonitorSnapshot = {
   sessions: CodexMonitorSession[];
   isCodexRunning: boolean;
   updatedAt: number;
+  /** 按模型与推理强度聚合的会话数量（统计筛选前的全部会话）。 */
+  modelUsage?: CodexModelUsage[];
+};
+
+export type CodexModelUsage = {
+  model: string | null;
+  effort: string | null;
+  count: number;
+};
+
+/** 快照筛选条件，按模型/推理强度精确匹配（忽略大小写），为空表示不限。 */
+export type CodexSessionFilter = {
+  model?: string | null;
+  effort?: string | null;
+};
+
+/** 打开 Codex 会话工作目录的结果；匹配到项目时包含项目 ID 与命中的项目/worktree 路径。 */
+export type CodexSessionOpenResult = {
+  sessionId: string;
+  cwd: string;
+  projectId: string | null;
+  projectPath: string | null;
+};
+
+/** 实时跟随会话时推送的一行记录摘要（`codex-session-line` 事件）。 */
+export type CodexSessionLine = {
+  sessionId: string;
+  timestamp: number;
+  entryType: string;
+  itemType?: string | null;
+  role?: string | null;
+  preview?: string | null;
 };
 
 export type CodexSessionView = CodexMonitorSession & {
@@ -1,9 +1,59 @@
+import type { GitIdentity } from "./types";
+
 export type GitFileStatus = "added" | "modified" | "deleted" | "renamed" | "copied" | "untracked";
 
+/** 未跟踪文件粒度：no 不列出，normal 折叠目录（路径以 `/` 结尾），all 逐个列出。 */
+export type GitUntrackedMode = "no" | "normal" | "all";
+
+/** 重命名检测选项；关闭后重命名以删除 + 新增展示，阈值为 1-100 的相似度百分比。 */
+export type GitRenameDetection = {
+  detectRenames?: boolean;
+  renameThreshold?: number | null;
+};
+
 export type GitChangedFile = {
   path: string;
   oldPath?: string | null;
   status: GitFileStatus;
+  /** 子模块状态（逗号分隔：new-commits/modified-content/untracked-content），普通文件为空。 */
+  submodule?: string | null;
+};
+
+/** 仓库健康检查结果：index.lock 残留与 fsck 报告的问题。 */
+export type GitRepoHealth = {
+  hasIndexLock: boolean;
+  indexLockPath?: string | null;
+  fsckErrors: string[];
+};
+
+/** 单仓库提交统计；空仓库各项为 0，lastCommitTs 为 Unix 毫秒时间戳。 */
+export type GitRepoStats = {
+  commitCount: number;
+  lastCommitTs: number;
+  contributorCount: number;
+};
+
+/** 仓库贡献者摘要，来自 `git shortlog -sne`。 */
+export type GitContributor = {
+  name: string;
+  email: string;
+  commitCount: number;
+};
+
+/** Git 配置项的来源层级。 */
+export type GitConfigScope = "system" | "global" | "local" | "worktree";
+
+/** 仓库提交时实际生效的身份；未配置的字段为空字符串，对应 scope 为 null。 */
+export type GitRepoIdentity = {
+  identity: GitIdentity;
+  nameScope: GitConfigScope | null;
+  emailScope: GitConfigScope | null;
+};
+
+/** HEAD 相对于与基准分支分叉点（merge-base）的变更文件。 */
+export type GitBaseDiff = {
+  mergeBase: string;
+  files: GitChangedFile[];
 };
 
 export type GitRepoStatus = {
@@ -21,4 +71,126 @@ export type GitDiffContents = {
   modified: string;
   originalTruncated?: boolean;
   modifiedTruncated?: boolean;
+  /** 工作区文件是否包含冲突标记。 */
+  conflict?: boolean;
+  conflictRegions?: GitConflictRegion[];
+  /** 两侧内容仅存在行尾（CRLF/LF）差异。 */
+  eolOnly?: boolean;
+};
+
+/** 文件对比的归一化选项。 */
+export type GitDiffOptions = {
+  ignoreEol?: boolean;
+  ignoreTrailingWhitespace?: boolean;
+};
+
+/** 冲突文件的三方内容，索引中缺失的一方为 null（如 add/add 冲突没有 base）。 */
+export type GitConflictContents = {
+  base?: string | null;
+  ours?: string | null;
+  theirs?: string | null;
+  baseTruncated?: boolean;
+  oursTruncated?: boolean;
+  theirsTruncated?: boolean;
+};
+
+/** 冲突区块，行号从 1 开始并指向各标记行。 */
+export type GitConflictRegion = {
+  startLine: number;
+  baseLine?: number | null;
+  separatorLine: number;
+  endLine: number;
+};
+
+export type GitDiffStatEntry = {
+  relativePath: string;
+  oldRelativePath?: string | null;
+  additions: number;
+  deletions: number;
+  binary: boolean;
+};
+
+export type GitHeadInfo = {
+  commit: string;
+  shortCommit: string;
+  branch?: string | null;
+  detached: boolean;
+  tag?: string | null;
+};
+
+/** 仓库中进行到一半的 Git 操作。 */
+export type GitOperation = "none" | "rebase" | "merge" | "cherry-pick" | "revert" | "bisect";
+
+/** 仓库特殊状态：进行中的操作以及 HEAD 是否游离。 */
+export type GitOperationState = {
+  operation: GitOperation;
+  detached: boolean;
+};
+
+/** `branch-checked-out` 事件载荷：切换分支后的分支名与 HEAD 提交。 */
+export type GitBranchCheckedOutPayload = {
+  path: string;
+  branch: string;
+  commit: string;
+  shortCommit: string;
+};
+
+/** 当前分支相对上游的领先/落后数；未设置上游时 hasUpstream 为 false。 */
+export type GitAheadBehind = {
+  ahead: number;
+  behind: number;
+  hasUpstream: boolean;
+  upstream?: string | null;
+};
+
+/** `git clean` 结果：performed 为 false 时是预览，files 为将被删除的路径（目录以 `/` 结尾）。 */
+export type GitCleanResult = {
+  performed: boolean;
+  files: string[];
+};
+
+/** 远程仓库配置；未单独配置 push 地址时与 fetchUrl 相同。 */
+export type GitRemote = {
+  name: string;
+  fetchUrl: string;
+  pushUrl: string;
+};
+
+export type GitTagItem = {
+  name: string;
+  commit: string;
+  message?: string | null;
+  isAnnotated: boolean;
+};
+
+export type GitResetMode = "soft" | "mixed" | "hard";
+
+export type GitResetResult = {
+  /** hard 模式未确认时为 false，仅返回将被丢弃的文件。 */
+  performed: boolean;
+  commit: string;
+  discardedFiles: string[];
+};
+
+export type GitErrorKind =
+  | "not-a-repo"
+  | "branch-not-found"
+  | "conflict"
+  | "dirty-worktree"
+  | "remote-error"
+  | "io"
+  | "raw";
+
+/** git_* 命令失败时返回的结构化错误，kind 用于按类别处理，message 为可读文案。 */
+export type GitError = {
+  kind: GitErrorKind;
+  message: string;
+};
+
+export type CommitValidationCode = "empty-message" | "subject-too-long" | "missing-body" | "commit-failed";
+
+/** git_commit 失败时返回的结构化错误。 */
+export type CommitValidationError = {
+  code: CommitValidationCode;
+  message: string;
 };
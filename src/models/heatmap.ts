@@ -2,6 +2,8 @@ export type HeatmapCacheEntry = {
   dateString: string;
   commitCount: number;
   projectIds: string[];
+  /** 当天各项目的提交数（projectId -> count），旧缓存可能缺失。 */
+  projectCounts?: Record<string, number>;
 };
 
 export type HeatmapCacheFile = {
@@ -10,12 +12,15 @@ export type HeatmapCacheFile = {
   dailyActivity: Record<string, HeatmapCacheEntry>;
   projectCount: number;
   gitDailySignature: string;
+  /** 上次统计每日提交时的仓库 HEAD 签名（含身份签名），未变化时跳过重新统计。 */
+  gitHeadSignature?: string;
 };
 
 export type HeatmapData = {
   date: Date;
   commitCount: number;
   projectIds: string[];
+  projectCounts: Record<string, number>;
   intensity: number;
 };
 
@@ -56,4 +61,5 @@ export const EMPTY_HEATMAP_CACHE: HeatmapCacheFile = {
   dailyActivity: {},
   projectCount: 0,
   gitDailySignature: "",
+  gitHeadSignature: "",
 };
@@ -18,6 +18,24 @@ export type OpenToolSettings = {
   arguments: string[];
 };
 
+/** 外部工具配置校验失败原因。 */
+export type OpenToolFailureReason = "empty" | "not-found" | "not-a-file" | "not-executable";
+
+/** 外部工具配置校验结果。 */
+export type OpenToolValidation = {
+  ok: boolean;
+  resolvedPath?: string | null;
+  reason?: OpenToolFailureReason | null;
+  message?: string | null;
+};
+
+/** 本机探测到的编辑器。 */
+export type DetectedEditor = {
+  name: string;
+  commandPath: string;
+  defaultArgs: string[];
+};
+
 export type ProjectScript = {
   id: string;
   name: string;
@@ -65,6 +83,22 @@ export type AppSettings = {
   showMonitorWindow: boolean;
   gitIdentities: GitIdentity[];
   projectListViewMode: ProjectListViewMode;
+  /** 提交信息模板；模板包含正文时提交需填写正文。 */
+  commitMessageTemplate?: string | null;
+  /** 提交标题最大长度，0 表示不限制。 */
+  commitSubjectMaxLen?: number;
+  /** 新建 worktree 时从主仓库复制的文件（支持 `*`/`?` 通配）。 */
+  worktreeInheritFiles?: string[];
+  /** 单个终端 pane 每秒最多转发的输出字节数，0 表示不限制。 */
+  terminalMaxOutputBytesPerSec?: number;
+  /** Git 可执行文件路径，为空时自动探测（优先 Homebrew）。 */
+  gitPath?: string | null;
+  /** 退出应用时结束仍在运行的项目脚本进程。 */
+  stopScriptsOnExit?: boolean;
+  /** Codex 会话目录，为空时使用 `$CODEX_HOME/sessions` 或 `~/.codex/sessions`。 */
+  codexSessionsDir?: string | null;
+  /** Codex 监控首次推送事件时，为仍在进行中的会话补发当前状态事件。 */
+  codexMonitorBootstrapEvents?: boolean;
 };
 
 export type AppStateFile = {
@@ -75,6 +109,20 @@ export type AppStateFile = {
   settings: AppSettings;
 };
 
+/** 应用窗口状态。 */
+export type AppWindowInfo = {
+  label: string;
+  visible: boolean;
+  focused: boolean;
+  minimized: boolean;
+};
+
+/** 数据目录备份/恢复结果。 */
+export type DataBackupResult = {
+  path: string;
+  files: string[];
+};
+
 export type Project = {
   id: string;
   name: string;
@@ -90,6 +138,62 @@ export type Project = {
   git_daily?: string | null;
   created: SwiftDate;
   checked: SwiftDate;
+  /** 项目终端使用的 shell，为空时使用用户默认 shell。 */
+  terminal_shell?: string | null;
+  /** 项目终端额外注入的环境变量。 */
+  terminal_env?: Record<string, string>;
+};
+
+/** 项目缓存清理结果。 */
+export type ProjectPruneResult = {
+  /** 路径已不存在而被移除的项目。 */
+  removed: Project[];
+  /** id 重复而被移除的项目（保留首个）。 */
+  duplicates: Project[];
+  /** 位于回收站中的项目（缓存条目保留，可恢复）。 */
+  recycled: Project[];
+};
+
+/** 标签批量操作结果：最新的标签列表与项目缓存，以及引用被更新的项目数。 */
+export type TagUpdateResult = {
+  tags: TagData[];
+  projects: Project[];
+  affectedProjects: number;
+};
+
+/** 后端项目列表排序字段，取值与项目字段名一致。 */
+export type ProjectSortKey = "name" | "mtime" | "git_last_commit" | "size" | "created";
+
+/** 模糊搜索命中区间（字符下标，左闭右开），用于高亮。 */
+export type MatchSpan = {
+  start: number;
+  end: number;
+};
+
+/** 项目模糊搜索结果；field 为空表示未输入搜索词，仅经过标签筛选。 */
+export type ProjectSearchResult = {
+  projectId: string;
+  score: number;
+  field: "name" | "path" | "tag" | null;
+  tag: string | null;
+  spans: MatchSpan[];
+};
+
+/** 项目分页结果；matches 仅在带搜索词时返回当前页的命中信息。 */
+export type ProjectPage = {
+  projects: Project[];
+  total: number;
+  matches: ProjectSearchResult[];
+};
+
+/** 回收站操作结果：操作后的回收站与项目缓存，以及被彻底移除的路径和移到废纸篓失败的信息。 */
+export type RecycleBinResult = {
+  recycleBin: string[];
+  /** 操作后的扫描目录列表（恢复项目时可能新增）。 */
+  directories: string[];
+  projects: Project[];
+  purged: string[];
+  errors: string[];
 };
 
 const APPLE_REFERENCE_EPOCH_MS = Date.UTC(2001, 0, 1, 0, 0, 0, 0);
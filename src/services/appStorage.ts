@@ -1,6 +1,17 @@
 import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
 
-import type { AppStateFile, Project } from "../models/types";
+import type {
+  AppStateFile,
+  DataBackupResult,
+  Project,
+  ProjectPage,
+  ProjectPruneResult,
+  ProjectSearchResult,
+  ProjectSortKey,
+  RecycleBinResult,
+  TagUpdateResult,
+} from "../models/types";
 
 /** 读取应用状态文件。 */
 export async function loadAppState(): Promise<AppStateFile> {
@@ -17,11 +28,90 @@ export async function loadProjects(): Promise<Project[]> {
   return invoke<Project[]>("load_projects");
 }
 
+/** 读取项目缓存并由后端排序，默认按修改时间倒序。 */
+export async function listProjectsSorted(sortBy?: ProjectSortKey, descending?: boolean): Promise<Project[]> {
+  return invoke<Project[]>("list_projects_sorted", { sortBy, descending });
+}
+
+/** 重新统计单个项目的 Git 提交信息并写回缓存，返回更新后的项目。 */
+export async function refreshProject(projectId: string): Promise<Project> {
+  return invoke<Project>("refresh_project", { projectId });
+}
+
+/** 分页读取项目：后端先模糊过滤与排序，再截取 offset/limit，并返回过滤后的总数。 */
+export async function listProjectsPage(options: {
+  offset: number;
+  limit: number;
+  sortBy?: ProjectSortKey;
+  descending?: boolean;
+  query?: string;
+}): Promise<ProjectPage> {
+  return invoke<ProjectPage>("list_projects_page", options);
+}
+
 /** 保存项目缓存列表。 */
 export async function saveProjects(projects: Project[]): Promise<void> {
   await invoke("save_projects", { projects });
 }
 
+/** 清理项目缓存：移除路径失效与 id 重复的条目（同步清理回收站），返回被移除项与回收站中的项目便于恢复。 */
+export async function pruneMissingProjects(): Promise<ProjectPruneResult> {
+  return invoke<ProjectPruneResult>("prune_missing_projects");
+}
+
+/** 将项目移入回收站（缓存条目保留以便恢复），返回最新的回收站与项目缓存。 */
+export async function recycleProject(projectId: string): Promise<RecycleBinResult> {
+  return invoke<RecycleBinResult>("recycle_project", { projectId });
+}
+
+/** 从回收站恢复项目路径，项目缓存中缺失时由后端重新构建，路径不在扫描目录范围内时一并加入目录列表。 */
+export async function restoreFromRecycleBin(path: string): Promise<RecycleBinResult> {
+  return invoke<RecycleBinResult>("restore_from_recycle_bin", { path });
+}
+
+/** 从回收站彻底移除路径并删除项目缓存，moveToTrash 为 true 时同时将目录移到系统废纸篓。 */
+export async function purgeRecycleBin(paths: string[], moveToTrash = false): Promise<RecycleBinResult> {
+  return invoke<RecycleBinResult>("purge_recycle_bin", { paths, moveToTrash });
+}
+
+/** 清空回收站，moveToTrash 为 true 时同时将目录移到系统废纸篓。 */
+export async function emptyRecycleBin(moveToTrash = false): Promise<RecycleBinResult> {
+  return invoke<RecycleBinResult>("empty_recycle_bin", { moveToTrash });
+}
+
+/** 重命名标签，后端同步更新所有项目中的引用。 */
+export async function renameStoredTag(oldName: string, newName: string): Promise<TagUpdateResult> {
+  return invoke<TagUpdateResult>("rename_tag", { oldName, newName });
+}
+
+/** 将标签 from 合并到 into，后端同步更新所有项目中的引用。 */
+export async function mergeStoredTags(from: string, into: string): Promise<TagUpdateResult> {
+  return invoke<TagUpdateResult>("merge_tags", { from, into });
+}
+
+/** 删除标签，removeFromProjects 为 true 时同时移除项目中的引用。 */
+export async function deleteStoredTag(name: string, removeFromProjects: boolean): Promise<TagUpdateResult> {
+  return invoke<TagUpdateResult>("delete_tag", { name, removeFromProjects });
+}
+
+/** 为一组项目批量添加或移除标签，返回发生变化的项目。 */
+export async function assignTag(
+  projectIds: string[],
+  tagName: string,
+  action: "add" | "remove",
+): Promise<Project[]> {
+  return invoke<Project[]>("assign_tag", { projectIds, tagName, action });
+}
+
+/** 按名称、路径与标签模糊搜索项目，tagsFilter 中的标签需全部命中。 */
+export async function searchProjects(
+  projects: Project[],
+  query: string,
+  tagsFilter?: string[],
+): Promise<ProjectSearchResult[]> {
+  return invoke<ProjectSearchResult[]>("search_projects", { projects, query, tagsFilter });
+}
+
 /** 扫描目录，返回可识别的项目路径集合。 */
 export async function discoverProjects(directories: string[]): Promise<string[]> {
   return invoke<string[]>("discover_projects", { directories });
@@ -31,3 +121,35 @@ export async function discoverProjects(directories: string[]): Promise<string[]>
 export async function buildProjects(paths: string[], existing: Project[]): Promise<Project[]> {
   return invoke<Project[]>("build_projects", { paths, existing });
 }
+
+/** 计算项目占用大小（字节），默认排除 .git 与被 .gitignore 忽略的文件。 */
+export async function computeProjectSize(
+  path: string,
+  excludeGit = true,
+  respectGitignore = true,
+): Promise<number> {
+  return invoke<number>("compute_project_size", { path, excludeGit, respectGitignore });
+}
+
+/** 选择目标目录并将数据目录备份为 zip，取消选择时返回 null。 */
+export async function backupDataDir(): Promise<DataBackupResult | null> {
+  const dest = await open({ directory: true, multiple: false, title: "选择备份保存目录" });
+  if (!dest || Array.isArray(dest)) {
+    return null;
+  }
+  return invoke<DataBackupResult>("backup_data_dir", { dest });
+}
+
+/** 选择 zip 备份并恢复数据目录，取消选择时返回 null。 */
+export async function restoreDataDir(): Promise<DataBackupResult | null> {
+  const src = await open({
+    directory: false,
+    multiple: false,
+    title: "选择备份文件",
+    filters: [{ name: "DevHaven Backup", extensions: ["zip"] }],
+  });
+  if (!src || Array.isArray(src)) {
+    return null;
+  }
+  return invoke<DataBackupResult>("restore_data_dir", { src });
+}
@@ -1,11 +1,43 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
-import type { CodexMonitorSnapshot } from "../models/codex";
+import type {
+  CodexMonitorSnapshot,
+  CodexSessionFilter,
+  CodexSessionLine,
+  CodexSessionOpenResult,
+} from "../models/codex";
 
 export const CODEX_MONITOR_SNAPSHOT_EVENT = "codex-monitor-snapshot";
 export const CODEX_MONITOR_AGENT_EVENT = "codex-monitor-agent-event";
+export const CODEX_SESSION_FOCUS_PROJECT_EVENT = "codex-session-focus-project";
+export const CODEX_SESSION_LINE_EVENT = "codex-session-line";
 
-/** 拉取 Codex 监控快照。 */
-export async function getCodexMonitorSnapshot(): Promise<CodexMonitorSnapshot> {
-  return invoke<CodexMonitorSnapshot>("get_codex_monitor_snapshot");
+/** 拉取 Codex 监控快照，可按模型/推理强度筛选会话。 */
+export async function getCodexMonitorSnapshot(filter?: CodexSessionFilter): Promise<CodexMonitorSnapshot> {
+  return invoke<CodexMonitorSnapshot>("get_codex_monitor_snapshot", { filter: filter ?? null });
+}
+
+/** 打开 Codex 会话的工作目录：匹配到项目时后端广播 `codex-session-focus-project`，否则在文件管理器中打开。 */
+export async function openCodexSessionProject(sessionId: string): Promise<CodexSessionOpenResult> {
+  return invoke<CodexSessionOpenResult>("open_codex_session_project", { sessionId });
+}
+
+/** 结束卡住的 Codex 会话进程（SIGTERM），需显式确认；返回是否找到并通知了进程。 */
+export async function killCodexSession(sessionId: string, confirm: boolean): Promise<boolean> {
+  return invoke<boolean>("kill_codex_session", { sessionId, confirm });
+}
+
+/** 开始实时跟随 Codex 会话，之后追加的记录通过 `codex-session-line` 事件推送。 */
+export async function watchCodexSession(sessionId: string): Promise<void> {
+  await invoke("watch_codex_session", { sessionId });
+}
+
+/** 停止跟随 Codex 会话，返回此前是否在监听。 */
+export async function stopWatchingCodexSession(sessionId: string): Promise<boolean> {
+  return invoke<boolean>("stop_watching_codex_session", { sessionId });
+}
+
+export async function listenCodexSessionLine(handler: (event: { payload: CodexSessionLine }) => void) {
+  return listen<CodexSessionLine>(CODEX_SESSION_LINE_EVENT, handler);
 }
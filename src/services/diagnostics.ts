@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** 日志目录与当前日志文件路径。 */
+export type LogFileInfo = {
+  logDir: string;
+  logFile: string;
+  exists: boolean;
+};
+
+export type LogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
+/** 运行时调整后端日志级别，返回生效的级别。 */
+export async function setLogLevel(level: LogLevel): Promise<LogLevel> {
+  return invoke<LogLevel>("set_log_level", { level });
+}
+
+export async function getLogPath(): Promise<LogFileInfo> {
+  return invoke<LogFileInfo>("get_log_path");
+}
+
+/** 读取当前日志文件的最后若干行（默认 200 行，后端限制最多 2000 行 / 512KB）。 */
+export async function readRecentLogs(lines?: number): Promise<string[]> {
+  return invoke<string[]>("read_recent_logs", { lines });
+}
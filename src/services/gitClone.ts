@@ -0,0 +1,65 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+import type { Project } from "../models/types";
+
+export const GIT_CLONE_PROGRESS_EVENT = "git-clone-progress";
+
+export type GitCloneStep = "pending" | "cloning" | "registering" | "ready" | "failed" | "cancelled";
+
+export type GitCloneStartRequest = {
+  url: string;
+  dest: string;
+  branch?: string | null;
+  /** 克隆完成后由后端登记到项目缓存。 */
+  registerAsProject?: boolean;
+};
+
+export type GitCloneJobStatus = {
+  jobId: string;
+  url: string;
+  dest: string;
+  branch?: string | null;
+  step: GitCloneStep;
+  message: string;
+  /** 当前阶段（如 `Receiving objects`）及百分比，来自 `git clone --progress`。 */
+  phase?: string | null;
+  percent?: number | null;
+  error?: string | null;
+  updatedAt: number;
+  isRunning: boolean;
+  cancelRequested: boolean;
+};
+
+export type GitCloneProgressPayload = {
+  jobId: string;
+  url: string;
+  dest: string;
+  branch?: string | null;
+  step: GitCloneStep;
+  message: string;
+  phase?: string | null;
+  percent?: number | null;
+  error?: string | null;
+  /** 已登记到项目缓存的项目（仅 registerAsProject 且克隆成功时存在）。 */
+  project?: Project | null;
+};
+
+/** 启动后台克隆任务，进度通过 `git-clone-progress` 事件推送。 */
+export async function gitClone(request: GitCloneStartRequest): Promise<GitCloneJobStatus> {
+  return invoke<GitCloneJobStatus>("git_clone", { request });
+}
+
+/** 取消克隆任务，本次克隆创建的目标目录会被清理。 */
+export async function gitCloneCancel(jobId: string): Promise<void> {
+  await invoke("git_clone_cancel", { jobId });
+}
+
+/** 查询克隆任务状态；已结束的任务只会返回一次，之后即从后端移除。 */
+export async function gitCloneStatus(): Promise<GitCloneJobStatus[]> {
+  return invoke<GitCloneJobStatus[]>("git_clone_status");
+}
+
+export async function listenGitCloneProgress(handler: (event: { payload: GitCloneProgressPayload }) => void) {
+  return listen<GitCloneProgressPayload>(GIT_CLONE_PROGRESS_EVENT, handler);
+}
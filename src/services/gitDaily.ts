@@ -18,3 +18,17 @@ export async function collectGitDaily(
   }
   return invoke<GitDailyResult[]>("collect_git_daily", { paths, identities });
 }
+
+/** 计算每日提交统计的签名（路径 + 各仓库 HEAD），签名未变时可跳过重新统计。 */
+export async function computeGitDailySignature(paths: string[]): Promise<string> {
+  return invoke<string>("compute_git_daily_signature", { paths });
+}
+
+/** 统计指定日期（YYYY-MM-DD，本地时区）各小时的提交数，返回长度为 24 的数组。 */
+export async function gitHourlyActivity(
+  paths: string[],
+  date: string,
+  identities: GitIdentity[] = [],
+): Promise<number[]> {
+  return invoke<number[]>("git_hourly_activity", { paths, date, identities });
+}
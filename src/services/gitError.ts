@@ -0,0 +1,32 @@
+import { invoke, type InvokeArgs } from "@tauri-apps/api/core";
+
+import type { GitError, GitErrorKind } from "../models/gitManagement";
+
+/** git 命令失败时抛出的错误，保留后端返回的错误类别。 */
+export class GitCommandError extends Error {
+  readonly kind: GitErrorKind;
+
+  constructor(error: GitError) {
+    super(error.message);
+    this.name = "GitCommandError";
+    this.kind = error.kind;
+  }
+}
+
+function isGitError(value: unknown): value is GitError {
+  return (
+    !!value &&
+    typeof value === "object" &&
+    typeof (value as GitError).kind === "string" &&
+    typeof (value as GitError).message === "string"
+  );
+}
+
+/** 调用返回 GitError 的命令，失败时转换为 GitCommandError，便于沿用 `error.message` 的展示逻辑。 */
+export async function invokeGit<T>(command: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await invoke<T>(command, args);
+  } catch (error) {
+    throw isGitError(error) ? new GitCommandError(error) : error;
+  }
+}
@@ -1,13 +1,119 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
-import type { GitDiffContents, GitRepoStatus } from "../models/gitManagement";
+import type {
+  GitAheadBehind,
+  GitBaseDiff,
+  GitBranchCheckedOutPayload,
+  GitCleanResult,
+  GitConfigScope,
+  GitConflictContents,
+  GitContributor,
+  GitDiffContents,
+  GitDiffOptions,
+  GitDiffStatEntry,
+  GitHeadInfo,
+  GitOperationState,
+  GitRemote,
+  GitRenameDetection,
+  GitRepoHealth,
+  GitRepoIdentity,
+  GitRepoStats,
+  GitRepoStatus,
+  GitResetMode,
+  GitResetResult,
+  GitTagItem,
+  GitUntrackedMode,
+} from "../models/gitManagement";
+import type { GitIdentity } from "../models/types";
+import { invokeGit } from "./gitError";
 
 export async function gitIsRepo(path: string): Promise<boolean> {
   return invoke<boolean>("git_is_repo", { path });
 }
 
-export async function gitGetStatus(path: string): Promise<GitRepoStatus> {
-  return invoke<GitRepoStatus>("git_get_status", { path });
+/** 在普通目录中初始化仓库；createReadme 为 true 时写入 README 并用 identity（缺省取 git 配置）完成首次提交。 */
+export async function gitInit(
+  path: string,
+  options?: { initialBranch?: string; createReadme?: boolean; identity?: GitIdentity | null },
+): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_init", {
+    path,
+    initialBranch: options?.initialBranch,
+    createReadme: options?.createReadme,
+    identity: options?.identity ?? null,
+  });
+}
+
+export async function gitGetStatus(
+  path: string,
+  untrackedMode?: GitUntrackedMode,
+  force = false,
+  renames?: GitRenameDetection | null,
+): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_get_status", {
+    path,
+    untrackedMode: untrackedMode ?? null,
+    force,
+    renames: renames ?? null,
+  });
+}
+
+/** 轻量查询领先/落后数，适合频繁轮询的角标，无需解析完整状态。 */
+export async function gitAheadBehind(path: string): Promise<GitAheadBehind> {
+  return invokeGit<GitAheadBehind>("git_ahead_behind", { path });
+}
+
+/** 检测 rebase/merge/cherry-pick/revert/bisect 是否进行中，以及 HEAD 是否游离。 */
+export async function gitOperationState(path: string): Promise<GitOperationState> {
+  return invokeGit<GitOperationState>("git_operation_state", { path });
+}
+
+/** 继续进行中的 rebase/merge/cherry-pick/revert（冲突需已解决），返回最新状态。 */
+export async function gitOperationContinue(path: string): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_operation_continue", { path });
+}
+
+/** 中止进行中的 rebase/merge/cherry-pick/revert，或结束 bisect，返回最新状态。 */
+export async function gitOperationAbort(path: string): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_operation_abort", { path });
+}
+
+export async function gitHeadInfo(path: string): Promise<GitHeadInfo> {
+  return invokeGit<GitHeadInfo>("git_head_info", { path });
+}
+
+export async function gitDiffAgainstBase(path: string, baseBranch: string): Promise<GitBaseDiff> {
+  return invokeGit<GitBaseDiff>("git_diff_against_base", { path, baseBranch });
+}
+
+export async function gitGetIdentity(path: string): Promise<GitRepoIdentity> {
+  return invokeGit<GitRepoIdentity>("git_get_identity", { path });
+}
+
+export async function gitSetIdentity(
+  path: string,
+  identity: GitIdentity,
+  scope: Extract<GitConfigScope, "local" | "global">,
+): Promise<void> {
+  await invokeGit("git_set_identity", { path, identity, scope });
+}
+
+export async function gitRepoHealth(path: string): Promise<GitRepoHealth> {
+  return invokeGit<GitRepoHealth>("git_repo_health", { path });
+}
+
+export async function gitRepoStats(path: string): Promise<GitRepoStats> {
+  return invokeGit<GitRepoStats>("git_repo_stats", { path });
+}
+
+/** 按提交数倒序列出贡献者；since 为 git 可识别的日期，如 `2024-01-01` 或 `3.months`。 */
+export async function gitContributors(path: string, since?: string): Promise<GitContributor[]> {
+  return invokeGit<GitContributor[]>("git_contributors", { path, since });
+}
+
+export async function gitRemoveIndexLock(path: string): Promise<boolean> {
+  return invokeGit<boolean>("git_remove_index_lock", { path });
 }
 
 export async function gitGetDiffContents(
@@ -15,31 +121,158 @@ export async function gitGetDiffContents(
   relativePath: string,
   staged: boolean,
   oldRelativePath?: string | null,
+  options?: GitDiffOptions | null,
 ): Promise<GitDiffContents> {
-  return invoke<GitDiffContents>("git_get_diff_contents", {
+  return invokeGit<GitDiffContents>("git_get_diff_contents", {
     path,
     relativePath,
     staged,
     oldRelativePath: oldRelativePath ?? null,
+    options: options ?? null,
   });
 }
 
+export async function gitGetConflictContents(
+  path: string,
+  relativePath: string,
+): Promise<GitConflictContents> {
+  return invokeGit<GitConflictContents>("git_get_conflict_contents", { path, relativePath });
+}
+
+export async function gitDiffStat(path: string, staged: boolean): Promise<GitDiffStatEntry[]> {
+  return invokeGit<GitDiffStatEntry[]>("git_diff_stat", { path, staged });
+}
+
 export async function gitStageFiles(path: string, relativePaths: string[]): Promise<void> {
-  await invoke<void>("git_stage_files", { path, relativePaths });
+  await invokeGit<void>("git_stage_files", { path, relativePaths });
+}
+
+export async function gitMarkResolved(path: string, relativePaths: string[]): Promise<void> {
+  await invokeGit<void>("git_mark_resolved", { path, relativePaths });
+}
+
+export async function gitStageHunk(path: string, relativePath: string, patch: string): Promise<void> {
+  await invokeGit<void>("git_stage_hunk", { path, relativePath, patch });
+}
+
+export async function gitUnstageHunk(path: string, relativePath: string, patch: string): Promise<void> {
+  await invokeGit<void>("git_unstage_hunk", { path, relativePath, patch });
 }
 
 export async function gitUnstageFiles(path: string, relativePaths: string[]): Promise<void> {
-  await invoke<void>("git_unstage_files", { path, relativePaths });
+  await invokeGit<void>("git_unstage_files", { path, relativePaths });
 }
 
 export async function gitDiscardFiles(path: string, relativePaths: string[]): Promise<void> {
-  await invoke<void>("git_discard_files", { path, relativePaths });
+  await invokeGit<void>("git_discard_files", { path, relativePaths });
+}
+
+/** 暂存全部改动（git add -A），返回最新状态。 */
+export async function gitStageAll(path: string): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_stage_all", { path });
+}
+
+/** 取消暂存全部改动，返回最新状态。 */
+export async function gitUnstageAll(path: string): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_unstage_all", { path });
+}
+
+/** 丢弃全部未暂存修改（不可恢复，confirm 必须为 true）；includeUntracked 时同时清理未跟踪文件。 */
+export async function gitDiscardAll(
+  path: string,
+  confirm: boolean,
+  includeUntracked = false,
+): Promise<GitRepoStatus> {
+  return invokeGit<GitRepoStatus>("git_discard_all", { path, confirm, includeUntracked });
+}
+
+/** 删除未跟踪文件；默认 dryRun 仅预览将被删除的列表，实际删除需 dryRun=false 且 confirm=true。 */
+export async function gitClean(
+  path: string,
+  options?: { includeIgnored?: boolean; dryRun?: boolean; paths?: string[]; confirm?: boolean },
+): Promise<GitCleanResult> {
+  return invokeGit<GitCleanResult>("git_clean", {
+    path,
+    includeIgnored: options?.includeIgnored,
+    dryRun: options?.dryRun,
+    paths: options?.paths,
+    confirm: options?.confirm,
+  });
 }
 
-export async function gitCommit(path: string, message: string): Promise<void> {
-  await invoke<void>("git_commit", { path, message });
+/** 恢复文件到 HEAD：staged 恢复暂存区，worktree 恢复工作区；与只丢弃未暂存改动的 gitDiscardFiles 不同。 */
+export async function gitRestoreFile(
+  path: string,
+  relativePath: string,
+  staged: boolean,
+  worktree: boolean,
+): Promise<void> {
+  await invokeGit<void>("git_restore_file", { path, relativePath, staged, worktree });
+}
+
+export async function gitCheckoutFile(
+  path: string,
+  reference: string,
+  relativePath: string,
+  force = false,
+): Promise<void> {
+  await invokeGit<void>("git_checkout_file", { path, reference, relativePath, force });
+}
+
+export async function gitCommit(path: string, message: string, skipHooks = false): Promise<void> {
+  await invoke<void>("git_commit", { path, message, skipHooks });
+}
+
+/** 手动执行仓库 hook（如 pre-commit/pre-push），返回 hook 输出。 */
+export async function gitRunHook(path: string, hookName: string): Promise<string> {
+  return invokeGit<string>("run_git_hook", { path, hookName });
 }
 
 export async function gitCheckoutBranch(path: string, branch: string): Promise<void> {
-  await invoke<void>("git_checkout_branch", { path, branch });
+  await invokeGit<void>("git_checkout_branch", { path, branch });
+}
+
+/** 监听后端在切换分支成功后广播的 `branch-checked-out` 事件。 */
+export async function listenBranchCheckedOut(
+  handler: (event: { payload: GitBranchCheckedOutPayload }) => void,
+) {
+  return listen<GitBranchCheckedOutPayload>("branch-checked-out", handler);
+}
+
+export async function gitListTags(path: string): Promise<GitTagItem[]> {
+  return invokeGit<GitTagItem[]>("git_list_tags", { path });
+}
+
+export async function gitCreateTag(
+  path: string,
+  name: string,
+  message: string | null,
+  annotated: boolean,
+): Promise<void> {
+  await invokeGit<void>("git_create_tag", { path, name, message, annotated });
+}
+
+export async function gitDeleteTag(path: string, name: string): Promise<void> {
+  await invokeGit<void>("git_delete_tag", { path, name });
+}
+
+export async function gitListRemotes(path: string): Promise<GitRemote[]> {
+  return invokeGit<GitRemote[]>("git_list_remotes", { path });
+}
+
+export async function gitAddRemote(path: string, name: string, url: string): Promise<void> {
+  await invokeGit<void>("git_add_remote", { path, name, url });
+}
+
+export async function gitSetRemoteUrl(path: string, name: string, url: string): Promise<void> {
+  await invokeGit<void>("git_set_remote_url", { path, name, url });
+}
+
+export async function gitReset(
+  path: string,
+  reference: string,
+  mode: GitResetMode,
+  confirmHard = false,
+): Promise<GitResetResult> {
+  return invokeGit<GitResetResult>("git_reset", { path, reference, mode, confirmHard });
 }
@@ -1,4 +1,5 @@
-import { invoke } from "@tauri-apps/api/core";
+import type { GitBaseDiff } from "../models/gitManagement";
+import { invokeGit } from "./gitError";
 
 export type GitWorktreeAddPayload = {
   path: string;
@@ -17,6 +18,37 @@ export type GitWorktreeListItem = {
   branch: string;
 };
 
+/** worktree 分支相对基线分支的领先/落后提交数。 */
+export type GitWorktreeDivergence = {
+  worktreePath: string;
+  branch: string;
+  baseBranch: string;
+  ahead: number;
+  behind: number;
+};
+
+/** worktree 评审结果：相对基线分支的变更文件；编辑器打开失败不影响变更列表。 */
+export type GitWorktreeReview = {
+  worktreePath: string;
+  branch: string;
+  baseBranch: string;
+  diff: GitBaseDiff;
+  editorOpened: boolean;
+  editorError?: string | null;
+};
+
+export type GitWorktreePruneEntry = {
+  /** `.git/worktrees` 下的记录名，如 `worktrees/feature`。 */
+  name: string;
+  reason: string;
+};
+
+/** `git worktree prune` 结果；performed 为 false 时是预览。 */
+export type GitWorktreePruneResult = {
+  performed: boolean;
+  entries: GitWorktreePruneEntry[];
+};
+
 export type GitWorktreeRemovePayload = {
   path: string;
   worktreePath: string;
@@ -39,23 +71,45 @@ export async function gitWorktreeAdd(payload: GitWorktreeAddPayload): Promise<Gi
   if (targetPath) {
     params.targetPath = targetPath;
   }
-  return invoke<GitWorktreeAddResult>("git_worktree_add", params);
+  return invokeGit<GitWorktreeAddResult>("git_worktree_add", params);
 }
 
 export async function gitWorktreeList(path: string): Promise<GitWorktreeListItem[]> {
-  return invoke<GitWorktreeListItem[]>("git_worktree_list", { path });
+  return invokeGit<GitWorktreeListItem[]>("git_worktree_list", { path });
+}
+
+export async function gitWorktreeDivergence(
+  path: string,
+  worktreePath: string,
+  baseBranch: string,
+): Promise<GitWorktreeDivergence> {
+  return invokeGit<GitWorktreeDivergence>("git_worktree_divergence", { path, worktreePath, baseBranch });
 }
 
 export async function gitWorktreeRemove(payload: GitWorktreeRemovePayload): Promise<void> {
-  await invoke("git_worktree_remove", {
+  await invokeGit("git_worktree_remove", {
     path: payload.path,
     worktreePath: payload.worktreePath,
     force: payload.force ?? false,
   });
 }
 
+/** 评审 worktree：返回相对基线分支的变更文件，并用设置中的编辑器打开 worktree 目录。 */
+export async function openWorktreeReview(
+  path: string,
+  worktreePath: string,
+  baseBranch: string,
+): Promise<GitWorktreeReview> {
+  return invokeGit<GitWorktreeReview>("open_worktree_review", { path, worktreePath, baseBranch });
+}
+
+/** 清理目录已被手动删除的 worktree 残留记录；dryRun 默认为 true（仅预览），传 false 才会真正清理。 */
+export async function gitWorktreePrune(path: string, dryRun = true): Promise<GitWorktreePruneResult> {
+  return invokeGit<GitWorktreePruneResult>("git_worktree_prune", { path, dryRun });
+}
+
 export async function gitDeleteBranch(payload: GitDeleteBranchPayload): Promise<void> {
-  await invoke("git_delete_branch", {
+  await invokeGit("git_delete_branch", {
     path: payload.path,
     branch: payload.branch,
     force: payload.force ?? false,
@@ -5,14 +5,26 @@ import { MONITOR_COLLAPSED_SIZE } from "../constants/monitorWindow";
 const MONITOR_WINDOW_LABEL = "cli-monitor";
 const MONITOR_WINDOW_URL = "index.html?view=monitor";
 
-async function enableMonitorWindowFullscreenAuxiliary(): Promise<void> {
+/** 全屏辅助模式设置结果（`supported` 为 false 表示当前环境不可用）。 */
+export type FullscreenAuxiliaryResult = {
+  supported: boolean;
+  enabled: boolean;
+  message?: string | null;
+};
+
+async function enableMonitorWindowFullscreenAuxiliary(): Promise<FullscreenAuxiliaryResult | null> {
   try {
-    await invoke("set_window_fullscreen_auxiliary", {
+    const result = await invoke<FullscreenAuxiliaryResult>("set_window_fullscreen_auxiliary", {
       windowLabel: MONITOR_WINDOW_LABEL,
       enabled: true,
     });
+    if (!result.supported) {
+      console.warn("悬浮窗全屏辅助显示不可用。", result.message);
+    }
+    return result;
   } catch (error) {
     console.error("设置悬浮窗全屏辅助显示失败。", error);
+    return null;
   }
 }
 
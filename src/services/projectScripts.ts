@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export const PROJECT_SCRIPT_OUTPUT_EVENT = "project-script-output";
+export const PROJECT_SCRIPT_EXIT_EVENT = "project-script-exit";
+
+export type ProjectScriptAction = "start" | "stop";
+
+export type ProjectScriptRunResult = {
+  projectId: string;
+  scriptId: string;
+  pid: number | null;
+  running: boolean;
+};
+
+/** 已登记脚本进程的状态；startedAt 为 Unix 毫秒时间戳。 */
+export type RunningScriptStatus = {
+  projectId: string;
+  scriptId: string;
+  pid: number | null;
+  startedAt: number;
+  alive: boolean;
+};
+
+export type ProjectScriptOutputPayload = {
+  projectId: string;
+  scriptId: string;
+  data: string;
+};
+
+export type ProjectScriptExitPayload = {
+  projectId: string;
+  scriptId: string;
+  code: number | null;
+};
+
+/** 启动或停止项目脚本；停止时若配置了 stop 命令会先执行它，再结束仍在运行的进程。 */
+export async function runProjectScript(
+  projectPath: string,
+  scriptId: string,
+  action: ProjectScriptAction,
+): Promise<ProjectScriptRunResult> {
+  return invoke<ProjectScriptRunResult>("run_project_script", { projectPath, scriptId, action });
+}
+
+/** 列出正在运行的项目脚本，已退出的进程会被回收并以 alive=false 返回一次。 */
+export async function listRunningScripts(): Promise<RunningScriptStatus[]> {
+  return invoke<RunningScriptStatus[]>("list_running_scripts");
+}
+
+export async function listenProjectScriptOutput(
+  handler: (event: { payload: ProjectScriptOutputPayload }) => void,
+) {
+  return listen<ProjectScriptOutputPayload>(PROJECT_SCRIPT_OUTPUT_EVENT, handler);
+}
+
+export async function listenProjectScriptExit(handler: (event: { payload: ProjectScriptExitPayload }) => void) {
+  return listen<ProjectScriptExitPayload>(PROJECT_SCRIPT_EXIT_EVENT, handler);
+}
@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export const PROJECT_CHANGED_EVENT = "project-changed";
+
+export type ProjectChangedPayload = {
+  path: string;
+};
+
+/** 开始监听项目目录，文件变更时后端广播 `project-changed` 事件。 */
+export async function watchProject(path: string): Promise<void> {
+  await invoke<void>("watch_project", { path });
+}
+
+/** 停止监听项目目录，返回是否存在对应监听。 */
+export async function unwatchProject(path: string): Promise<boolean> {
+  return invoke<boolean>("unwatch_project", { path });
+}
+
+export async function listenProjectChanged(handler: (event: { payload: ProjectChangedPayload }) => void) {
+  return listen<ProjectChangedPayload>(PROJECT_CHANGED_EVENT, handler);
+}
@@ -1,8 +1,30 @@
 import { invoke } from "@tauri-apps/api/core";
 
-/** 在系统文件管理器中定位路径。 */
-export async function openInFinder(path: string) {
-  await invoke("open_in_finder", { path });
+import type {
+  AppWindowInfo,
+  DetectedEditor,
+  OpenToolSettings,
+  OpenToolValidation,
+} from "../models/types";
+
+/** 在系统文件管理器中打开路径；`reveal` 为 true（默认）时选中目标，false 时直接打开目录。 */
+export async function openInFinder(path: string, reveal = true) {
+  await invoke("open_in_finder", { path, reveal });
+}
+
+/** 使用设置中的外部终端打开路径（未配置时使用系统默认终端）。 */
+export async function openInTerminal(path: string) {
+  await invoke("open_in_terminal", { path });
+}
+
+/** 探测本机已安装的常见编辑器，供设置页下拉选择。 */
+export async function detectEditors(): Promise<DetectedEditor[]> {
+  return invoke<DetectedEditor[]>("detect_editors");
+}
+
+/** 校验外部编辑器/终端工具配置（路径存在且可执行），用于设置保存前提示。 */
+export async function validateOpenTool(settings: OpenToolSettings): Promise<OpenToolValidation> {
+  return invoke<OpenToolValidation>("validate_open_tool", { settings });
 }
 
 /** 将内容写入系统剪贴板。 */
@@ -18,6 +40,21 @@ export async function copyToClipboard(content: string) {
   await invoke("copy_to_clipboard", { content });
 }
 
+/** 读取系统剪贴板文本，非文本内容时返回 null。 */
+export async function readFromClipboard(): Promise<string | null> {
+  return invoke<string | null>("read_from_clipboard");
+}
+
+/** 列出应用当前的所有窗口。 */
+export async function listWindows(): Promise<AppWindowInfo[]> {
+  return invoke<AppWindowInfo[]>("list_windows");
+}
+
+/** 将指定窗口前置并聚焦（例如从主界面跳转到悬浮监控窗）。 */
+export async function focusWindow(label: string) {
+  await invoke("focus_window", { label });
+}
+
 /** 发送系统通知。 */
 export async function sendSystemNotification(title: string, body?: string) {
   if (typeof window === "undefined" || typeof Notification === "undefined") {
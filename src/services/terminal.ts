@@ -7,6 +7,9 @@ export type TerminalCreateRequest = {
   rows: number;
   windowLabel: string;
   sessionId?: string;
+  projectId?: string | null;
+  shell?: string | null;
+  env?: Record<string, string> | null;
 };
 
 export type TerminalCreateResult = {
@@ -15,6 +18,15 @@ export type TerminalCreateResult = {
   shell: string;
 };
 
+/** 终端会话归属的项目信息。 */
+export type TerminalSessionMetadata = {
+  sessionId: string;
+  ptyId: string;
+  projectPath: string;
+  projectId?: string | null;
+  shellPid?: number | null;
+};
+
 export type TerminalOutputPayload = {
   sessionId: string;
   data: string;
@@ -32,12 +44,36 @@ export type TerminalCodexPaneOverlay = {
   updatedAt: number;
 };
 
+export type TerminalPaneCommandStatus = {
+  sessionId: string;
+  /** shell 的前台子进程名，空闲时为 null。 */
+  currentCommand: string | null;
+  /** 最近一条命令的退出码，需 shell 输出 OSC 133;D 标记才可得。 */
+  lastExitCode: number | null;
+  lastExitAt: number | null;
+  /** 是否有全屏程序（vim/less/htop 等）占用备用屏幕，此时应禁用清屏等操作。 */
+  inAltScreen: boolean;
+};
+
 export async function createTerminalSession(request: TerminalCreateRequest): Promise<TerminalCreateResult> {
   return invoke<TerminalCreateResult>("terminal_create_session", request);
 }
 
-export async function writeTerminal(ptyId: string, data: string): Promise<void> {
-  await invoke("terminal_write", { ptyId, data });
+export async function getTerminalSessionMetadata(
+  windowLabel: string,
+  sessionId: string,
+): Promise<TerminalSessionMetadata | null> {
+  return invoke<TerminalSessionMetadata | null>("terminal_get_session_metadata", { windowLabel, sessionId });
+}
+
+/** bracketed 为 true 时，若前台程序开启了 bracketed paste，后端会用粘贴标记包裹数据，避免多行内容被逐行执行。 */
+export async function writeTerminal(ptyId: string, data: string, bracketed?: boolean): Promise<void> {
+  await invoke("terminal_write", { ptyId, data, bracketed });
+}
+
+/** 在终端中执行一行命令（后端负责追加回车）。 */
+export async function runTerminalCommand(ptyId: string, command: string): Promise<void> {
+  await invoke("terminal_run_command", { ptyId, command });
 }
 
 export async function resizeTerminal(ptyId: string, cols: number, rows: number): Promise<void> {
@@ -58,6 +94,16 @@ export async function getTerminalCodexPaneOverlay(
   });
 }
 
+export async function getTerminalPaneCommandStatus(
+  windowLabel: string,
+  sessionIds: string[],
+): Promise<TerminalPaneCommandStatus[]> {
+  return invoke<TerminalPaneCommandStatus[]>("get_terminal_pane_command_status", {
+    windowLabel,
+    sessionIds,
+  });
+}
+
 export async function listenTerminalOutput(
   handler: (event: { payload: TerminalOutputPayload }) => void,
 ) {
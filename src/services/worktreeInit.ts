@@ -1,6 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 
+import type { ProjectWorktree } from "../models/types";
+
 export const WORKTREE_INIT_CLIENT_LOCK_EVENT = "worktree-init-client-lock";
 
 export type WorktreeInitStep =
@@ -21,6 +23,14 @@ export type WorktreeInitStartRequest = {
   createBranch: boolean;
   baseBranch?: string;
   targetPath?: string;
+  /** 创建成功后由后端写入项目缓存的 worktrees 列表。 */
+  registerAsProject?: boolean;
+  /** 是否继承主仓库配置，关闭时不复制继承文件（默认 true）。 */
+  inheritConfig?: boolean;
+  /** 环境准备阶段执行的创建后脚本（如 `pnpm install`），失败仅作为告警。 */
+  postCreateScript?: string | null;
+  /** 仅校验并返回创建计划，不实际创建。 */
+  dryRun?: boolean;
 };
 
 export type WorktreeInitStartResult = {
@@ -32,6 +42,11 @@ export type WorktreeInitStartResult = {
   baseBranch?: string;
   step: WorktreeInitStep;
   message: string;
+  dryRun?: boolean;
+  startPoint?: string | null;
+  branchExists?: boolean;
+  /** 分支已在其他 worktree 检出时直接复用（此时 jobId 为空）。 */
+  reused?: boolean;
 };
 
 export type WorktreeInitCreateBlockingResult = {
@@ -43,6 +58,7 @@ export type WorktreeInitCreateBlockingResult = {
   baseBranch?: string;
   message: string;
   warning?: string | null;
+  reused?: boolean;
 };
 
 export type WorktreeInitProgressPayload = {
@@ -55,6 +71,8 @@ export type WorktreeInitProgressPayload = {
   step: WorktreeInitStep;
   message: string;
   error?: string | null;
+  /** 后端已登记到项目缓存的 worktree 条目。 */
+  worktree?: ProjectWorktree | null;
 };
 
 export type WorktreeInitClientLockPayload = {
@@ -125,9 +143,7 @@ export async function worktreeInitCreate(
     await flushUiBeforeBlockingCall();
   }
 
-  try {
-    return await invoke<WorktreeInitStartResult>("worktree_init_create", { request });
-  } catch (error) {
+  const releaseClientLock = () => {
     if (typeof window !== "undefined") {
       window.dispatchEvent(
         new CustomEvent<WorktreeInitClientLockPayload>(WORKTREE_INIT_CLIENT_LOCK_EVENT, {
@@ -140,6 +156,17 @@ export async function worktreeInitCreate(
         }),
       );
     }
+  };
+
+  try {
+    const result = await invoke<WorktreeInitStartResult>("worktree_init_create", { request });
+    // 复用/预览不会启动后台任务，也不会有进度事件来解除锁定。
+    if (result.reused || result.dryRun) {
+      releaseClientLock();
+    }
+    return result;
+  } catch (error) {
+    releaseClientLock();
     throw error;
   }
 }
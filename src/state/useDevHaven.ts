@@ -1,16 +1,33 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 
-import type { AppStateFile, Project, ProjectScript, ProjectWorktree, TagData } from "../models/types";
+import type {
+  AppStateFile,
+  Project,
+  ProjectScript,
+  ProjectWorktree,
+  RecycleBinResult,
+  TagData,
+  TagUpdateResult,
+} from "../models/types";
 import { jsDateToSwiftDate } from "../models/types";
 import {
+  assignTag,
   buildProjects,
+  deleteStoredTag,
   discoverProjects,
   loadAppState,
   loadProjects,
+  mergeStoredTags,
+  purgeRecycleBin,
+  recycleProject,
+  renameStoredTag,
+  restoreFromRecycleBin,
   saveAppState,
   saveProjects,
 } from "../services/appStorage";
-import { collectGitDaily } from "../services/gitDaily";
+import { collectGitDaily, computeGitDailySignature } from "../services/gitDaily";
+import { loadHeatmapCache, saveHeatmapCache } from "../services/heatmap";
+import { buildGitIdentitySignature } from "../utils/gitIdentity";
 import { pickColorForTag } from "../utils/tagColors";
 
 const emptyState: AppStateFile = {
@@ -32,6 +49,14 @@ const emptyState: AppStateFile = {
     showMonitorWindow: false,
     gitIdentities: [],
     projectListViewMode: "card",
+    commitMessageTemplate: null,
+    commitSubjectMaxLen: 0,
+    worktreeInheritFiles: [],
+    terminalMaxOutputBytesPerSec: 0,
+    gitPath: null,
+    stopScriptsOnExit: true,
+    codexSessionsDir: null,
+    codexMonitorBootstrapEvents: false,
   },
 };
 
@@ -65,16 +90,19 @@ export type DevHavenActions = {
   syncProjectWorktrees: (projectId: string, worktrees: Array<{ path: string; branch: string }>) => Promise<void>;
   addDirectory: (path: string) => Promise<void>;
   removeDirectory: (path: string) => Promise<void>;
-  moveProjectToRecycleBin: (path: string) => Promise<void>;
+  moveProjectToRecycleBin: (projectId: string) => Promise<void>;
   restoreProjectFromRecycleBin: (path: string) => Promise<void>;
+  purgeProjectsFromRecycleBin: (paths: string[], moveToTrash?: boolean) => Promise<RecycleBinResult>;
   updateSettings: (settings: AppStateFile["settings"]) => Promise<void>;
   updateTags: (tags: TagData[]) => Promise<void>;
   addTag: (name: string, colorHex?: string) => Promise<void>;
   renameTag: (from: string, to: string) => Promise<void>;
+  mergeTags: (from: string, into: string) => Promise<void>;
   removeTag: (name: string) => Promise<void>;
   toggleTagHidden: (name: string) => Promise<void>;
   setTagColor: (name: string, colorHex: string) => Promise<void>;
   addTagToProject: (projectId: string, tag: string) => Promise<void>;
+  assignTagToProjects: (projectIds: string[], tag: string, action?: "add" | "remove") => Promise<void>;
   removeTagFromProject: (projectId: string, tag: string) => Promise<void>;
 };
 
@@ -112,12 +140,6 @@ export function useDevHaven(): DevHavenStore {
     await saveProjects(nextProjects);
   }, []);
 
-  const commitAppStateAndProjects = useCallback(async (nextState: AppStateFile, nextProjects: Project[]) => {
-    setAppState(nextState);
-    setProjects(nextProjects);
-    await Promise.all([saveAppState(nextState), saveProjects(nextProjects)]);
-  }, []);
-
   /** 将项目中的标签同步到全局标签配置，并持久化。 */
   const syncTagsFromProjects = useCallback(
     async (state: AppStateFile, nextProjects: Project[]) => {
@@ -231,7 +253,18 @@ export function useDevHaven(): DevHavenStore {
       if (targetPaths.length === 0) {
         return;
       }
+      const isFullRefresh = !paths || paths.length === 0;
       try {
+        // 全量刷新时比较仓库 HEAD 签名，路径、HEAD 与身份均未变化则跳过重新统计。
+        let headSignature: string | null = null;
+        if (isFullRefresh) {
+          const repoSignature = await computeGitDailySignature(targetPaths);
+          headSignature = `${repoSignature}:${buildGitIdentitySignature(appState.settings.gitIdentities)}`;
+          const cached = await loadHeatmapCache().catch(() => null);
+          if (cached?.gitHeadSignature === headSignature) {
+            return;
+          }
+        }
         const results = await collectGitDaily(targetPaths, appState.settings.gitIdentities);
         if (results.length === 0) {
           return;
@@ -245,6 +278,10 @@ export function useDevHaven(): DevHavenStore {
           return { ...project, git_daily: match.gitDaily ?? null };
         });
         await commitProjects(nextProjects);
+        if (headSignature) {
+          const cached = await loadHeatmapCache();
+          await saveHeatmapCache({ ...cached, gitHeadSignature: headSignature });
+        }
       } catch (err) {
         handleError(err);
       }
@@ -455,30 +492,54 @@ export function useDevHaven(): DevHavenStore {
     [appState, commitAppState],
   );
 
-  /** 将项目路径移入回收站并持久化。 */
+  // 后端已落盘标签与项目引用，这里只同步内存状态。
+  const applyTagUpdateResult = useCallback((result: TagUpdateResult) => {
+    setAppState((prev) => ({ ...prev, tags: result.tags }));
+    setProjects(result.projects);
+  }, []);
+
+  // 后端已落盘回收站操作结果，这里只同步内存状态。
+  const applyRecycleBinResult = useCallback((result: RecycleBinResult) => {
+    setAppState((prev) => ({
+      ...prev,
+      recycleBin: result.recycleBin,
+      directories: result.directories,
+    }));
+    setProjects(result.projects);
+  }, []);
+
+  /** 将项目移入回收站，由后端一次性落盘回收站与项目缓存。 */
   const moveProjectToRecycleBin = useCallback(
-    async (path: string) => {
-      if (!path) {
+    async (projectId: string) => {
+      if (!projectId) {
         return;
       }
-      const nextRecycleBin = Array.from(new Set([...appState.recycleBin, path]));
-      const nextState = { ...appState, recycleBin: nextRecycleBin };
-      await commitAppState(nextState);
+      const result = await recycleProject(projectId);
+      applyRecycleBinResult(result);
     },
-    [appState, commitAppState],
+    [applyRecycleBinResult],
   );
 
-  /** 从回收站恢复项目路径并持久化。 */
+  /** 从回收站恢复项目路径；项目缓存中缺失时由后端重新构建条目。 */
   const restoreProjectFromRecycleBin = useCallback(
     async (path: string) => {
       if (!path) {
         return;
       }
-      const nextRecycleBin = appState.recycleBin.filter((item) => item !== path);
-      const nextState = { ...appState, recycleBin: nextRecycleBin };
-      await commitAppState(nextState);
+      const result = await restoreFromRecycleBin(path);
+      applyRecycleBinResult(result);
     },
-    [appState, commitAppState],
+    [applyRecycleBinResult],
+  );
+
+  /** 从回收站彻底移除项目路径并删除项目缓存，可选将目录移到系统废纸篓。 */
+  const purgeProjectsFromRecycleBin = useCallback(
+    async (paths: string[], moveToTrash = false) => {
+      const result = await purgeRecycleBin(paths, moveToTrash);
+      applyRecycleBinResult(result);
+      return result;
+    },
+    [applyRecycleBinResult],
   );
 
   /** 批量更新标签配置并持久化。 */
@@ -535,34 +596,28 @@ export function useDevHaven(): DevHavenStore {
       if (appState.tags.some((tag) => tag.name === normalized)) {
         return;
       }
+      applyTagUpdateResult(await renameStoredTag(from, normalized));
+    },
+    [appState.tags, applyTagUpdateResult],
+  );
 
-      const nextTags = appState.tags.map((tag) =>
-        tag.name === from ? { ...tag, name: normalized } : tag,
-      );
-      const nextProjects = projects.map((project) =>
-        project.tags.includes(from)
-          ? { ...project, tags: project.tags.map((tag) => (tag === from ? normalized : tag)) }
-          : project,
-      );
-
-      const nextState = { ...appState, tags: nextTags };
-      await commitAppStateAndProjects(nextState, nextProjects);
+  /** 将标签合并到另一个标签并同步项目引用。 */
+  const mergeTags = useCallback(
+    async (from: string, into: string) => {
+      if (!from || !into || from === into) {
+        return;
+      }
+      applyTagUpdateResult(await mergeStoredTags(from, into));
     },
-    [appState, commitAppStateAndProjects, projects],
+    [applyTagUpdateResult],
   );
 
   /** 删除标签并同步移除项目引用。 */
   const removeTag = useCallback(
     async (name: string) => {
-      const nextTags = appState.tags.filter((tag) => tag.name !== name);
-      const nextProjects = projects.map((project) => ({
-        ...project,
-        tags: project.tags.filter((tag) => tag !== name),
-      }));
-      const nextState = { ...appState, tags: nextTags };
-      await commitAppStateAndProjects(nextState, nextProjects);
+      applyTagUpdateResult(await deleteStoredTag(name, true));
     },
-    [appState, commitAppStateAndProjects, projects],
+    [applyTagUpdateResult],
   );
 
   /** 切换标签的隐藏状态。 */
@@ -604,6 +659,22 @@ export function useDevHaven(): DevHavenStore {
   );
 
   /** 从指定项目移除标签。 */
+  /** 为多个项目批量添加或移除标签，由后端一次性落盘。 */
+  const assignTagToProjects = useCallback(
+    async (projectIds: string[], tag: string, action: "add" | "remove" = "add") => {
+      if (projectIds.length === 0 || !tag) {
+        return;
+      }
+      const updated = await assignTag(projectIds, tag, action);
+      if (updated.length === 0) {
+        return;
+      }
+      const updatedById = new Map(updated.map((project) => [project.id, project]));
+      setProjects((prev) => prev.map((project) => updatedById.get(project.id) ?? project));
+    },
+    [],
+  );
+
   const removeTagFromProject = useCallback(
     async (projectId: string, tag: string) => {
       const nextProjects = projects.map((project) =>
@@ -638,14 +709,17 @@ export function useDevHaven(): DevHavenStore {
     removeDirectory,
     moveProjectToRecycleBin,
     restoreProjectFromRecycleBin,
+    purgeProjectsFromRecycleBin,
     updateSettings,
     updateTags,
     addTag,
     renameTag,
+    mergeTags,
     removeTag,
     toggleTagHidden,
     setTagColor,
     addTagToProject,
+    assignTagToProjects,
     removeTagFromProject,
   };
 }
@@ -9,6 +9,7 @@ import { buildGitIdentitySignature } from "../utils/gitIdentity";
 import { formatDateKey, parseGitDaily } from "../utils/gitDaily";
 
 const REFRESH_INTERVAL_MS = 30 * 60 * 1000;
+const HEATMAP_CACHE_VERSION = 2;
 
 export type HeatmapStore = {
   cache: HeatmapCacheFile;
@@ -34,7 +35,10 @@ export function useHeatmapData(projects: Project[], gitIdentities: GitIdentity[]
         const stored = await loadHeatmapCache().catch(() => EMPTY_HEATMAP_CACHE);
         const shouldRebuild = shouldRefreshCache(stored, projects, gitIdentities, force);
         if (shouldRebuild) {
-          const rebuilt = buildHeatmapCache(projects, gitIdentities);
+          const rebuilt = {
+            ...buildHeatmapCache(projects, gitIdentities),
+            gitHeadSignature: stored.gitHeadSignature ?? "",
+          };
           setCache(rebuilt);
           await saveHeatmapCache(rebuilt);
         } else {
@@ -91,7 +95,7 @@ function shouldRefreshCache(
   if (force) {
     return true;
   }
-  if (!cache.lastUpdated) {
+  if (!cache.lastUpdated || cache.version !== HEATMAP_CACHE_VERSION) {
     return true;
   }
   const lastUpdated = Date.parse(cache.lastUpdated);
@@ -122,10 +126,13 @@ function buildHeatmapCache(projects: Project[], gitIdentities: GitIdentity[]): H
           dateString,
           commitCount: 0,
           projectIds: [],
+          projectCounts: {},
         };
       }
       const entry = dailyActivity[dateString];
       entry.commitCount += count;
+      const projectCounts = (entry.projectCounts ??= {});
+      projectCounts[project.id] = (projectCounts[project.id] ?? 0) + count;
       if (!entry.projectIds.includes(project.id)) {
         entry.projectIds.push(project.id);
       }
@@ -133,7 +140,7 @@ function buildHeatmapCache(projects: Project[], gitIdentities: GitIdentity[]): H
   }
 
   return {
-    version: 1,
+    version: HEATMAP_CACHE_VERSION,
     lastUpdated: new Date().toISOString(),
     dailyActivity,
     projectCount: projects.length,
@@ -161,6 +168,7 @@ function buildHeatmapData(cache: HeatmapCacheFile, days: number): HeatmapData[]
       date,
       commitCount,
       projectIds: entry?.projectIds ?? [],
+      projectCounts: entry?.projectCounts ?? {},
       intensity: 0,
     });
   }